@@ -82,6 +82,12 @@ pub trait Actor: Debug + Send + Sync + 'static {
   async fn get_supervisor_strategy(&mut self) -> Option<SupervisorStrategyHandle> {
     None
   }
+
+  // Receivers currently stacked by this actor, top first. Actors embedding an `ActorBehavior`
+  // should delegate to it so that the stack shows up in diagnostics.
+  async fn get_behavior_stack(&self) -> Vec<String> {
+    Vec::new()
+  }
 }
 
 #[allow(clippy::type_complexity)]
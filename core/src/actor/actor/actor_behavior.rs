@@ -58,6 +58,11 @@ impl Actor for ActorBehavior {
     }
     Ok(())
   }
+
+  async fn get_behavior_stack(&self) -> Vec<String> {
+    let mg = self.stack.read().await;
+    mg.iter().map(|receiver| receiver.get_type_name().to_string()).collect()
+  }
 }
 
 static_assertions::assert_impl_all!(ActorBehavior: Send, Sync);
@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+
+use crate::actor::actor::ExtendedPid;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActorDiagnostics {
+  pub pid: Option<ExtendedPid>,
+  pub actor_type: String,
+  pub user_messages_count: i32,
+  pub system_messages_count: i32,
+  pub children: Vec<ExtendedPid>,
+  pub watchers: Vec<ExtendedPid>,
+  pub behavior_stack: Vec<String>,
+}
+
+impl Display for ActorDiagnostics {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let join = |pids: &[ExtendedPid]| pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", ");
+    if let Some(pid) = &self.pid {
+      writeln!(f, "pid: {}", pid)?;
+    }
+    writeln!(f, "actor_type: {}", self.actor_type)?;
+    writeln!(
+      f,
+      "mailbox: user_messages = {}, system_messages = {}",
+      self.user_messages_count, self.system_messages_count
+    )?;
+    writeln!(f, "children: [{}]", join(&self.children))?;
+    writeln!(f, "watchers: [{}]", join(&self.watchers))?;
+    write!(f, "behavior_stack: [{}]", self.behavior_stack.join(", "))
+  }
+}
@@ -9,7 +9,7 @@ use crate::actor::context::ContextHandle;
 use crate::actor::supervisor::SupervisorStrategyHandle;
 
 #[derive(Debug, Clone)]
-pub struct ActorHandle(Arc<RwLock<dyn Actor>>);
+pub struct ActorHandle(Arc<RwLock<dyn Actor>>, Arc<String>);
 
 impl PartialEq for ActorHandle {
   fn eq(&self, other: &Self) -> bool {
@@ -27,16 +27,24 @@ impl std::hash::Hash for ActorHandle {
 
 impl ActorHandle {
   pub fn new_arc(actor: Arc<RwLock<dyn Actor>>) -> Self {
-    ActorHandle(actor)
+    let type_name = actor
+      .try_read()
+      .map_or_else(|_| "unknown".to_string(), |actor| actor.get_type_name());
+    ActorHandle(actor, Arc::new(type_name))
   }
 
   pub fn new(actor: impl Actor + 'static) -> Self {
-    ActorHandle(Arc::new(RwLock::new(actor)))
+    let type_name = actor.get_type_name();
+    ActorHandle(Arc::new(RwLock::new(actor)), Arc::new(type_name))
   }
 }
 
 #[async_trait]
 impl Actor for ActorHandle {
+  fn get_type_name(&self) -> String {
+    self.1.to_string()
+  }
+
   async fn handle(&mut self, c: ContextHandle) -> Result<(), ActorError> {
     let mut mg = self.0.write().await;
     mg.handle(c).await
@@ -51,4 +59,12 @@ impl Actor for ActorHandle {
     let mut mg = self.0.write().await;
     mg.get_supervisor_strategy().await
   }
+
+  async fn get_behavior_stack(&self) -> Vec<String> {
+    // The actor is locked while it processes a message; don't wait for it.
+    match self.0.try_read() {
+      Ok(mg) => mg.get_behavior_stack().await,
+      Err(_) => Vec::new(),
+    }
+  }
 }
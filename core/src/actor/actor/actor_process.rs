@@ -5,6 +5,8 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::actor::actor::pid::ExtendedPid;
+use crate::actor::actor::ActorDiagnostics;
+use crate::actor::context::ActorContext;
 use crate::actor::dispatch::Mailbox;
 use crate::actor::dispatch::MailboxHandle;
 use crate::actor::message::MessageHandle;
@@ -15,6 +17,7 @@ use crate::actor::process::Process;
 pub struct ActorProcess {
   mailbox: MailboxHandle,
   dead: Arc<AtomicBool>,
+  context: Option<ActorContext>,
 }
 
 impl PartialEq for ActorProcess {
@@ -37,12 +40,28 @@ impl ActorProcess {
     Self {
       mailbox,
       dead: Arc::new(AtomicBool::new(false)),
+      context: None,
     }
   }
 
+  pub fn with_context(mut self, context: ActorContext) -> Self {
+    self.context = Some(context);
+    self
+  }
+
   pub fn is_dead(&self) -> bool {
     self.dead.load(Ordering::SeqCst)
   }
+
+  pub async fn get_diagnostics(&self) -> ActorDiagnostics {
+    let mut diagnostics = match &self.context {
+      Some(context) => context.get_diagnostics().await,
+      None => ActorDiagnostics::default(),
+    };
+    diagnostics.user_messages_count = self.mailbox.get_user_messages_count().await;
+    diagnostics.system_messages_count = self.mailbox.get_system_messages_count().await;
+    diagnostics
+  }
 }
 
 #[async_trait]
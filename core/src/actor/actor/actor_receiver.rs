@@ -11,6 +11,7 @@ use crate::actor::context::ContextHandle;
 #[derive(Clone)]
 pub struct ActorReceiver(
   Arc<dyn Fn(ContextHandle) -> BoxFuture<'static, Result<(), ActorError>> + Send + Sync + 'static>,
+  &'static str,
 );
 
 unsafe impl Send for ActorReceiver {}
@@ -21,9 +22,14 @@ impl ActorReceiver {
   where
     F: Fn(ContextHandle) -> Fut + Send + Sync + 'static,
     Fut: Future<Output = Result<(), ActorError>> + Send + 'static, {
-    ActorReceiver(Arc::new(move |ch| {
-      Box::pin(f(ch)) as BoxFuture<'static, Result<(), ActorError>>
-    }))
+    ActorReceiver(
+      Arc::new(move |ch| Box::pin(f(ch)) as BoxFuture<'static, Result<(), ActorError>>),
+      std::any::type_name::<F>(),
+    )
+  }
+
+  pub fn get_type_name(&self) -> &'static str {
+    self.1
   }
 
   pub async fn run(&self, context: ContextHandle) -> Result<(), ActorError> {
@@ -1,3 +1,6 @@
 mod logging;
+mod propagation;
+mod trace_context;
+mod trace_context_test;
 
-pub use logging::*;
+pub use {logging::*, propagation::*, trace_context::*};
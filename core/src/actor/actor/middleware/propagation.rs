@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use crate::actor::actor::{SenderMiddleware, SenderMiddlewareChain};
+use crate::actor::context::{MessagePart, SenderContextHandle};
+use crate::actor::message::{MessageEnvelope, MessageHeaders, ReadonlyMessageHeaders};
+
+pub struct HeaderPropagation;
+
+impl HeaderPropagation {
+  pub fn of_sender(keys: impl IntoIterator<Item = impl Into<String>>) -> SenderMiddleware {
+    let keys = Arc::new(keys.into_iter().map(Into::into).collect::<Vec<String>>());
+    SenderMiddleware::new(move |next| {
+      let keys = keys.clone();
+      SenderMiddlewareChain::new(
+        move |context_handle: SenderContextHandle, target, envelope: MessageEnvelope| {
+          let cloned_next = next.clone();
+          let keys = keys.clone();
+          async move {
+            let envelope = match context_handle.get_message_header_handle().await {
+              Some(current) => propagate_headers(envelope, &current, &keys),
+              None => envelope,
+            };
+            cloned_next.run(context_handle, target, envelope).await
+          }
+        },
+      )
+    })
+  }
+}
+
+fn propagate_headers(
+  envelope: MessageEnvelope,
+  current: &impl ReadonlyMessageHeaders,
+  keys: &[String],
+) -> MessageEnvelope {
+  let mut headers = envelope
+    .get_header()
+    .map(|h| MessageHeaders::with_values(h.to_map()))
+    .unwrap_or_default();
+  let mut propagated = false;
+  for key in keys {
+    if headers.get(key).is_some() {
+      continue;
+    }
+    if let Some(value) = current.get(key) {
+      headers.set(key.clone(), value);
+      propagated = true;
+    }
+  }
+  if propagated {
+    envelope.with_header(headers)
+  } else {
+    envelope
+  }
+}
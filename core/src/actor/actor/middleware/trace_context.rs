@@ -0,0 +1,137 @@
+use std::fmt::{Display, Formatter};
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use uuid::Uuid;
+
+use crate::actor::actor::middleware::HeaderPropagation;
+use crate::actor::actor::{Actor, ReceiverMiddleware, ReceiverMiddlewareChain, SenderMiddleware};
+use crate::actor::context::{InfoPart, ReceiverContextHandle};
+use crate::actor::message::{Message, MessageEnvelope, MessageHeaders, ReadonlyMessageHeaders};
+
+pub const TRACE_PARENT_HEADER: &str = "traceparent";
+
+// W3C Trace Context (https://www.w3.org/TR/trace-context/). Versions above 00 are read by their 00 fields, and
+// always written as 00.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+  pub trace_id: u128,
+  pub span_id: u64,
+  pub sampled: bool,
+}
+
+impl TraceContext {
+  pub fn new_root() -> Self {
+    Self {
+      trace_id: Uuid::new_v4().as_u128(),
+      span_id: new_span_id(),
+      sampled: true,
+    }
+  }
+
+  pub fn new_child(&self) -> Self {
+    Self {
+      trace_id: self.trace_id,
+      span_id: new_span_id(),
+      sampled: self.sampled,
+    }
+  }
+
+  pub fn parse(traceparent: &str) -> Option<Self> {
+    let mut parts = traceparent.trim().split('-');
+    let (version, trace_id, span_id, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if version.len() != 2 || trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+      return None;
+    }
+    // Version ff is invalid, and only later versions may append fields.
+    match u8::from_str_radix(version, 16).ok()? {
+      0xff => return None,
+      0x00 if parts.next().is_some() => return None,
+      _ => {}
+    }
+    let trace_id = u128::from_str_radix(trace_id, 16).ok()?;
+    let span_id = u64::from_str_radix(span_id, 16).ok()?;
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+    if trace_id == 0 || span_id == 0 {
+      return None;
+    }
+    Some(Self {
+      trace_id,
+      span_id,
+      sampled: flags & 0x01 == 0x01,
+    })
+  }
+
+  pub fn from_headers(headers: &impl ReadonlyMessageHeaders) -> Option<Self> {
+    headers.get(TRACE_PARENT_HEADER).and_then(|v| Self::parse(&v))
+  }
+
+  pub fn of_sender() -> SenderMiddleware {
+    HeaderPropagation::of_sender([TRACE_PARENT_HEADER])
+  }
+
+  pub fn of_receiver() -> ReceiverMiddleware {
+    ReceiverMiddleware::new(|next| {
+      ReceiverMiddlewareChain::new(
+        move |context_handle: ReceiverContextHandle, envelope: MessageEnvelope| {
+          let cloned_next = next.clone();
+          async move {
+            let parent = envelope.get_header().and_then(|h| TraceContext::from_headers(&h));
+            let current = parent.map(|p| p.new_child()).unwrap_or_else(TraceContext::new_root);
+
+            let actor_type = context_handle
+              .get_actor()
+              .await
+              .map_or_else(|| "".to_string(), |a| a.get_type_name());
+            let span = tracing::info_span!(
+              "actor.receive",
+              actor_type = %actor_type,
+              message_type = %envelope.get_message_handle().get_type_name(),
+              trace_id = %format!("{:032x}", current.trace_id),
+              span_id = %format!("{:016x}", current.span_id),
+              parent_span_id = %parent.map(|p| format!("{:016x}", p.span_id)).unwrap_or_default(),
+            );
+            // Links the span to the sender's span when an OpenTelemetry layer is installed.
+            if let Some(headers) = envelope.get_header().filter(|_| parent.is_some()) {
+              span.set_parent(TraceContextPropagator::new().extract(&headers.to_map()));
+            }
+
+            // Replace the incoming traceparent so that messages sent while handling
+            // this one are parented to the receive span.
+            let mut headers = envelope
+              .get_header()
+              .map(|h| MessageHeaders::with_values(h.to_map()))
+              .unwrap_or_default();
+            headers.set(TRACE_PARENT_HEADER.to_string(), current.to_string());
+            let envelope = envelope.with_header(headers);
+
+            cloned_next.run(context_handle, envelope).instrument(span).await
+          }
+        },
+      )
+    })
+  }
+}
+
+impl Display for TraceContext {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "00-{:032x}-{:016x}-{:02x}",
+      self.trace_id,
+      self.span_id,
+      if self.sampled { 0x01 } else { 0x00 }
+    )
+  }
+}
+
+fn new_span_id() -> u64 {
+  loop {
+    let id = Uuid::new_v4().as_u64_pair().0;
+    if id != 0 {
+      return id;
+    }
+  }
+}
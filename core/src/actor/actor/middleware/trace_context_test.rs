@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+  use std::time::Duration;
+
+  use opentelemetry::trace::{SpanId, TraceId, TracerProvider};
+  use opentelemetry_sdk::testing::trace::InMemorySpanExporterBuilder;
+  use opentelemetry_sdk::trace::TracerProvider as SdkTracerProvider;
+  use tokio::sync::mpsc;
+  use tokio::time::timeout;
+  use tracing_subscriber::layer::SubscriberExt;
+
+  use crate::actor::actor::middleware::{TraceContext, TRACE_PARENT_HEADER};
+  use crate::actor::actor::Props;
+  use crate::actor::actor_system::ActorSystem;
+  use crate::actor::context::{MessagePart, RootContext, SenderPart, SpawnerPart};
+  use crate::actor::message::{MessageHandle, MessageHeaders, ReadonlyMessageHeaders};
+
+  #[test]
+  fn test_trace_context_parse_and_display() {
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let tc = TraceContext::parse(traceparent).unwrap();
+    assert_eq!(tc.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+    assert_eq!(tc.span_id, 0x00f067aa0ba902b7);
+    assert!(tc.sampled);
+    assert_eq!(tc.to_string(), traceparent);
+
+    let child = tc.new_child();
+    assert_eq!(child.trace_id, tc.trace_id);
+    assert_ne!(child.span_id, tc.span_id);
+    assert_eq!(TraceContext::parse(&child.to_string()), Some(child));
+  }
+
+  #[test]
+  fn test_trace_context_parse_invalid() {
+    assert_eq!(TraceContext::parse(""), None);
+    assert_eq!(
+      TraceContext::parse("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+      None
+    );
+    assert_eq!(
+      TraceContext::parse("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+      None
+    );
+    assert_eq!(
+      TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01"),
+      None
+    );
+    assert_eq!(TraceContext::parse("00-4bf92f3577b34da6-00f067aa0ba902b7-01"), None);
+    assert_eq!(
+      TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_trace_context_parse_later_version() {
+    let expected = TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01");
+    assert!(expected.is_some());
+    assert_eq!(
+      TraceContext::parse("01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+      expected
+    );
+    assert_eq!(
+      TraceContext::parse("cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-what-the-future-will-be-like"),
+      expected
+    );
+    // Written back as version 00.
+    assert_eq!(
+      expected.unwrap().to_string(),
+      "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_receive_span_is_parented_to_the_traceparent() {
+    let exporter = InMemorySpanExporterBuilder::new().build();
+    let provider = SdkTracerProvider::builder()
+      .with_simple_exporter(exporter.clone())
+      .build();
+    let subscriber =
+      tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let system = ActorSystem::new().await.unwrap();
+    let parent = TraceContext::new_root();
+    let mut headers = MessageHeaders::new();
+    headers.set(TRACE_PARENT_HEADER.to_string(), parent.to_string());
+    let mut root_context = RootContext::new(system.clone(), Arc::new(headers), &[TraceContext::of_sender()]);
+
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let props = Props::from_async_actor_receiver_with_opts(
+      move |ctx| {
+        let tx = tx.clone();
+        async move {
+          if ctx.get_message_handle().await.to_typed::<String>().is_some() {
+            tx.send(()).await.unwrap();
+          }
+          Ok(())
+        }
+      },
+      [Props::with_receiver_middlewares([TraceContext::of_receiver()])],
+    )
+    .await;
+    let pid = root_context.spawn(props).await;
+    root_context.send(pid, MessageHandle::new("hello".to_string())).await;
+    timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+
+    // The span is exported once the receive has returned.
+    let span = timeout(Duration::from_secs(1), async {
+      loop {
+        let spans = exporter.get_finished_spans().unwrap();
+        if let Some(span) = spans
+          .into_iter()
+          .find(|span| span.name == "actor.receive" && span.span_context.trace_id() == TraceId::from(parent.trace_id))
+        {
+          return span;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+      }
+    })
+    .await
+    .unwrap();
+    assert_eq!(span.parent_span_id, SpanId::from(parent.span_id));
+  }
+
+  #[tokio::test]
+  async fn test_trace_context_propagates_across_actors() {
+    let system = ActorSystem::new().await.unwrap();
+    let parent = TraceContext::new_root();
+    let mut headers = MessageHeaders::new();
+    headers.set(TRACE_PARENT_HEADER.to_string(), parent.to_string());
+    let mut root_context = RootContext::new(system.clone(), Arc::new(headers), &[TraceContext::of_sender()]);
+
+    let (tx, mut rx) = mpsc::channel::<(&'static str, Option<String>)>(10);
+
+    let cloned_tx = tx.clone();
+    let downstream_props = Props::from_async_actor_receiver(move |ctx| {
+      let tx = cloned_tx.clone();
+      async move {
+        if ctx.get_message_handle().await.to_typed::<String>().is_some() {
+          let header = ctx
+            .get_message_header_handle()
+            .await
+            .and_then(|h| h.get(TRACE_PARENT_HEADER));
+          tx.send(("downstream", header)).await.unwrap();
+        }
+        Ok(())
+      }
+    })
+    .await;
+    let downstream = root_context.spawn(downstream_props).await;
+
+    let upstream_props = Props::from_async_actor_receiver_with_opts(
+      move |mut ctx| {
+        let tx = tx.clone();
+        let downstream = downstream.clone();
+        async move {
+          if let Some(msg) = ctx.get_message_handle().await.to_typed::<String>() {
+            let header = ctx
+              .get_message_header_handle()
+              .await
+              .and_then(|h| h.get(TRACE_PARENT_HEADER));
+            tx.send(("upstream", header)).await.unwrap();
+            ctx.send(downstream, MessageHandle::new(msg)).await;
+          }
+          Ok(())
+        }
+      },
+      [
+        Props::with_receiver_middlewares([TraceContext::of_receiver()]),
+        Props::with_sender_middlewares([TraceContext::of_sender()]),
+      ],
+    )
+    .await;
+    let upstream = root_context.spawn(upstream_props).await;
+
+    root_context
+      .send(upstream, MessageHandle::new("hello".to_string()))
+      .await;
+
+    let (name, upstream_header) = timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+    assert_eq!(name, "upstream");
+    let upstream_tc = TraceContext::parse(&upstream_header.unwrap()).unwrap();
+    assert_eq!(upstream_tc.trace_id, parent.trace_id);
+    assert_ne!(upstream_tc.span_id, parent.span_id);
+
+    let (name, downstream_header) = timeout(Duration::from_secs(1), rx.recv()).await.unwrap().unwrap();
+    assert_eq!(name, "downstream");
+    assert_eq!(downstream_header, Some(upstream_tc.to_string()));
+  }
+}
@@ -75,7 +75,7 @@ static DEFAULT_SPAWNER: Lazy<Spawner> = Lazy::new(|| {
       let mut mb = props.produce_mailbox().await;
 
       let dp = DispatcherHandle::new_arc(actor_system.get_config().await.system_dispatcher.clone());
-      let proc = ActorProcess::new(mb.clone());
+      let proc = ActorProcess::new(mb.clone()).with_context(ctx.clone());
       let proc_handle = ProcessHandle::new(proc);
       let pr = actor_system.get_process_registry().await;
 
@@ -139,6 +139,10 @@ impl Actor for ActorReceiverActor {
   async fn get_supervisor_strategy(&mut self) -> Option<SupervisorStrategyHandle> {
     None
   }
+
+  async fn get_behavior_stack(&self) -> Vec<String> {
+    vec![self.0.get_type_name().to_string()]
+  }
 }
 
 #[derive(Clone)]
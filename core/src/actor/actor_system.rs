@@ -12,7 +12,9 @@ use crate::actor::message::EMPTY_MESSAGE_HEADER;
 use crate::actor::metrics::metrics_impl::Metrics;
 use crate::actor::process::process_registry::ProcessRegistry;
 use crate::actor::process::ProcessHandle;
-use crate::actor::supervisor::subscribe_supervision;
+use crate::actor::supervisor::{
+  subscribe_supervision, SupervisionHistory, EXTENSION_ID as SUPERVISION_HISTORY_EXTENSION_ID,
+};
 use crate::actor::{Config, ConfigOption};
 use crate::event_stream::EventStream;
 use crate::extensions::Extensions;
@@ -77,6 +79,14 @@ impl ActorSystem {
 
     subscribe_supervision(&system).await;
 
+    let supervision_history = SupervisionHistory::new(system.clone()).await;
+    supervision_history.subscribe().await;
+    system
+      .get_extensions()
+      .await
+      .register(Arc::new(Mutex::new(supervision_history)))
+      .await;
+
     if config.metrics_provider.is_some() {
       system
         .get_extensions()
@@ -169,6 +179,16 @@ impl ActorSystem {
     inner_mg.dead_letter = Some(dead_letter);
   }
 
+  pub async fn get_supervision_history(&self) -> Option<SupervisionHistory> {
+    let extension_arc = self
+      .get_extensions()
+      .await
+      .get(*SUPERVISION_HISTORY_EXTENSION_ID)
+      .await?;
+    let extension = extension_arc.lock().await;
+    extension.as_any().downcast_ref::<SupervisionHistory>().cloned()
+  }
+
   pub async fn get_extensions(&self) -> Extensions {
     let inner_mg = self.inner.lock().await;
     inner_mg.extensions.clone()
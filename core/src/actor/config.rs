@@ -60,6 +60,9 @@ pub struct Config {
   pub dead_letter_throttle_count: usize,
   pub dead_letter_request_logging: bool,
   pub developer_supervision_logging: bool,
+  pub supervision_history_size: usize,
+  pub restart_storm_threshold: u32,
+  pub restart_storm_window: Duration,
   // Other fields...
 }
 
@@ -74,6 +77,9 @@ impl Default for Config {
       dead_letter_throttle_count: 10,
       dead_letter_request_logging: false,
       developer_supervision_logging: false,
+      supervision_history_size: 32,
+      restart_storm_threshold: 10,
+      restart_storm_window: Duration::from_secs(10),
       // Set other default values...
     }
   }
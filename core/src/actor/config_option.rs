@@ -12,6 +12,9 @@ pub enum ConfigOption {
   SetDeadLetterThrottleInterval(Duration),
   SetDeadLetterThrottleCount(usize),
   SetDeadLetterRequestLogging(bool),
+  SetSupervisionHistorySize(usize),
+  SetRestartStormThreshold(u32),
+  SetRestartStormWindow(Duration),
   // Other options...
 }
 
@@ -38,6 +41,15 @@ impl ConfigOption {
       }
       ConfigOption::SetDeadLetterRequestLogging(enabled) => {
         config.dead_letter_request_logging = *enabled;
+      }
+      ConfigOption::SetSupervisionHistorySize(size) => {
+        config.supervision_history_size = *size;
+      }
+      ConfigOption::SetRestartStormThreshold(threshold) => {
+        config.restart_storm_threshold = *threshold;
+      }
+      ConfigOption::SetRestartStormWindow(window) => {
+        config.restart_storm_window = *window;
       } // Handle other options...
     }
   }
@@ -53,4 +65,16 @@ impl ConfigOption {
   pub fn with_dead_letter_request_logging(enabled: bool) -> ConfigOption {
     ConfigOption::SetDeadLetterRequestLogging(enabled)
   }
+
+  pub fn with_supervision_history_size(size: usize) -> ConfigOption {
+    ConfigOption::SetSupervisionHistorySize(size)
+  }
+
+  pub fn with_restart_storm_threshold(threshold: u32) -> ConfigOption {
+    ConfigOption::SetRestartStormThreshold(threshold)
+  }
+
+  pub fn with_restart_storm_window(window: Duration) -> ConfigOption {
+    ConfigOption::SetRestartStormWindow(window)
+  }
 }
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::actor::actor::Actor;
+use crate::actor::actor::ActorDiagnostics;
 use crate::actor::actor::ActorError;
 use crate::actor::actor::ActorHandle;
 use crate::actor::actor::ActorProducer;
@@ -17,6 +18,7 @@ use crate::actor::actor::SpawnError;
 use crate::actor::actor_system::ActorSystem;
 use crate::actor::context::actor_context_extras::ActorContextExtras;
 use crate::actor::context::context_handle::ContextHandle;
+use crate::actor::context::sender_context_handle::SenderContextHandle;
 use crate::actor::context::spawner_context_handle::SpawnerContextHandle;
 use crate::actor::context::state::State;
 use crate::actor::context::{
@@ -29,6 +31,7 @@ use crate::actor::dispatch::MessageInvoker;
 use crate::actor::message::AutoReceiveMessage;
 use crate::actor::message::Continuation;
 use crate::actor::message::Failure;
+use crate::actor::message::Message;
 use crate::actor::message::MessageHandle;
 use crate::actor::message::NotInfluenceReceiveTimeoutHandle;
 use crate::actor::message::ReadonlyMessageHeadersHandle;
@@ -167,7 +170,7 @@ impl ActorContext {
 
   async fn default_receive(&mut self) -> Result<(), ActorError> {
     let message = self.get_message_handle_opt().await.expect("Failed to retrieve message");
-    if message.to_typed::<PoisonPill>().is_some() {
+    if message.is_typed::<PoisonPill>() {
       let me = self.get_self().await;
       self.stop(&me).await;
       Ok(())
@@ -179,8 +182,8 @@ impl ActorContext {
 
       let result = actor.handle(context.clone()).await;
 
-      let me = message.to_typed::<MessageEnvelope>();
-      let ar = message.to_typed::<AutoRespond>();
+      let me = message.as_typed::<MessageEnvelope>();
+      let ar = message.as_typed::<AutoRespond>();
       let msg = match (me, ar) {
         (Some(me), _) => me.get_message_handle().to_typed::<AutoRespond>(),
         (_, Some(ar)) => Some(ar.clone()),
@@ -222,6 +225,36 @@ impl ActorContext {
       .await;
   }
 
+  pub(crate) async fn get_diagnostics(&self) -> ActorDiagnostics {
+    let mut diagnostics = ActorDiagnostics {
+      pid: self.get_self_opt().await,
+      ..ActorDiagnostics::default()
+    };
+    if let Some(actor) = self.get_actor().await {
+      diagnostics.actor_type = actor.get_type_name();
+      diagnostics.behavior_stack = actor.get_behavior_stack().await;
+    }
+    if let Some(extras) = self.get_extras().await {
+      diagnostics.children = extras
+        .get_children()
+        .await
+        .to_vec()
+        .await
+        .into_iter()
+        .map(ExtendedPid::new)
+        .collect();
+      diagnostics.watchers = extras
+        .get_watchers()
+        .await
+        .to_vec()
+        .await
+        .into_iter()
+        .map(ExtendedPid::new)
+        .collect();
+    }
+    diagnostics
+  }
+
   async fn get_receiver_middleware_chain(&self) -> Option<ReceiverMiddlewareChain> {
     let mg = self.inner.lock().await;
     mg.props.get_receiver_middleware_chain().clone()
@@ -235,9 +268,10 @@ impl ActorContext {
   pub async fn send_user_message(&self, pid: ExtendedPid, message_handle: MessageHandle) {
     match self.get_sender_middleware_chain().await {
       Some(chain) => {
-        let mut cloned = self.clone();
-        let context = cloned.ensure_extras().await.get_sender_context().await;
-        chain.run(context, pid, MessageEnvelope::new(message_handle)).await;
+        // The context in the extras is locked while a receiver middleware runs, which may be the one sending, so the
+        // chain gets a handle of its own.
+        let context = SenderContextHandle::new(ContextHandle::new(self.clone()));
+        chain.run(context, pid, wrap_envelope(message_handle)).await;
       }
       _ => {
         pid
@@ -319,6 +353,10 @@ impl ActorContext {
       .await
       .remove_process(&self.get_self_opt().await.unwrap())
       .await;
+    // The restart history of a terminated actor is never looked up again.
+    if let Some(supervision_history) = self.get_actor_system().await.get_supervision_history().await {
+      supervision_history.clear(&self.get_self_opt().await.unwrap());
+    }
     let result = self
       .invoke_user_message(MessageHandle::new(AutoReceiveMessage::PostStop))
       .await;
@@ -707,7 +745,7 @@ impl BasePart for ActorContext {
     let inner_mg = self.inner.lock().await;
     let mg = inner_mg.message_or_envelope_opt.read().await;
     if let Some(message_or_envelope) = &*mg {
-      if let Some(sm) = message_or_envelope.to_typed::<SystemMessage>() {
+      if let Some(sm) = message_or_envelope.as_typed::<SystemMessage>() {
         panic!("SystemMessage cannot be forwarded: {:?}", sm);
       } else {
         pid
@@ -1031,7 +1069,9 @@ impl MessageInvoker for ActorContext {
     };
 
     if receive_timeout.unwrap_or_else(|| Duration::from_millis(0)) > Duration::from_millis(0) {
-      influence_timeout = !message_handle.to_typed::<NotInfluenceReceiveTimeoutHandle>().is_some();
+      let message = unwrap_envelope_message(message_handle.clone());
+      influence_timeout =
+        !message.is_typed::<NotInfluenceReceiveTimeoutHandle>() && !message.is_not_influence_receive_timeout();
       if influence_timeout {
         let mg = self.get_extras().await;
         if let Some(extras) = mg {
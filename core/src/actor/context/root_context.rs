@@ -18,6 +18,7 @@ use crate::actor::context::{
   InfoPart, MessagePart, SenderContext, SenderPart, SpawnerContext, SpawnerPart, StopperPart, TypedRootContext,
 };
 use crate::actor::dispatch::future::{ActorFuture, ActorFutureProcess};
+use crate::actor::message::wrap_envelope;
 use crate::actor::message::MessageEnvelope;
 use crate::actor::message::MessageHandle;
 use crate::actor::message::MessageHeaders;
@@ -46,7 +47,7 @@ impl RootContext {
           let actor_system = actor_system.clone();
           async move {
             target
-              .send_user_message(actor_system, envelope.get_message_handle())
+              .send_user_message(actor_system, MessageHandle::new(envelope))
               .await
           }
         }),
@@ -75,7 +76,7 @@ impl RootContext {
   async fn send_user_message(&self, pid: ExtendedPid, message_handle: MessageHandle) {
     if self.sender_middleware_chain.is_some() {
       let sch = SenderContextHandle::new(self.clone());
-      let me = MessageEnvelope::new(message_handle);
+      let me = wrap_envelope(message_handle);
       self.sender_middleware_chain.clone().unwrap().run(sch, pid, me).await;
     } else {
       tracing::debug!("Sending user message to pid: {}", pid);
@@ -1,4 +1,6 @@
 mod bounded;
+pub mod circuit_breaker;
+mod circuit_breaker_test;
 mod dead_letter_process;
 mod dead_letter_test;
 mod default_mailbox;
@@ -0,0 +1,265 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use nexus_actor_message_derive_rs::Message;
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::actor::actor::ExtendedPid;
+use crate::actor::actor_system::ActorSystem;
+use crate::actor::context::SenderPart;
+use crate::actor::dispatch::future::{ActorFuture, ActorFutureError};
+use crate::actor::message::Message;
+use crate::actor::message::MessageHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitBreakerState {
+  Closed,
+  Open,
+  HalfOpen,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub struct CircuitBreakerStateChanged {
+  pub name: String,
+  pub from: CircuitBreakerState,
+  pub to: CircuitBreakerState,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CircuitBreakerError {
+  #[error("circuit breaker: open")]
+  Open,
+  #[error("circuit breaker: call failed: {0}")]
+  CallFailed(ActorFutureError),
+}
+
+#[derive(Debug)]
+struct CircuitBreakerInner {
+  state: CircuitBreakerState,
+  failure_count: u32,
+  opened_at: Option<Instant>,
+  // Incremented on every transition and reset. Results of calls admitted under an older generation are ignored,
+  // so a late failure doesn't reopen the breaker and a late success doesn't close it.
+  generation: u64,
+}
+
+// Frees the half-open slot when the trial call is dropped before it completes, e.g. when its task is cancelled.
+struct HalfOpenCallGuard(Option<Arc<AtomicBool>>);
+
+impl HalfOpenCallGuard {
+  fn disarm(&mut self) {
+    self.0 = None;
+  }
+}
+
+impl Drop for HalfOpenCallGuard {
+  fn drop(&mut self) {
+    if let Some(half_open_call_in_flight) = &self.0 {
+      half_open_call_in_flight.store(false, Ordering::SeqCst);
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+  actor_system: ActorSystem,
+  name: String,
+  max_failures: u32,
+  call_timeout: Duration,
+  reset_timeout: Duration,
+  inner: Arc<Mutex<CircuitBreakerInner>>,
+  half_open_call_in_flight: Arc<AtomicBool>,
+}
+
+impl CircuitBreaker {
+  pub fn new(
+    actor_system: ActorSystem,
+    name: &str,
+    max_failures: u32,
+    call_timeout: Duration,
+    reset_timeout: Duration,
+  ) -> Self {
+    Self {
+      actor_system,
+      name: name.to_string(),
+      max_failures,
+      call_timeout,
+      reset_timeout,
+      inner: Arc::new(Mutex::new(CircuitBreakerInner {
+        state: CircuitBreakerState::Closed,
+        failure_count: 0,
+        opened_at: None,
+        generation: 0,
+      })),
+      half_open_call_in_flight: Arc::new(AtomicBool::new(false)),
+    }
+  }
+
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn get_call_timeout(&self) -> Duration {
+    self.call_timeout
+  }
+
+  pub async fn get_state(&self) -> CircuitBreakerState {
+    let mg = self.inner.lock().await;
+    mg.state
+  }
+
+  pub async fn get_failure_count(&self) -> u32 {
+    let mg = self.inner.lock().await;
+    mg.failure_count
+  }
+
+  pub async fn request<C>(
+    &self,
+    ctx: &C,
+    pid: ExtendedPid,
+    message_handle: MessageHandle,
+  ) -> Result<MessageHandle, CircuitBreakerError>
+  where
+    C: SenderPart, {
+    self
+      .call(|timeout| async move { ctx.request_future(pid, message_handle, timeout).await })
+      .await
+  }
+
+  pub async fn call<F, Fut>(&self, f: F) -> Result<MessageHandle, CircuitBreakerError>
+  where
+    F: FnOnce(Duration) -> Fut + Send,
+    Fut: Future<Output = ActorFuture> + Send, {
+    let (mut guard, generation) = self.before_call().await?;
+    let result = f(self.call_timeout).await.result().await;
+    let result = match result {
+      Ok(message_handle) => {
+        self.on_success(generation).await;
+        Ok(message_handle)
+      }
+      Err(error) => {
+        self.on_failure(generation).await;
+        Err(CircuitBreakerError::CallFailed(error))
+      }
+    };
+    // on_success, on_failure or a reset in the meantime have freed the half-open slot, which another call may hold
+    // by now.
+    guard.disarm();
+    result
+  }
+
+  pub async fn reset(&self) {
+    let transition = {
+      let mut mg = self.inner.lock().await;
+      mg.failure_count = 0;
+      mg.opened_at = None;
+      mg.generation += 1;
+      self.half_open_call_in_flight.store(false, Ordering::SeqCst);
+      Self::transition(&mut mg, CircuitBreakerState::Closed)
+    };
+    self.publish_transition(transition).await;
+  }
+
+  // Returns the guard of the half-open slot, and the generation the call is admitted under.
+  async fn before_call(&self) -> Result<(HalfOpenCallGuard, u64), CircuitBreakerError> {
+    let (result, transition) = {
+      let mut mg = self.inner.lock().await;
+      let (result, transition) = match mg.state {
+        CircuitBreakerState::Closed => (Ok(HalfOpenCallGuard(None)), None),
+        CircuitBreakerState::Open => {
+          let elapsed = mg.opened_at.map(|t| t.elapsed()).unwrap_or_default();
+          if elapsed >= self.reset_timeout {
+            self.half_open_call_in_flight.store(true, Ordering::SeqCst);
+            (
+              Ok(self.half_open_call_guard()),
+              Self::transition(&mut mg, CircuitBreakerState::HalfOpen),
+            )
+          } else {
+            (Err(CircuitBreakerError::Open), None)
+          }
+        }
+        CircuitBreakerState::HalfOpen => {
+          if self.half_open_call_in_flight.swap(true, Ordering::SeqCst) {
+            (Err(CircuitBreakerError::Open), None)
+          } else {
+            (Ok(self.half_open_call_guard()), None)
+          }
+        }
+      };
+      (result.map(|guard| (guard, mg.generation)), transition)
+    };
+    self.publish_transition(transition).await;
+    result
+  }
+
+  fn half_open_call_guard(&self) -> HalfOpenCallGuard {
+    HalfOpenCallGuard(Some(self.half_open_call_in_flight.clone()))
+  }
+
+  async fn on_success(&self, generation: u64) {
+    let transition = {
+      let mut mg = self.inner.lock().await;
+      if mg.generation != generation {
+        return;
+      }
+      mg.failure_count = 0;
+      mg.opened_at = None;
+      self.half_open_call_in_flight.store(false, Ordering::SeqCst);
+      Self::transition(&mut mg, CircuitBreakerState::Closed)
+    };
+    self.publish_transition(transition).await;
+  }
+
+  async fn on_failure(&self, generation: u64) {
+    let transition = {
+      let mut mg = self.inner.lock().await;
+      if mg.generation != generation {
+        return;
+      }
+      mg.failure_count += 1;
+      self.half_open_call_in_flight.store(false, Ordering::SeqCst);
+      if mg.state == CircuitBreakerState::HalfOpen || mg.failure_count >= self.max_failures {
+        mg.opened_at = Some(Instant::now());
+        Self::transition(&mut mg, CircuitBreakerState::Open)
+      } else {
+        None
+      }
+    };
+    self.publish_transition(transition).await;
+  }
+
+  fn transition(
+    inner: &mut CircuitBreakerInner,
+    to: CircuitBreakerState,
+  ) -> Option<(CircuitBreakerState, CircuitBreakerState)> {
+    if inner.state == to {
+      return None;
+    }
+    let from = inner.state;
+    inner.state = to;
+    inner.generation += 1;
+    Some((from, to))
+  }
+
+  async fn publish_transition(&self, transition: Option<(CircuitBreakerState, CircuitBreakerState)>) {
+    if let Some((from, to)) = transition {
+      tracing::debug!("CircuitBreaker {}: {:?} -> {:?}", self.name, from, to);
+      self
+        .actor_system
+        .get_event_stream()
+        .await
+        .publish(MessageHandle::new(CircuitBreakerStateChanged {
+          name: self.name.clone(),
+          from,
+          to,
+        }))
+        .await;
+    }
+  }
+}
+
+static_assertions::assert_impl_all!(CircuitBreaker: Send, Sync);
@@ -0,0 +1,305 @@
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use tokio::sync::mpsc;
+  use tokio::time::{sleep, Instant};
+
+  use crate::actor::actor::{ExtendedPid, Props};
+  use crate::actor::actor_system::ActorSystem;
+  use crate::actor::context::{BasePart, InfoPart, MessagePart, RootContext, SenderPart, SpawnerPart};
+  use crate::actor::dispatch::circuit_breaker::{
+    CircuitBreaker, CircuitBreakerError, CircuitBreakerState, CircuitBreakerStateChanged,
+  };
+  use crate::actor::dispatch::future::ActorFutureError;
+  use crate::actor::message::Message;
+  use crate::actor::message::MessageHandle;
+  use crate::actor::message::ResponseHandle;
+
+  #[tokio::test]
+  async fn test_circuit_breaker_opens_and_recovers() {
+    let system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = mpsc::channel(10);
+    system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<CircuitBreakerStateChanged>().cloned();
+        async move {
+          if let Some(changed) = evt {
+            tx.try_send(changed.to).unwrap();
+          }
+        }
+      })
+      .await;
+
+    let mut root_context = system.get_root_context().await;
+    let props = Props::from_async_actor_receiver(|ctx| async move {
+      if let Some(msg) = ctx.get_message_handle().await.to_typed::<String>() {
+        if msg == "ok" {
+          ctx.respond(ResponseHandle::new(msg)).await;
+        }
+      }
+      Ok(())
+    })
+    .await;
+    let pid = root_context.spawn(props).await;
+
+    let breaker = CircuitBreaker::new(
+      system.clone(),
+      "downstream",
+      2,
+      Duration::from_millis(50),
+      Duration::from_millis(200),
+    );
+
+    for _ in 0..2 {
+      let result = breaker
+        .request(&root_context, pid.clone(), MessageHandle::new("fail".to_string()))
+        .await;
+      assert_eq!(
+        result.unwrap_err(),
+        CircuitBreakerError::CallFailed(ActorFutureError::TimeoutError)
+      );
+    }
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Open);
+    assert_eq!(rx.recv().await, Some(CircuitBreakerState::Open));
+
+    let started = Instant::now();
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("ok".to_string()))
+      .await;
+    assert_eq!(result.unwrap_err(), CircuitBreakerError::Open);
+    assert!(started.elapsed() < Duration::from_millis(50));
+
+    sleep(Duration::from_millis(250)).await;
+
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("ok".to_string()))
+      .await;
+    assert!(result.is_ok());
+    assert_eq!(rx.recv().await, Some(CircuitBreakerState::HalfOpen));
+    assert_eq!(rx.recv().await, Some(CircuitBreakerState::Closed));
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Closed);
+  }
+
+  // Spawns an actor that responds to "ok" at once, to "slow" after 300ms and never to anything else.
+  async fn spawn_downstream(root_context: &mut RootContext) -> ExtendedPid {
+    let props = Props::from_async_actor_receiver(|ctx| async move {
+      if let Some(msg) = ctx.get_message_handle().await.to_typed::<String>() {
+        match msg.as_str() {
+          "ok" => ctx.respond(ResponseHandle::new(msg)).await,
+          "slow" => {
+            let sender = ctx.get_sender().await.unwrap();
+            let actor_system = ctx.get_actor_system().await;
+            tokio::spawn(async move {
+              sleep(Duration::from_millis(300)).await;
+              actor_system
+                .get_root_context()
+                .await
+                .send(sender, MessageHandle::new(msg))
+                .await;
+            });
+          }
+          _ => {}
+        }
+      }
+      Ok(())
+    })
+    .await;
+    root_context.spawn(props).await
+  }
+
+  #[tokio::test]
+  async fn test_stale_failure_does_not_extend_the_open_state() {
+    let system = ActorSystem::new().await.unwrap();
+    let mut root_context = system.get_root_context().await;
+    let pid = spawn_downstream(&mut root_context).await;
+    let breaker = CircuitBreaker::new(
+      system.clone(),
+      "downstream",
+      1,
+      Duration::from_millis(20),
+      Duration::from_millis(300),
+    );
+
+    // Admitted while closed, and failing only after the breaker has opened.
+    let stale = tokio::spawn({
+      let breaker = breaker.clone();
+      let root_context = root_context.clone();
+      let pid = pid.clone();
+      async move {
+        breaker
+          .call(|_| async move {
+            root_context
+              .request_future(pid, MessageHandle::new("fail".to_string()), Duration::from_millis(200))
+              .await
+          })
+          .await
+      }
+    });
+    let started = Instant::now();
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("fail".to_string()))
+      .await;
+    assert!(result.is_err());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Open);
+    assert!(stale.await.unwrap().is_err());
+    assert_eq!(breaker.get_failure_count().await, 1);
+
+    // The reset timeout counts from the first failure, not from the stale one.
+    sleep(Duration::from_millis(330).saturating_sub(started.elapsed())).await;
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("ok".to_string()))
+      .await;
+    assert!(result.is_ok());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Closed);
+  }
+
+  #[tokio::test]
+  async fn test_stale_success_does_not_close_a_half_open_breaker() {
+    let system = ActorSystem::new().await.unwrap();
+    let mut root_context = system.get_root_context().await;
+    let pid = spawn_downstream(&mut root_context).await;
+    let breaker = CircuitBreaker::new(
+      system.clone(),
+      "downstream",
+      1,
+      Duration::from_secs(1),
+      Duration::from_millis(100),
+    );
+
+    // Admitted while closed, and succeeding only while the trial call is in flight.
+    let stale = tokio::spawn({
+      let breaker = breaker.clone();
+      let root_context = root_context.clone();
+      let pid = pid.clone();
+      async move {
+        breaker
+          .request(&root_context, pid, MessageHandle::new("slow".to_string()))
+          .await
+      }
+    });
+    let result = breaker
+      .call(|_| {
+        let root_context = root_context.clone();
+        let pid = pid.clone();
+        async move {
+          root_context
+            .request_future(pid, MessageHandle::new("fail".to_string()), Duration::from_millis(20))
+            .await
+        }
+      })
+      .await;
+    assert!(result.is_err());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Open);
+
+    sleep(Duration::from_millis(150)).await;
+    let trial = tokio::spawn({
+      let breaker = breaker.clone();
+      let root_context = root_context.clone();
+      let pid = pid.clone();
+      async move {
+        breaker
+          .request(&root_context, pid, MessageHandle::new("fail".to_string()))
+          .await
+      }
+    });
+    assert!(stale.await.unwrap().is_ok());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::HalfOpen);
+    trial.abort();
+  }
+
+  #[tokio::test]
+  async fn test_circuit_breaker_in_actor_receive() {
+    let system = ActorSystem::new().await.unwrap();
+    let mut root_context = system.get_root_context().await;
+    let downstream = spawn_downstream(&mut root_context).await;
+    let breaker = CircuitBreaker::new(
+      system.clone(),
+      "downstream",
+      1,
+      Duration::from_millis(50),
+      Duration::from_secs(10),
+    );
+
+    // Forwards each message to the downstream actor through the breaker, and responds with the outcome.
+    let props = Props::from_async_actor_receiver(move |ctx| {
+      let breaker = breaker.clone();
+      let downstream = downstream.clone();
+      async move {
+        if let Some(msg) = ctx.get_message_handle().await.to_typed::<String>() {
+          let outcome = match breaker.request(&ctx, downstream, MessageHandle::new(msg)).await {
+            Ok(_) => "ok",
+            Err(CircuitBreakerError::Open) => "open",
+            Err(CircuitBreakerError::CallFailed(_)) => "failed",
+          };
+          ctx.respond(ResponseHandle::new(outcome.to_string())).await;
+        }
+        Ok(())
+      }
+    })
+    .await;
+    let client = root_context.spawn(props).await;
+
+    for (msg, expected) in [("ok", "ok"), ("fail", "failed"), ("ok", "open")] {
+      let response = root_context
+        .request_future(
+          client.clone(),
+          MessageHandle::new(msg.to_string()),
+          Duration::from_secs(1),
+        )
+        .await
+        .result()
+        .await
+        .unwrap();
+      assert_eq!(response.to_typed::<String>().unwrap(), expected);
+    }
+  }
+
+  #[tokio::test]
+  async fn test_cancelled_half_open_call_frees_the_slot() {
+    let system = ActorSystem::new().await.unwrap();
+    let mut root_context = system.get_root_context().await;
+    let props = Props::from_async_actor_receiver(|ctx| async move {
+      if let Some(msg) = ctx.get_message_handle().await.to_typed::<String>() {
+        if msg == "ok" {
+          ctx.respond(ResponseHandle::new(msg)).await;
+        }
+      }
+      Ok(())
+    })
+    .await;
+    let pid = root_context.spawn(props).await;
+
+    let breaker = CircuitBreaker::new(
+      system.clone(),
+      "downstream",
+      1,
+      Duration::from_millis(500),
+      Duration::from_millis(100),
+    );
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("fail".to_string()))
+      .await;
+    assert!(result.is_err());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Open);
+
+    sleep(Duration::from_millis(150)).await;
+    // The trial call is cancelled before its response or timeout.
+    let cancelled = tokio::time::timeout(
+      Duration::from_millis(50),
+      breaker.request(&root_context, pid.clone(), MessageHandle::new("fail".to_string())),
+    )
+    .await;
+    assert!(cancelled.is_err());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::HalfOpen);
+
+    let result = breaker
+      .request(&root_context, pid.clone(), MessageHandle::new("ok".to_string()))
+      .await;
+    assert!(result.is_ok());
+    assert_eq!(breaker.get_state().await, CircuitBreakerState::Closed);
+  }
+}
@@ -2,7 +2,6 @@ use crate::actor::actor::ExtendedPid;
 use crate::actor::actor_system::ActorSystem;
 use crate::actor::context::SenderPart;
 use crate::actor::message::unwrap_envelope;
-use crate::actor::message::IgnoreDeadLetterLogging;
 use crate::actor::message::Message;
 use crate::actor::message::MessageHandle;
 use crate::actor::message::SystemMessage;
@@ -83,19 +82,23 @@ impl DeadLetterProcess {
               return;
             }
 
-            if let Some(is_ignore_dead_letter) = dead_letter.message_handle.to_typed::<IgnoreDeadLetterLogging>() {
-              if cloned_throttle.should_throttle() == Valve::Open {
-                tracing::debug!(
-                  "DeadLetterProcess: Message from {} to {} was not delivered, message: {:?}",
-                  dead_letter.sender.as_ref().unwrap(),
-                  dead_letter
-                    .pid
-                    .as_ref()
-                    .map(|v| v.to_string())
-                    .unwrap_or("None".to_string()),
-                  is_ignore_dead_letter
-                );
-              }
+            if !dead_letter.message_handle.is_ignore_dead_letter_logging()
+              && cloned_throttle.should_throttle() == Valve::Open
+            {
+              tracing::debug!(
+                "DeadLetterProcess: Message from {} to {} was not delivered, message: {:?}",
+                dead_letter
+                  .sender
+                  .as_ref()
+                  .map(|v| v.to_string())
+                  .unwrap_or("None".to_string()),
+                dead_letter
+                  .pid
+                  .as_ref()
+                  .map(|v| v.to_string())
+                  .unwrap_or("None".to_string()),
+                dead_letter.message_handle
+              );
             }
           }
         }
@@ -199,7 +199,7 @@ impl DefaultMailbox {
 
       if let Ok(Some(msg)) = self.poll_system_mailbox().await {
         self.decrement_system_messages_count().await;
-        let mailbox_message = msg.to_typed::<MailboxMessage>();
+        let mailbox_message = msg.as_typed::<MailboxMessage>();
         match mailbox_message {
           Some(MailboxMessage::SuspendMailbox) => {
             self.set_suspended(true).await;
@@ -12,6 +12,7 @@ mod tests {
   use nexus_actor_utils_rs::collections::{QueueReader, QueueWriter, RingQueue};
   use rand::rngs::SmallRng;
   use rand::Rng;
+  use rand::SeedableRng;
   use std::env;
   use std::sync::Arc;
   use std::time::Duration;
@@ -89,7 +90,7 @@ mod tests {
       .await;
 
     let mut join_handles = Vec::new();
-    let rng = SmallRng::from_thread_rng();
+    let rng = SmallRng::from_rng(&mut rand::rng());
 
     for j in 0..c {
       let cmax = max / c;
@@ -147,7 +148,7 @@ mod tests {
       .await;
 
     let mut join_handles = Vec::new();
-    let rng = SmallRng::from_thread_rng();
+    let rng = SmallRng::from_rng(&mut rand::rng());
 
     for j in 0..c {
       let cmax = max / c;
@@ -8,6 +8,7 @@ mod message;
 mod message_batch;
 mod message_batch_test;
 mod message_handle;
+mod message_handle_test;
 mod message_handles;
 mod message_headers;
 mod message_or_envelope;
@@ -3,6 +3,7 @@ use nexus_actor_message_derive_rs::Message;
 use std::fmt::Display;
 
 #[derive(Debug, Clone, PartialEq, Eq, Message)]
+#[message(ignore_dead_letter_logging)]
 pub struct IgnoreDeadLetterLogging;
 
 impl IgnoreDeadLetterLogging {
@@ -1,8 +1,19 @@
 use nexus_actor_utils_rs::collections::DEFAULT_PRIORITY;
 use std::any::Any;
 use std::fmt::Debug;
+use std::sync::Arc;
 
-pub trait Message: Debug + Send + Sync + 'static {
+pub trait IntoAnyArc: Any + Send + Sync {
+  fn into_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Any + Send + Sync> IntoAnyArc for T {
+  fn into_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+    self
+  }
+}
+
+pub trait Message: Debug + IntoAnyArc + Send + Sync + 'static {
   fn get_priority(&self) -> i8 {
     DEFAULT_PRIORITY
   }
@@ -10,6 +21,14 @@ pub trait Message: Debug + Send + Sync + 'static {
   fn as_any(&self) -> &(dyn Any + Send + Sync + 'static);
 
   fn get_type_name(&self) -> String;
+
+  fn is_not_influence_receive_timeout(&self) -> bool {
+    false
+  }
+
+  fn is_ignore_dead_letter_logging(&self) -> bool {
+    false
+  }
 }
 
 impl Message for i8 {
@@ -220,7 +239,8 @@ impl Message for String {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use nexus_actor_message_derive_rs::Message;
+  use crate::actor::message::ResponseHandle;
+  use nexus_actor_message_derive_rs::{Message, Response};
   #[derive(Debug, Clone, PartialEq, Message)]
   pub struct Hello {
     pub who: String,
@@ -241,4 +261,42 @@ mod tests {
     assert!(msg1.eq_message(&msg2));
     assert!(!msg1.eq_message(&msg3));
   }
+
+  #[derive(Debug, Clone, Message)]
+  #[message(priority = 7, no_eq, not_influence_receive_timeout, ignore_dead_letter_logging)]
+  pub struct Tick {
+    pub count: u32,
+  }
+
+  #[test]
+  fn test_message_derive_attributes() {
+    let msg1 = Tick { count: 1 };
+    let msg2 = msg1.clone();
+
+    assert_eq!(msg1.get_priority(), 7);
+    assert!(msg1.eq_message(&msg1));
+    assert!(!msg1.eq_message(&msg2));
+    assert!(msg1.is_not_influence_receive_timeout());
+    assert!(msg1.is_ignore_dead_letter_logging());
+
+    let hello = Hello {
+      who: "World".to_string(),
+    };
+    assert_eq!(hello.get_priority(), DEFAULT_PRIORITY);
+    assert!(!hello.is_not_influence_receive_timeout());
+    assert!(!hello.is_ignore_dead_letter_logging());
+  }
+
+  #[derive(Debug, Clone, PartialEq, Message, Response)]
+  pub struct Pong {
+    pub count: u32,
+  }
+
+  #[test]
+  fn test_response_derive() {
+    let response: ResponseHandle = Pong { count: 3 }.into();
+    assert_eq!(response.to_typed_arc::<Pong>().unwrap().count, 3);
+    assert_eq!(response, ResponseHandle::from(Pong { count: 3 }));
+    assert_ne!(response, ResponseHandle::from(Pong { count: 4 }));
+  }
 }
@@ -1,4 +1,5 @@
 use crate::actor::message::message::Message;
+use crate::actor::message::response::ResponseHandle;
 use nexus_actor_utils_rs::collections::{Element, PriorityMessage};
 use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
@@ -27,6 +28,41 @@ impl MessageHandle {
     }
   }
 
+  pub fn to_typed_arc<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+    match self.0.clone().into_any_arc().downcast::<T>() {
+      Ok(msg) => Some(msg),
+      Err(msg) => msg
+        .downcast::<ResponseHandle>()
+        .ok()
+        .and_then(|response| response.to_typed_arc::<T>()),
+    }
+  }
+
+  // Like to_typed_arc, a response is taken out of its ResponseHandle.
+  pub fn try_take<T: Message>(self) -> Result<T, MessageHandle> {
+    if self.0.clone().into_any_arc().is::<ResponseHandle>() {
+      let response = self
+        .0
+        .into_any_arc()
+        .downcast::<ResponseHandle>()
+        .expect("type was checked above");
+      let response = Arc::try_unwrap(response).map_err(|response| MessageHandle(response))?;
+      return response.try_take::<T>().map_err(MessageHandle::new);
+    }
+    if !self.0.clone().into_any_arc().is::<T>() {
+      return Err(self);
+    }
+    let msg = self.0.into_any_arc().downcast::<T>().expect("type was checked above");
+    Arc::try_unwrap(msg).map_err(|msg| MessageHandle(msg))
+  }
+
+  pub fn take_or_clone<T: Message + Clone>(self) -> Option<T> {
+    match self.try_take::<T>() {
+      Ok(msg) => Some(msg),
+      Err(message_handle) => message_handle.to_typed::<T>(),
+    }
+  }
+
   pub fn as_typed<T: 'static>(&self) -> Option<&T> {
     self.0.as_any().downcast_ref::<T>()
   }
@@ -56,6 +92,14 @@ impl Message for MessageHandle {
   fn get_type_name(&self) -> String {
     self.0.get_type_name()
   }
+
+  fn is_not_influence_receive_timeout(&self) -> bool {
+    self.0.is_not_influence_receive_timeout()
+  }
+
+  fn is_ignore_dead_letter_logging(&self) -> bool {
+    self.0.is_ignore_dead_letter_logging()
+  }
 }
 
 impl PartialEq for MessageHandle {
@@ -0,0 +1,69 @@
+#[cfg(test)]
+mod tests {
+  use crate::actor::message::{Message, MessageHandle, ResponseHandle};
+  use nexus_actor_message_derive_rs::Message;
+  use std::sync::Arc;
+
+  #[derive(Debug, Clone, PartialEq, Message)]
+  struct Payload(Vec<u8>);
+
+  #[test]
+  fn test_to_typed_arc_shares_payload() {
+    let message_handle = MessageHandle::new(Payload(vec![1, 2, 3]));
+
+    let first = message_handle.to_typed_arc::<Payload>().unwrap();
+    let second = message_handle.to_typed_arc::<Payload>().unwrap();
+
+    assert!(Arc::ptr_eq(&first, &second));
+    assert!(std::ptr::eq(
+      first.as_ref(),
+      message_handle.as_typed::<Payload>().unwrap()
+    ));
+    assert!(message_handle.to_typed_arc::<String>().is_none());
+  }
+
+  #[test]
+  fn test_to_typed_arc_through_response_handle() {
+    let message_handle = MessageHandle::new(ResponseHandle::new(Payload(vec![1])));
+
+    assert_eq!(
+      message_handle.to_typed_arc::<Payload>().as_deref(),
+      Some(&Payload(vec![1]))
+    );
+  }
+
+  #[test]
+  fn test_try_take_moves_unique_payload() {
+    let message_handle = MessageHandle::new(Payload(vec![1, 2, 3]));
+    let data_ptr = message_handle.as_typed::<Payload>().unwrap().0.as_ptr();
+
+    let payload = message_handle.try_take::<Payload>().unwrap();
+
+    assert_eq!(payload.0.as_ptr(), data_ptr);
+  }
+
+  #[test]
+  fn test_try_take_through_response_handle() {
+    let message_handle = MessageHandle::new(ResponseHandle::new(Payload(vec![1, 2, 3])));
+    let shared = message_handle.clone();
+
+    let message_handle = message_handle.try_take::<Payload>().unwrap_err();
+    assert_eq!(message_handle.to_typed::<Payload>(), Some(Payload(vec![1, 2, 3])));
+    drop(shared);
+    let message_handle = message_handle.try_take::<String>().unwrap_err();
+    assert_eq!(message_handle.try_take::<Payload>().unwrap(), Payload(vec![1, 2, 3]));
+  }
+
+  #[test]
+  fn test_try_take_returns_handle_when_shared_or_mismatched() {
+    let message_handle = MessageHandle::new(Payload(vec![1, 2, 3]));
+    let shared = message_handle.clone();
+
+    let message_handle = message_handle.try_take::<Payload>().unwrap_err();
+    let message_handle = message_handle.try_take::<String>().unwrap_err();
+    assert_eq!(message_handle, shared);
+
+    drop(shared);
+    assert_eq!(message_handle.take_or_clone::<Payload>(), Some(Payload(vec![1, 2, 3])));
+  }
+}
@@ -4,16 +4,42 @@ use crate::actor::message::message_headers::MessageHeaders;
 use crate::actor::message::readonly_message_headers::ReadonlyMessageHeaders;
 use crate::actor::message::system_message::SystemMessage;
 use crate::actor::message::Message;
-use nexus_actor_message_derive_rs::Message;
+use std::any::Any;
 use std::fmt::Debug;
 
-#[derive(Debug, Clone, PartialEq, Message)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MessageEnvelope {
   header: Option<MessageHeaders>,
   message_handle: MessageHandle,
   sender: Option<ExtendedPid>,
 }
 
+// The markers belong to the wrapped message, so they are forwarded like ResponseHandle does.
+impl Message for MessageEnvelope {
+  fn eq_message(&self, other: &dyn Message) -> bool {
+    other
+      .as_any()
+      .downcast_ref::<Self>()
+      .map_or(false, |other| self == other)
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+    self
+  }
+
+  fn get_type_name(&self) -> String {
+    std::any::type_name_of_val(self).to_string()
+  }
+
+  fn is_not_influence_receive_timeout(&self) -> bool {
+    self.message_handle.is_not_influence_receive_timeout()
+  }
+
+  fn is_ignore_dead_letter_logging(&self) -> bool {
+    self.message_handle.is_ignore_dead_letter_logging()
+  }
+}
+
 impl MessageEnvelope {
   pub fn new(message_handle: MessageHandle) -> Self {
     if message_handle.as_any().is::<SystemMessage>() {
@@ -67,7 +93,7 @@ impl MessageEnvelope {
 }
 
 pub fn wrap_envelope(message_handle: MessageHandle) -> MessageEnvelope {
-  if let Some(envelope) = message_handle.to_typed::<MessageEnvelope>() {
+  if let Some(envelope) = message_handle.as_typed::<MessageEnvelope>() {
     envelope.clone()
   } else {
     MessageEnvelope::new(message_handle)
@@ -75,7 +101,7 @@ pub fn wrap_envelope(message_handle: MessageHandle) -> MessageEnvelope {
 }
 
 pub fn unwrap_envelope(message_handle: MessageHandle) -> (Option<MessageHeaders>, MessageHandle, Option<ExtendedPid>) {
-  if let Some(envelope) = message_handle.to_typed::<MessageEnvelope>() {
+  if let Some(envelope) = message_handle.as_typed::<MessageEnvelope>() {
     (
       envelope.header.clone(),
       envelope.message_handle.clone(),
@@ -87,7 +113,7 @@ pub fn unwrap_envelope(message_handle: MessageHandle) -> (Option<MessageHeaders>
 }
 
 pub fn unwrap_envelope_header(message_handle: MessageHandle) -> Option<MessageHeaders> {
-  if let Some(envelope) = message_handle.to_typed::<MessageEnvelope>() {
+  if let Some(envelope) = message_handle.as_typed::<MessageEnvelope>() {
     envelope.header.clone().map(|h| MessageHeaders::with_values(h.to_map()))
   } else {
     None
@@ -95,7 +121,7 @@ pub fn unwrap_envelope_header(message_handle: MessageHandle) -> Option<MessageHe
 }
 
 pub fn unwrap_envelope_message(message_handle: MessageHandle) -> MessageHandle {
-  if let Some(envelope) = message_handle.to_typed::<MessageEnvelope>() {
+  if let Some(envelope) = message_handle.as_typed::<MessageEnvelope>() {
     envelope.message_handle.clone()
   } else {
     message_handle
@@ -103,7 +129,7 @@ pub fn unwrap_envelope_message(message_handle: MessageHandle) -> MessageHandle {
 }
 
 pub fn unwrap_envelope_sender(message_handle: MessageHandle) -> Option<ExtendedPid> {
-  if let Some(envelope) = message_handle.to_typed::<MessageEnvelope>() {
+  if let Some(envelope) = message_handle.as_typed::<MessageEnvelope>() {
     envelope.sender.clone()
   } else {
     None
@@ -8,6 +8,7 @@ mod test {
   use crate::actor::context::{BasePart, MessagePart, SenderPart, SpawnerPart};
   use crate::actor::message::message::Message;
   use crate::actor::message::message_handle::MessageHandle;
+  use crate::actor::message::message_or_envelope::MessageEnvelope;
   use crate::actor::message::readonly_message_headers::ReadonlyMessageHeaders;
   use crate::actor::message::response::ResponseHandle;
   use nexus_actor_message_derive_rs::Message;
@@ -16,6 +17,22 @@ mod test {
   #[derive(Debug, Clone, PartialEq, Eq, Message)]
   pub struct Length(pub usize);
 
+  #[derive(Debug, Clone, PartialEq, Eq, Message)]
+  #[message(not_influence_receive_timeout, ignore_dead_letter_logging)]
+  pub struct Tick;
+
+  #[test]
+  fn test_envelope_forwards_the_message_markers() {
+    let envelope = MessageEnvelope::new(MessageHandle::new(Tick));
+    assert!(envelope.is_not_influence_receive_timeout());
+    assert!(envelope.is_ignore_dead_letter_logging());
+    assert!(MessageHandle::new(envelope).is_not_influence_receive_timeout());
+
+    let envelope = MessageEnvelope::new(MessageHandle::new(Length(1)));
+    assert!(!envelope.is_not_influence_receive_timeout());
+    assert!(!envelope.is_ignore_dead_letter_logging());
+  }
+
   #[tokio::test]
   async fn test_normal_message_gives_empty_message_headers() {
     let _ = env::set_var("RUST_LOG", "debug");
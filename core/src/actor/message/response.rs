@@ -25,6 +25,18 @@ impl ResponseHandle {
   pub fn new(response: impl Response + 'static) -> Self {
     ResponseHandle(Arc::new(response))
   }
+
+  pub fn to_typed_arc<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+    self.0.clone().into_any_arc().downcast::<T>().ok()
+  }
+
+  pub fn try_take<T: Message>(self) -> Result<T, ResponseHandle> {
+    if !self.0.clone().into_any_arc().is::<T>() {
+      return Err(self);
+    }
+    let response = self.0.into_any_arc().downcast::<T>().expect("type was checked above");
+    Arc::try_unwrap(response).map_err(|response| ResponseHandle(response))
+  }
 }
 
 impl PartialEq for ResponseHandle {
@@ -53,4 +65,12 @@ impl Message for ResponseHandle {
   fn get_type_name(&self) -> String {
     self.0.get_type_name()
   }
+
+  fn is_not_influence_receive_timeout(&self) -> bool {
+    self.0.is_not_influence_receive_timeout()
+  }
+
+  fn is_ignore_dead_letter_logging(&self) -> bool {
+    self.0.is_ignore_dead_letter_logging()
+  }
 }
@@ -158,6 +158,28 @@ impl ProcessRegistry {
     self.get_local_process(pid.id()).await
   }
 
+  // Unlike `get_local_process`, returns `None` instead of the dead letter process for unknown ids.
+  pub fn find_local_process(&self, id: &str) -> Option<ProcessHandle> {
+    self.local_pids.get_bucket(id).get(id).map(|r| r.clone())
+  }
+
+  pub async fn list_local_pids(&self) -> Vec<ExtendedPid> {
+    let address = self.get_address().await;
+    self
+      .local_pids
+      .local_pids
+      .iter()
+      .flat_map(|bucket| bucket.iter().map(|entry| entry.key().clone()).collect::<Vec<_>>())
+      .map(|id| {
+        ExtendedPid::new(Pid {
+          address: address.clone(),
+          id,
+          request_id: 0,
+        })
+      })
+      .collect()
+  }
+
   pub async fn get_local_process(&self, id: &str) -> Option<ProcessHandle> {
     let bucket = self.local_pids.get_bucket(id);
     let result = bucket.get(id);
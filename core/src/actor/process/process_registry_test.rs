@@ -1,7 +1,13 @@
 #[cfg(test)]
 mod tests {
+  use crate::actor::actor::{ActorProcess, Props};
+  use crate::actor::actor_system::ActorSystem;
+  use crate::actor::context::{MessagePart, SenderPart, SpawnerPart};
+  use crate::actor::message::MessageHandle;
   use crate::actor::process::process_registry::uint64_to_id;
-  use std::time::Instant;
+  use crate::actor::process::Process;
+  use std::time::{Duration, Instant};
+  use tokio::time::sleep;
 
   const ITERATIONS: u32 = 1_000_000; // 適切な反復回数に調整してください
 
@@ -15,4 +21,46 @@ mod tests {
     let duration = start.elapsed();
     tracing::debug!("uint64_to_id: {:?}, last result: {}", duration, s);
   }
+
+  #[tokio::test]
+  async fn test_list_local_pids_and_diagnostics() {
+    let system = ActorSystem::new().await.unwrap();
+    let mut root_context = system.get_root_context().await;
+    let child_props = Props::from_async_actor_receiver(|_| async { Ok(()) }).await;
+    let parent_props = Props::from_async_actor_receiver(move |mut ctx| {
+      let child_props = child_props.clone();
+      async move {
+        if ctx.get_message_handle().await.to_typed::<String>().is_some() {
+          ctx.spawn_named(child_props, "child").await.unwrap();
+        }
+        Ok(())
+      }
+    })
+    .await;
+    let parent = root_context.spawn_named(parent_props, "parent").await.unwrap();
+    root_context
+      .send(parent.clone(), MessageHandle::new("spawn".to_string()))
+      .await;
+    sleep(Duration::from_millis(100)).await;
+
+    let registry = system.get_process_registry().await;
+    let ids = registry
+      .list_local_pids()
+      .await
+      .into_iter()
+      .map(|pid| pid.id().to_string())
+      .collect::<Vec<_>>();
+    assert!(ids.contains(&"parent".to_string()));
+    assert!(ids.contains(&"parent/child".to_string()));
+    assert!(registry.find_local_process("missing").is_none());
+
+    let process = registry.find_local_process("parent").unwrap();
+    let actor_process = process.as_any().downcast_ref::<ActorProcess>().unwrap();
+    let diagnostics = actor_process.get_diagnostics().await;
+    assert_eq!(diagnostics.pid, Some(parent));
+    assert!(diagnostics.actor_type.ends_with("ActorReceiverActor"));
+    assert_eq!(diagnostics.children.len(), 1);
+    assert_eq!(diagnostics.children[0].id(), "parent/child");
+    assert_eq!(diagnostics.behavior_stack.len(), 1);
+  }
 }
@@ -7,6 +7,8 @@ mod strategy_one_for_one_test;
 mod strategy_restarting;
 mod supervision_event;
 mod supervision_event_test;
+mod supervision_history;
+mod supervision_history_test;
 mod supervision_test;
 mod supervisor_strategy;
 mod supervisor_strategy_handle;
@@ -14,5 +16,5 @@ mod supervisor_strategy_handle;
 pub use {
   self::directive::*, self::exponential_backoff_strategy::*, self::strategy_all_for_one::*,
   self::strategy_one_for_one::*, self::strategy_restarting::*, self::supervision_event::*,
-  self::supervisor_strategy::*, self::supervisor_strategy_handle::*,
+  self::supervision_history::*, self::supervisor_strategy::*, self::supervisor_strategy_handle::*,
 };
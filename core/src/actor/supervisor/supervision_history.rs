@@ -0,0 +1,195 @@
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use dashmap::DashMap;
+use nexus_actor_message_derive_rs::Message;
+use once_cell::sync::Lazy;
+use opentelemetry::KeyValue;
+
+use crate::actor::actor::ErrorReason;
+use crate::actor::actor::ExtendedPid;
+use crate::actor::actor_system::ActorSystem;
+use crate::actor::message::Message;
+use crate::actor::message::MessageHandle;
+use crate::actor::metrics::metrics_impl::{Metrics, EXTENSION_ID as METRICS_EXTENSION_ID};
+use crate::actor::supervisor::directive::Directive;
+use crate::actor::supervisor::supervision_event::SupervisorEvent;
+use crate::event_stream::Subscription;
+use crate::extensions::{next_extension_id, Extension, ExtensionId};
+use crate::metrics::ActorMetrics;
+
+pub static EXTENSION_ID: Lazy<ExtensionId> = Lazy::new(next_extension_id);
+
+#[derive(Debug, Clone)]
+pub struct RestartRecord {
+  pub timestamp: SystemTime,
+  pub reason: ErrorReason,
+  pub directive: Directive,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub struct RestartStormEvent {
+  pub child: ExtendedPid,
+  pub restart_count: u32,
+  pub window: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct SupervisionHistory {
+  actor_system: ActorSystem,
+  records: Arc<DashMap<ExtendedPid, VecDeque<RestartRecord>>>,
+  history_size: usize,
+  restart_storm_threshold: u32,
+  restart_storm_window: Duration,
+}
+
+impl Extension for SupervisionHistory {
+  fn extension_id(&self) -> ExtensionId {
+    *EXTENSION_ID
+  }
+
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn as_any_mut(&mut self) -> &mut dyn Any {
+    self
+  }
+}
+
+impl SupervisionHistory {
+  pub async fn new(actor_system: ActorSystem) -> Self {
+    let config = actor_system.get_config().await;
+    Self {
+      actor_system,
+      records: Arc::new(DashMap::new()),
+      history_size: config.supervision_history_size,
+      restart_storm_threshold: config.restart_storm_threshold,
+      restart_storm_window: config.restart_storm_window,
+    }
+  }
+
+  pub(crate) async fn subscribe(&self) -> Subscription {
+    let cloned_self = self.clone();
+    self
+      .actor_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let cloned_self = cloned_self.clone();
+        let evt = evt.as_any().downcast_ref::<SupervisorEvent>().cloned();
+        async move {
+          if let Some(supervisor_event) = evt {
+            cloned_self.record(supervisor_event).await;
+          }
+        }
+      })
+      .await
+  }
+
+  pub fn get_history(&self, pid: &ExtendedPid) -> Vec<RestartRecord> {
+    self
+      .records
+      .get(pid)
+      .map(|records| records.iter().cloned().collect())
+      .unwrap_or_default()
+  }
+
+  pub fn get_restart_count(&self, pid: &ExtendedPid, within_duration: Duration) -> u32 {
+    self
+      .records
+      .get(pid)
+      .map(|records| Self::count_restarts(&records, within_duration))
+      .unwrap_or(0)
+  }
+
+  pub fn get_pids(&self) -> Vec<ExtendedPid> {
+    self.records.iter().map(|entry| entry.key().clone()).collect()
+  }
+
+  pub fn clear(&self, pid: &ExtendedPid) {
+    self.records.remove(pid);
+  }
+
+  async fn record(&self, event: SupervisorEvent) {
+    let directive = event.directive;
+    let (before, after) = {
+      let mut records = self.records.entry(event.child.clone()).or_default();
+      let before = Self::count_restarts(&records, self.restart_storm_window);
+      records.push_back(RestartRecord {
+        timestamp: SystemTime::now(),
+        reason: event.reason,
+        directive,
+      });
+      while records.len() > self.history_size {
+        records.pop_front();
+      }
+      (before, Self::count_restarts(&records, self.restart_storm_window))
+    };
+
+    self
+      .metrics_foreach(|am, _| {
+        let am = am.clone();
+        async move {
+          am.increment_supervisor_directive_count_with_opts(&[KeyValue::new("directive", directive.as_str())])
+            .await;
+        }
+      })
+      .await;
+
+    if self.restart_storm_threshold > 0
+      && before < self.restart_storm_threshold
+      && after >= self.restart_storm_threshold
+    {
+      tracing::warn!(
+        "[SUPERVISION]: restart storm detected: child = {}, restarts = {}, window = {:?}",
+        event.child,
+        after,
+        self.restart_storm_window
+      );
+      let event_stream = self.actor_system.get_event_stream().await;
+      let storm_event = RestartStormEvent {
+        child: event.child,
+        restart_count: after,
+        window: self.restart_storm_window,
+      };
+      // The event stream is still publishing the SupervisorEvent, so publish asynchronously.
+      tokio::spawn(async move {
+        event_stream.publish(MessageHandle::new(storm_event)).await;
+      });
+    }
+  }
+
+  fn count_restarts(records: &VecDeque<RestartRecord>, within_duration: Duration) -> u32 {
+    let now = SystemTime::now();
+    records
+      .iter()
+      .filter(|r| r.directive == Directive::Restart)
+      .filter(|r| {
+        within_duration == Duration::ZERO || now.duration_since(r.timestamp).unwrap_or_default() < within_duration
+      })
+      .count() as u32
+  }
+
+  async fn metrics_foreach<F, Fut>(&self, f: F)
+  where
+    F: Fn(&ActorMetrics, &Metrics) -> Fut,
+    Fut: std::future::Future<Output = ()>, {
+    if self.actor_system.get_config().await.is_metrics_enabled() {
+      if let Some(extension_arc) = self
+        .actor_system
+        .get_extensions()
+        .await
+        .get(*METRICS_EXTENSION_ID)
+        .await
+      {
+        let mut extension = extension_arc.lock().await;
+        if let Some(m) = extension.as_any_mut().downcast_mut::<Metrics>() {
+          m.foreach(f).await;
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use async_trait::async_trait;
+  use tokio::sync::mpsc;
+  use tokio::time::sleep;
+
+  use crate::actor::actor::Actor;
+  use crate::actor::actor::ActorError;
+  use crate::actor::actor::ErrorReason;
+  use crate::actor::actor::Props;
+  use crate::actor::actor_system::ActorSystem;
+  use crate::actor::context::ContextHandle;
+  use crate::actor::context::{MessagePart, SenderPart, SpawnerPart, StopperPart};
+  use crate::actor::message::Message;
+  use crate::actor::message::MessageHandle;
+  use crate::actor::supervisor::directive::Directive;
+  use crate::actor::supervisor::strategy_one_for_one::OneForOneStrategy;
+  use crate::actor::supervisor::supervision_history::RestartStormEvent;
+  use crate::actor::supervisor::supervisor_strategy_handle::SupervisorStrategyHandle;
+  use crate::actor::ConfigOption;
+
+  #[derive(Debug)]
+  struct PanicActor;
+
+  #[async_trait]
+  impl Actor for PanicActor {
+    async fn receive(&mut self, ctx: ContextHandle) -> Result<(), ActorError> {
+      if ctx.get_message_handle().await.to_typed::<String>().is_some() {
+        Err(ActorError::ReceiveError(ErrorReason::new("Boom!".to_string(), 0)))
+      } else {
+        Ok(())
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_supervision_history_records_restarts_and_raises_storm() {
+    let system = ActorSystem::new_config_options([
+      ConfigOption::with_restart_storm_threshold(3),
+      ConfigOption::with_restart_storm_window(Duration::from_secs(10)),
+    ])
+    .await
+    .unwrap();
+    let (tx, mut rx) = mpsc::channel(10);
+
+    system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<RestartStormEvent>().cloned();
+        async move {
+          if let Some(storm) = evt {
+            tx.try_send(storm).unwrap();
+          }
+        }
+      })
+      .await;
+
+    let strategy = SupervisorStrategyHandle::new(OneForOneStrategy::new(10, Duration::from_secs(10)));
+    let props = Props::from_async_actor_producer_with_opts(
+      move |_| async { PanicActor },
+      [Props::with_supervisor_strategy(strategy)],
+    )
+    .await;
+
+    let mut root_context = system.get_root_context().await;
+    let pid = root_context.spawn(props).await;
+
+    for _ in 0..3 {
+      root_context
+        .send(pid.clone(), MessageHandle::new("Fail!".to_string()))
+        .await;
+    }
+
+    let storm = tokio::select! {
+        storm = rx.recv() => storm.unwrap(),
+        _ = sleep(Duration::from_secs(5)) => {
+            panic!("Timeout waiting for RestartStormEvent");
+        }
+    };
+    assert_eq!(storm.child, pid);
+    assert_eq!(storm.restart_count, 3);
+
+    let history = system.get_supervision_history().await.unwrap();
+    let records = history.get_history(&pid);
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|r| r.directive == Directive::Restart));
+    assert_eq!(history.get_restart_count(&pid, Duration::from_secs(10)), 3);
+  }
+
+  #[tokio::test]
+  async fn test_supervision_history_is_cleared_when_the_actor_stops() {
+    let system = ActorSystem::new().await.unwrap();
+    let props = Props::from_async_actor_producer(move |_| async { PanicActor }).await;
+    let mut root_context = system.get_root_context().await;
+    let pid = root_context.spawn(props).await;
+
+    root_context
+      .send(pid.clone(), MessageHandle::new("Fail!".to_string()))
+      .await;
+    let history = system.get_supervision_history().await.unwrap();
+    for _ in 0..50 {
+      if !history.get_history(&pid).is_empty() {
+        break;
+      }
+      sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(history.get_history(&pid).len(), 1);
+
+    root_context.stop_future(&pid).await.result().await.unwrap();
+    assert!(history.get_history(&pid).is_empty());
+    assert!(!history.get_pids().contains(&pid));
+  }
+}
@@ -3,3 +3,4 @@ pub mod actor {
 }
 pub mod actor_impl;
 mod poison_pill;
+mod stop;
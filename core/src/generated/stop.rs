@@ -0,0 +1,18 @@
+use crate::actor::message::Message;
+use crate::generated::actor::Stop;
+use std::any::Any;
+
+impl Message for Stop {
+  fn eq_message(&self, other: &dyn Message) -> bool {
+    let msg = other.as_any().downcast_ref::<Stop>();
+    matches!((self, msg), (Stop {}, Some(Stop {})))
+  }
+
+  fn as_any(&self) -> &(dyn Any + Send + Sync + 'static) {
+    self
+  }
+
+  fn get_type_name(&self) -> String {
+    std::any::type_name_of_val(self).to_string()
+  }
+}
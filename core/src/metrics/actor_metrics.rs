@@ -20,6 +20,7 @@ struct ActorMetricsInner {
   futures_started_count: Counter<u64>,
   futures_completed_count: Counter<u64>,
   futures_timed_out_count: Counter<u64>,
+  supervisor_directive_count: Counter<u64>,
   thread_pool_latency: Histogram<f64>,
 }
 
@@ -85,6 +86,11 @@ impl ActorMetrics {
           .with_description("Number of futures timed out")
           .with_unit("1")
           .try_init()?,
+        supervisor_directive_count: meter
+          .u64_counter("nexus_actor_supervisor_directive_count")
+          .with_description("Number of supervisor directives applied")
+          .with_unit("1")
+          .try_init()?,
         thread_pool_latency: meter
           .f64_histogram("nexus_actor_thread_pool_latency_duration_seconds")
           .with_description("History of latency in seconds")
@@ -186,6 +192,15 @@ impl ActorMetrics {
     let inner_mg = self.inner.lock().await;
     inner_mg.futures_timed_out_count.add(1, attributes);
   }
+
+  pub async fn increment_supervisor_directive_count(&self) {
+    self.increment_supervisor_directive_count_with_opts(&[]).await;
+  }
+
+  pub async fn increment_supervisor_directive_count_with_opts(&self, attributes: &[KeyValue]) {
+    let inner_mg = self.inner.lock().await;
+    inner_mg.supervisor_directive_count.add(1, attributes);
+  }
 }
 
 #[cfg(test)]
@@ -209,5 +224,6 @@ mod tests {
     metrics.increment_futures_started_count().await;
     metrics.increment_futures_completed_count().await;
     metrics.increment_futures_timed_out_count().await;
+    metrics.increment_supervisor_directive_count().await;
   }
 }
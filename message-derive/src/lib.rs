@@ -1,18 +1,98 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, DeriveInput, Ident, LitInt, LitStr};
 
-#[proc_macro_derive(Message)]
+#[derive(Default)]
+struct MessageAttributes {
+  priority: Option<LitInt>,
+  no_eq: bool,
+  not_influence_receive_timeout: bool,
+  ignore_dead_letter_logging: bool,
+}
+
+impl MessageAttributes {
+  fn parse(input: &DeriveInput) -> syn::Result<Self> {
+    let mut attributes = MessageAttributes::default();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("message")) {
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("priority") {
+          attributes.priority = Some(meta.value()?.parse::<LitInt>()?);
+          Ok(())
+        } else if meta.path.is_ident("no_eq") {
+          attributes.no_eq = true;
+          Ok(())
+        } else if meta.path.is_ident("not_influence_receive_timeout") {
+          attributes.not_influence_receive_timeout = true;
+          Ok(())
+        } else if meta.path.is_ident("ignore_dead_letter_logging") {
+          attributes.ignore_dead_letter_logging = true;
+          Ok(())
+        } else {
+          Err(meta.error("unsupported message attribute"))
+        }
+      })?;
+    }
+    Ok(attributes)
+  }
+}
+
+#[proc_macro_derive(Message, attributes(message))]
 pub fn derive_message(input: TokenStream) -> TokenStream {
   let input = parse_macro_input!(input as DeriveInput);
   let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  let attributes = match MessageAttributes::parse(&input) {
+    Ok(attributes) => attributes,
+    Err(err) => return err.to_compile_error().into(),
+  };
+
+  let eq_message = if attributes.no_eq {
+    quote! {
+        fn eq_message(&self, other: &dyn Message) -> bool {
+            other.as_any().downcast_ref::<Self>()
+                .map_or(false, |other| std::ptr::eq(self, other))
+        }
+    }
+  } else {
+    quote! {
+        fn eq_message(&self, other: &dyn Message) -> bool {
+            other.as_any().downcast_ref::<Self>()
+                .map_or(false, |other| self == other)
+        }
+    }
+  };
+
+  let get_priority = attributes.priority.map(|priority| {
+    quote! {
+        fn get_priority(&self) -> i8 {
+            #priority
+        }
+    }
+  });
+
+  let not_influence_receive_timeout = attributes.not_influence_receive_timeout.then(|| {
+    quote! {
+        fn is_not_influence_receive_timeout(&self) -> bool {
+            true
+        }
+    }
+  });
+
+  let ignore_dead_letter_logging = attributes.ignore_dead_letter_logging.then(|| {
+    quote! {
+        fn is_ignore_dead_letter_logging(&self) -> bool {
+            true
+        }
+    }
+  });
 
   let expanded = quote! {
-      impl Message for #name {
-          fn eq_message(&self, other: &dyn Message) -> bool {
-              other.as_any().downcast_ref::<Self>()
-                  .map_or(false, |other| self == other)
-          }
+      impl #impl_generics Message for #name #ty_generics #where_clause {
+          #get_priority
+
+          #eq_message
 
           fn as_any(&self) -> &(dyn std::any::Any + Send + Sync + 'static) {
               self
@@ -21,6 +101,119 @@ pub fn derive_message(input: TokenStream) -> TokenStream {
           fn get_type_name(&self) -> String {
               std::any::type_name_of_val(self).to_string()
           }
+
+          #not_influence_receive_timeout
+
+          #ignore_dead_letter_logging
+      }
+  };
+
+  TokenStream::from(expanded)
+}
+
+// Conversion into a ResponseHandle, so the type can be passed to `respond` with `into()`.
+// `Response` itself is implemented for every `Message`.
+#[proc_macro_derive(Response)]
+pub fn derive_response(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  let expanded = quote! {
+      impl #impl_generics From<#name #ty_generics> for ResponseHandle #where_clause {
+          fn from(response: #name #ty_generics) -> Self {
+              ResponseHandle::new(response)
+          }
+      }
+  };
+
+  TokenStream::from(expanded)
+}
+
+#[derive(Default)]
+struct SerializerAttributes {
+  // Registration methods of SerializerRegistry, the first one registers the default serializer.
+  register_fns: Vec<Ident>,
+  manifest: Option<(LitStr, LitInt)>,
+  aliases: Vec<LitStr>,
+}
+
+impl SerializerAttributes {
+  fn parse(input: &DeriveInput) -> syn::Result<Self> {
+    let mut attributes = SerializerAttributes::default();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("serializer")) {
+      let mut manifest = None;
+      let mut version = None;
+      attr.parse_nested_meta(|meta| {
+        let register_fn = ["proto", "json", "message_pack", "cbor", "bincode"]
+          .into_iter()
+          .find(|serializer| meta.path.is_ident(serializer));
+        if let Some(serializer) = register_fn {
+          attributes
+            .register_fns
+            .push(Ident::new(&format!("register_{}", serializer), meta.path.span()));
+          Ok(())
+        } else if meta.path.is_ident("manifest") {
+          manifest = Some(meta.value()?.parse::<LitStr>()?);
+          Ok(())
+        } else if meta.path.is_ident("version") {
+          version = Some(meta.value()?.parse::<LitInt>()?);
+          Ok(())
+        } else if meta.path.is_ident("alias") {
+          attributes.aliases.push(meta.value()?.parse::<LitStr>()?);
+          Ok(())
+        } else {
+          Err(meta.error("unsupported serializer attribute"))
+        }
+      })?;
+      match (manifest, version) {
+        (Some(manifest), version) => {
+          let version = version.unwrap_or_else(|| LitInt::new("1", manifest.span()));
+          attributes.manifest = Some((manifest, version));
+        }
+        (None, Some(version)) => return Err(syn::Error::new(version.span(), "version requires a manifest")),
+        (None, None) => {}
+      }
+    }
+    if attributes.register_fns.is_empty() {
+      return Err(syn::Error::new(
+        input.ident.span(),
+        "expected #[serializer(proto | json | message_pack | cbor | bincode, ..)]",
+      ));
+    }
+    Ok(attributes)
+  }
+}
+
+// Registers the type with a SerializerRegistry, for example
+// `#[serializer(json, manifest = "greeting", version = 2, alias = "old::Greeting")]`.
+#[proc_macro_derive(SerializerRegistration, attributes(serializer))]
+pub fn derive_serializer_registration(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  let attributes = match SerializerAttributes::parse(&input) {
+    Ok(attributes) => attributes,
+    Err(err) => return err.to_compile_error().into(),
+  };
+
+  let register_fns = attributes.register_fns;
+  let manifest = attributes.manifest.map(|(manifest, version)| {
+    quote! {
+        registry.register_manifest::<Self>(#manifest, #version);
+    }
+  });
+  let aliases = attributes.aliases;
+
+  let expanded = quote! {
+      impl #impl_generics SerializerRegistration for #name #ty_generics #where_clause {
+          fn register_serializers(registry: &SerializerRegistry) -> Result<(), SerializerError> {
+              #(registry.#register_fns::<Self>()?;)*
+              #manifest
+              #(registry.register_alias::<Self>(#aliases);)*
+              Ok(())
+          }
       }
   };
 
@@ -1,7 +1,7 @@
 // This file is @generated by prost-build.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RemoteMessage {
-    #[prost(oneof = "remote_message::MessageType", tags = "1, 2, 3, 4")]
+    #[prost(oneof = "remote_message::MessageType", tags = "1, 2, 3, 4, 5")]
     pub message_type: ::core::option::Option<remote_message::MessageType>,
 }
 /// Nested message and enum types in `RemoteMessage`.
@@ -16,6 +16,8 @@ pub mod remote_message {
         ConnectResponse(super::ConnectResponse),
         #[prost(message, tag = "4")]
         DisconnectRequest(super::DisconnectRequest),
+        #[prost(message, tag = "5")]
+        MessageAck(super::MessageAck),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -28,6 +30,9 @@ pub struct MessageBatch {
     pub envelopes: ::prost::alloc::vec::Vec<MessageEnvelope>,
     #[prost(message, repeated, tag = "4")]
     pub senders: ::prost::alloc::vec::Vec<super::actor::Pid>,
+    /// Identifies the sending EndpointWriter; sequence numbers are unique within a session.
+    #[prost(uint64, tag = "5")]
+    pub session_id: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MessageEnvelope {
@@ -47,6 +52,17 @@ pub struct MessageEnvelope {
     pub target_request_id: u32,
     #[prost(uint32, tag = "8")]
     pub sender_request_id: u32,
+    #[prost(uint32, tag = "9")]
+    pub compression: u32,
+    /// 0 for fire-and-forget envelopes, otherwise acknowledged by the receiver.
+    #[prost(uint64, tag = "10")]
+    pub sequence_number: u64,
+}
+/// Acknowledges every reliable envelope of a batch, identified by its highest sequence number.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct MessageAck {
+    #[prost(uint64, tag = "1")]
+    pub sequence_number: u64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MessageHeader {
@@ -62,6 +78,18 @@ pub struct ActorPidRequest {
     pub name: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub kind: ::prost::alloc::string::String,
+    /// Delivered to the spawned actor as its first message.
+    #[prost(message, optional, tag = "3")]
+    pub init: ::core::option::Option<SpawnInit>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SpawnInit {
+    #[prost(string, tag = "1")]
+    pub type_name: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub serializer_id: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ActorPidResponse {
@@ -91,6 +119,10 @@ pub struct DisconnectRequest {}
 pub struct ClientConnection {
     #[prost(string, tag = "1")]
     pub system_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub protocol_version: u32,
+    #[prost(string, tag = "3")]
+    pub auth_token: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ServerConnection {
@@ -98,6 +130,12 @@ pub struct ServerConnection {
     pub system_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub address: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub protocol_version: u32,
+    #[prost(string, tag = "4")]
+    pub auth_token: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "5")]
+    pub compressions: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ConnectResponse {
@@ -105,6 +143,12 @@ pub struct ConnectResponse {
     pub member_id: ::prost::alloc::string::String,
     #[prost(bool, tag = "3")]
     pub blocked: bool,
+    #[prost(uint32, tag = "4")]
+    pub protocol_version: u32,
+    #[prost(string, tag = "5")]
+    pub rejection_reason: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub compression: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ListProcessesRequest {
@@ -128,6 +172,24 @@ pub struct GetProcessDiagnosticsResponse {
     #[prost(string, tag = "1")]
     pub diagnostics_string: ::prost::alloc::string::String,
 }
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListKindsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KindInfo {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Empty when the kind is not versioned.
+    #[prost(string, tag = "2")]
+    pub version: ::prost::alloc::string::String,
+    /// 0 when the number of instances is not limited.
+    #[prost(uint32, tag = "3")]
+    pub max_instances: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListKindsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub kinds: ::prost::alloc::vec::Vec<KindInfo>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum ListProcessesMatchType {
@@ -317,6 +379,29 @@ pub mod remoting_client {
                 .insert(GrpcMethod::new("remote.Remoting", "GetProcessDiagnostics"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn list_kinds(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListKindsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListKindsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/remote.Remoting/ListKinds",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("remote.Remoting", "ListKinds"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -356,6 +441,13 @@ pub mod remoting_server {
             tonic::Response<super::GetProcessDiagnosticsResponse>,
             tonic::Status,
         >;
+        async fn list_kinds(
+            &self,
+            request: tonic::Request<super::ListKindsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListKindsResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct RemotingServer<T> {
@@ -572,6 +664,51 @@ pub mod remoting_server {
                     };
                     Box::pin(fut)
                 }
+                "/remote.Remoting/ListKinds" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListKindsSvc<T: Remoting>(pub Arc<T>);
+                    impl<
+                        T: Remoting,
+                    > tonic::server::UnaryService<super::ListKindsRequest>
+                    for ListKindsSvc<T> {
+                        type Response = super::ListKindsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListKindsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Remoting>::list_kinds(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListKindsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         let mut response = http::Response::new(empty_body());
@@ -1,19 +1,123 @@
-use crate::generated::remote::{ActorPidRequest, ActorPidResponse};
+use crate::config::activation_rate_limit::ActivationRateLimit;
+use crate::generated::remote::{ActorPidRequest, ActorPidResponse, SpawnInit};
 use crate::messages::{Ping, Pong};
 use crate::remote::Remote;
+use crate::remote_metrics::ActivationRejection;
 use crate::response_status_code::ResponseStatusCode;
+use crate::serializer::{SerializerError, SerializerId};
 use async_trait::async_trait;
 use dashmap::DashMap;
-use nexus_actor_core_rs::actor::actor::{Actor, ActorError, ErrorReason, ExtendedPid, Props, SpawnError};
+use nexus_actor_core_rs::actor::actor::{Actor, ActorError, ExtendedPid, Props, SpawnError};
 use nexus_actor_core_rs::actor::actor_system::ActorSystem;
 use nexus_actor_core_rs::actor::context::{BasePart, ContextHandle, InfoPart, MessagePart, SenderPart, SpawnerPart};
 use nexus_actor_core_rs::actor::dispatch::future::{ActorFuture, ActorFutureError};
 use nexus_actor_core_rs::actor::message::{MessageHandle, ResponseHandle};
-use nexus_actor_core_rs::generated::actor::Pid;
-use std::sync::Weak;
-use std::time::Duration;
+use nexus_actor_core_rs::generated::actor::{Pid, Terminated};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+// Actors spawned by the Activator and the quotas it enforces, kept by the Remote so that they survive restarts of
+// the Activator.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ActivationState {
+  // Kind of each live activated actor, by process id.
+  activated_kinds: Arc<DashMap<String, String>>,
+  counts: Arc<DashMap<String, Arc<AtomicUsize>>>,
+  rate_limit: Option<ActivationRateLimit>,
+  // Requests left to each peer and when they were last refilled, by the peer key of the EndpointReader.
+  buckets: Arc<DashMap<String, (f64, Instant)>>,
+  // When idle buckets were last dropped.
+  swept_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl ActivationState {
+  pub(crate) fn new(rate_limit: Option<ActivationRateLimit>) -> Self {
+    ActivationState {
+      rate_limit,
+      ..Self::default()
+    }
+  }
+
+  pub(crate) fn get_counts(&self) -> Arc<DashMap<String, Arc<AtomicUsize>>> {
+    self.counts.clone()
+  }
+
+  pub(crate) fn get_count(&self, kind: &str) -> usize {
+    self.counts.get(kind).map_or(0, |count| count.load(Ordering::Relaxed))
+  }
+
+  // Takes one request from the bucket of `peer`, refilling it for the time passed since.
+  pub(crate) fn try_acquire(&self, peer: &str) -> bool {
+    let Some(rate_limit) = &self.rate_limit else {
+      return true;
+    };
+    let burst = rate_limit.burst as f64;
+    let now = Instant::now();
+    self.sweep_idle_buckets(rate_limit, now);
+    let mut bucket = self.buckets.entry(peer.to_string()).or_insert((burst, now));
+    let (tokens, refilled_at) = bucket.value_mut();
+    let refill = if rate_limit.refill_interval.is_zero() {
+      burst
+    } else {
+      now.duration_since(*refilled_at).as_secs_f64() / rate_limit.refill_interval.as_secs_f64()
+    };
+    *tokens = (*tokens + refill).min(burst);
+    *refilled_at = now;
+    if *tokens >= 1.0 {
+      *tokens -= 1.0;
+      true
+    } else {
+      false
+    }
+  }
+
+  // Drops the buckets that have refilled completely, which a new bucket is equivalent to, at most once per refill.
+  fn sweep_idle_buckets(&self, rate_limit: &ActivationRateLimit, now: Instant) {
+    let full_refill = rate_limit.refill_interval.saturating_mul(rate_limit.burst);
+    let mut swept_at = self.swept_at.lock().unwrap();
+    if swept_at.map_or(false, |swept_at| now.duration_since(swept_at) < full_refill) {
+      return;
+    }
+    *swept_at = Some(now);
+    self
+      .buckets
+      .retain(|_, (_, refilled_at)| now.duration_since(*refilled_at) < full_refill);
+  }
+
+  fn try_reserve(&self, kind: &str, max_instances: Option<u32>) -> bool {
+    let count = self.counts.entry(kind.to_string()).or_default().value().clone();
+    match max_instances {
+      Some(max_instances) => count
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+          (current < max_instances as usize).then_some(current + 1)
+        })
+        .is_ok(),
+      None => {
+        count.fetch_add(1, Ordering::SeqCst);
+        true
+      }
+    }
+  }
+
+  fn release(&self, kind: &str) {
+    if let Some(count) = self.counts.get(kind) {
+      let _ = count.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| current.checked_sub(1));
+    }
+  }
+
+  fn record(&self, id: &str, kind: &str) {
+    self.activated_kinds.insert(id.to_string(), kind.to_string());
+  }
+
+  fn remove(&self, id: &str) {
+    if let Some((_, kind)) = self.activated_kinds.remove(id) {
+      self.release(&kind);
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct Activator {
   remote: Weak<Remote>,
@@ -41,6 +145,40 @@ impl Activator {
       .clone()
   }
 
+  fn get_activations(&self) -> ActivationState {
+    self
+      .remote
+      .upgrade()
+      .expect("Remote has been dropped")
+      .get_activations()
+      .clone()
+  }
+
+  fn get_max_instances(&self, kind: &str) -> Option<u32> {
+    self
+      .remote
+      .upgrade()
+      .expect("Remote has been dropped")
+      .get_kind_metadata(kind)
+      .and_then(|kind_metadata| kind_metadata.max_instances)
+  }
+
+  fn record_rejection(&self, kind: &str, rejection: ActivationRejection) {
+    if let Some(metrics) = self.remote.upgrade().and_then(|remote| remote.get_metrics().cloned()) {
+      metrics.increment_activation_rejections(kind, rejection);
+    }
+  }
+
+  async fn respond_quota_exceeded(&self, context_handle: &ContextHandle, kind: &str, rejection: ActivationRejection) {
+    self.record_rejection(kind, rejection);
+    context_handle
+      .respond(ResponseHandle::new(ActorPidResponse {
+        pid: None,
+        status_code: ResponseStatusCode::QuotaExceeded as i32,
+      }))
+      .await;
+  }
+
   async fn get_actor_system(&self) -> ActorSystem {
     self
       .remote
@@ -65,6 +203,7 @@ impl Activator {
         MessageHandle::new(ActorPidRequest {
           kind: kind.to_string(),
           name: name.to_string(),
+          init: None,
         }),
         timeout,
       )
@@ -97,6 +236,18 @@ impl Activator {
     }
   }
 
+  fn deserialize_init(&self, init: &SpawnInit) -> Result<MessageHandle, SerializerError> {
+    let serializer_id = SerializerId::try_from(init.serializer_id)
+      .map_err(|_| SerializerError::InvalidSerializerId(init.serializer_id))?;
+    self
+      .remote
+      .upgrade()
+      .expect("Remote has been dropped")
+      .get_serializer_registry()
+      .deserialize_manifest(&init.data, &serializer_id, &init.type_name)
+      .map(MessageHandle::new_arc)
+  }
+
   pub async fn spawn(&self, address: &str, kind: &str, timeout: Duration) -> Result<ActorPidResponse, ActivatorError> {
     self.spawn_named(address, "", kind, timeout).await
   }
@@ -110,6 +261,13 @@ impl Actor for Activator {
       context_handle.respond(ResponseHandle::new(Pong {})).await;
     }
     if let Some(msg) = message_handle.to_typed::<ActorPidRequest>() {
+      // The EndpointReader has applied the rate limit of the peer already.
+      let activations = self.get_activations();
+      let peer = context_handle
+        .get_sender()
+        .await
+        .map(|sender| sender.address().to_string())
+        .unwrap_or_default();
       return match self.get_kinds().get(&msg.kind) {
         None => {
           context_handle
@@ -118,9 +276,33 @@ impl Actor for Activator {
               status_code: ResponseStatusCode::Error as i32,
             }))
             .await;
-          Err(ActorError::ReceiveError(ErrorReason::new("Actor not found", 0)))
+          tracing::warn!("Unknown kind requested: peer = {}, kind = {}", peer, msg.kind);
+          Ok(())
         }
         Some(props) => {
+          let init = match &msg.init {
+            Some(init) => match self.deserialize_init(init) {
+              Ok(init) => Some(init),
+              Err(e) => {
+                tracing::error!("Failed to deserialize init message for kind {}: {}", msg.kind, e);
+                context_handle
+                  .respond(ResponseHandle::new(ActorPidResponse {
+                    pid: None,
+                    status_code: ResponseStatusCode::Error as i32,
+                  }))
+                  .await;
+                return Ok(());
+              }
+            },
+            None => None,
+          };
+          if !activations.try_reserve(&msg.kind, self.get_max_instances(&msg.kind)) {
+            tracing::warn!("Max instances of kind reached: peer = {}, kind = {}", peer, msg.kind);
+            self
+              .respond_quota_exceeded(&context_handle, &msg.kind, ActivationRejection::MaxInstances)
+              .await;
+            return Ok(());
+          }
           let mut name = msg.name;
           if name.is_empty() {
             name = context_handle
@@ -133,6 +315,10 @@ impl Actor for Activator {
           let mut ctx = context_handle.clone();
           match ctx.spawn_named(props.clone(), &format!("remote-{}", &name)).await {
             Ok(pid) => {
+              activations.record(pid.id(), &msg.kind);
+              if let Some(init) = init {
+                ctx.send(pid.clone(), init).await;
+              }
               context_handle
                 .respond(ResponseHandle::new(ActorPidResponse {
                   pid: Some(pid.inner_pid),
@@ -141,26 +327,30 @@ impl Actor for Activator {
                 .await;
               Ok(())
             }
-            Err(spawn_error) => match spawn_error {
-              SpawnError::ErrNameExists(pid) => {
-                context_handle
-                  .respond(ResponseHandle::new(ActorPidResponse {
-                    pid: Some(pid.inner_pid),
-                    status_code: ResponseStatusCode::ProcessNameAlreadyExists as i32,
-                  }))
-                  .await;
-                Err(ActorError::ReceiveError(ErrorReason::new("Actor already exists", 0)))
-              }
-              SpawnError::ErrPreStart(actor_error) => {
-                context_handle
-                  .respond(ResponseHandle::new(ActorPidResponse {
-                    pid: None,
-                    status_code: actor_error.reason().unwrap().code,
-                  }))
-                  .await;
-                Err(ActorError::ReceiveError(ErrorReason::new("Failed to spawn actor", 0)))
+            Err(spawn_error) => {
+              activations.release(&msg.kind);
+              match spawn_error {
+                SpawnError::ErrNameExists(pid) => {
+                  context_handle
+                    .respond(ResponseHandle::new(ActorPidResponse {
+                      pid: Some(pid.inner_pid),
+                      status_code: ResponseStatusCode::ProcessNameAlreadyExists as i32,
+                    }))
+                    .await;
+                  Ok(())
+                }
+                SpawnError::ErrPreStart(actor_error) => {
+                  context_handle
+                    .respond(ResponseHandle::new(ActorPidResponse {
+                      pid: None,
+                      status_code: actor_error.reason().unwrap().code,
+                    }))
+                    .await;
+                  tracing::error!("Failed to spawn actor of kind {}: {}", msg.kind, actor_error);
+                  Ok(())
+                }
               }
-            },
+            }
           }
         }
       };
@@ -172,4 +362,32 @@ impl Actor for Activator {
     tracing::info!("Started Activator");
     Ok(())
   }
+
+  async fn post_child_terminate(&mut self, _: ContextHandle, terminated: &Terminated) -> Result<(), ActorError> {
+    if let Some(who) = &terminated.who {
+      self.get_activations().remove(&who.id);
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ActivationState;
+  use crate::config::activation_rate_limit::ActivationRateLimit;
+  use std::time::Duration;
+
+  #[test]
+  fn test_idle_buckets_are_dropped() {
+    let activations = ActivationState::new(Some(ActivationRateLimit::new(2, Duration::from_millis(10))));
+    assert!(activations.try_acquire("peer-a"));
+    assert!(activations.try_acquire("peer-a"));
+    assert!(!activations.try_acquire("peer-a"));
+    assert_eq!(activations.buckets.len(), 1);
+
+    std::thread::sleep(Duration::from_millis(50));
+    assert!(activations.try_acquire("peer-b"));
+    assert_eq!(activations.buckets.len(), 1);
+    assert!(activations.try_acquire("peer-a"));
+  }
 }
@@ -1,47 +1,403 @@
-use dashmap::DashSet;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use nexus_actor_core_rs::actor::actor_system::ActorSystem;
+use nexus_actor_core_rs::actor::message::MessageHandle;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
+
+use crate::messages::BlockListEvent;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BlockListError {
+  #[error("Failed to read block list file: {0}")]
+  Io(String),
+  #[error("Invalid block list file: {0}")]
+  Format(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedBlock {
+  member: String,
+  // Milliseconds since the Unix epoch.
+  expires_at: Option<u64>,
+}
+
+// Members are system ids, addresses or TLS peer identities.
 #[derive(Debug, Clone)]
 pub struct BlockList {
-  blocked_members: Arc<DashSet<String>>,
+  // Each member with the time its block expires at, or None while blocked until unblocked.
+  blocked_members: Arc<DashMap<String, Option<SystemTime>>>,
+  // The single pending expiry task of each member blocked with an expiry, with the expiry it waits for.
+  expiry_tasks: Arc<DashMap<String, (SystemTime, AbortHandle)>>,
+  // Receives BlockListEvents when set.
+  actor_system: Option<ActorSystem>,
+  path: Option<PathBuf>,
+  persist_lock: Arc<Mutex<()>>,
+}
+
+impl Default for BlockList {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl BlockList {
   pub fn new() -> Self {
     BlockList {
-      blocked_members: Arc::new(DashSet::new()),
+      blocked_members: Arc::new(DashMap::new()),
+      expiry_tasks: Arc::new(DashMap::new()),
+      actor_system: None,
+      path: None,
+      persist_lock: Arc::new(Mutex::new(())),
+    }
+  }
+
+  pub(crate) fn with_actor_system(mut self, actor_system: ActorSystem) -> Self {
+    self.actor_system = Some(actor_system);
+    // Pending expiry tasks hold a copy without the actor system, e.g. those of blocks loaded by with_file.
+    let expiries = self
+      .blocked_members
+      .iter()
+      .filter_map(|entry| entry.value().map(|expires_at| (entry.key().clone(), expires_at)))
+      .collect::<Vec<_>>();
+    for (member, expires_at) in expiries {
+      self.schedule_expiry(member, expires_at);
+    }
+    self
+  }
+
+  // Loads the blocks stored in `path`, if it exists, and stores every later change there.
+  pub async fn with_file(mut self, path: impl Into<PathBuf>) -> Result<Self, BlockListError> {
+    let path = path.into();
+    self.path = Some(path.clone());
+    let bytes = match tokio::fs::read(&path).await {
+      Ok(bytes) => bytes,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(self),
+      Err(e) => return Err(BlockListError::Io(e.to_string())),
+    };
+    let blocks =
+      serde_json::from_slice::<Vec<PersistedBlock>>(&bytes).map_err(|e| BlockListError::Format(e.to_string()))?;
+    let now = SystemTime::now();
+    for block in blocks {
+      let expires_at = block
+        .expires_at
+        .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+      match expires_at {
+        Some(expires_at) if expires_at <= now => continue,
+        Some(expires_at) => self.schedule_expiry(block.member.clone(), expires_at),
+        None => {}
+      }
+      self.blocked_members.insert(block.member, expires_at);
     }
+    Ok(self)
   }
 
   pub async fn block(&self, member: String) {
-    self.blocked_members.insert(member);
+    self.insert(member, None).await;
+    self.persist().await;
+  }
+
+  // Blocks `member` until `ttl` has passed, replacing any earlier block of it.
+  pub async fn block_for(&self, member: String, ttl: Duration) {
+    let expires_at = SystemTime::now() + ttl;
+    self.schedule_expiry(member.clone(), expires_at);
+    self.insert(member, Some(expires_at)).await;
+    self.persist().await;
   }
 
   pub async fn block_multi(&self, members: impl IntoIterator<Item = String>) {
     for member in members {
-      self.blocked_members.insert(member);
+      self.insert(member, None).await;
     }
+    self.persist().await;
   }
 
   pub async fn unblock(&self, member: String) {
-    self.blocked_members.remove(&member);
+    self.remove(&member).await;
+    self.persist().await;
   }
 
   pub async fn unblock_multi(&self, members: impl IntoIterator<Item = String>) {
     for member in members {
-      self.blocked_members.remove(&member);
+      self.remove(&member).await;
     }
+    self.persist().await;
   }
 
   pub async fn is_blocked(&self, member: &str) -> bool {
-    self.blocked_members.contains(member)
+    let expires_at = match self.blocked_members.get(member) {
+      Some(entry) => *entry.value(),
+      None => return false,
+    };
+    match expires_at {
+      // The expiry timer has not caught up yet.
+      Some(expires_at) if expires_at <= SystemTime::now() => {
+        self.cancel_expiry(member);
+        self.expire(member, expires_at).await;
+        false
+      }
+      _ => true,
+    }
+  }
+
+  // None for members that are not blocked or blocked without expiry.
+  pub async fn get_expiry(&self, member: &str) -> Option<SystemTime> {
+    self.blocked_members.get(member).and_then(|entry| *entry.value())
   }
 
   pub async fn clear(&self) {
-    self.blocked_members.clear();
+    let members = self
+      .blocked_members
+      .iter()
+      .map(|entry| entry.key().clone())
+      .collect::<Vec<_>>();
+    for member in members {
+      self.remove(&member).await;
+    }
+    self.persist().await;
+  }
+
+  pub async fn get_blocked_members(&self) -> Vec<String> {
+    let now = SystemTime::now();
+    self
+      .blocked_members
+      .iter()
+      .filter(|entry| entry.value().map_or(true, |expires_at| expires_at > now))
+      .map(|entry| entry.key().clone())
+      .collect()
+  }
+
+  async fn insert(&self, member: String, expires_at: Option<SystemTime>) {
+    if expires_at.is_none() {
+      self.cancel_expiry(&member);
+    }
+    self.blocked_members.insert(member.clone(), expires_at);
+    self.publish(BlockListEvent::Blocked { member, expires_at }).await;
+  }
+
+  async fn remove(&self, member: &str) {
+    self.cancel_expiry(member);
+    if self.blocked_members.remove(member).is_some() {
+      self
+        .publish(BlockListEvent::Unblocked {
+          member: member.to_string(),
+        })
+        .await;
+    }
   }
 
-  pub async fn get_blocked_members(&self) -> Arc<DashSet<String>> {
-    self.blocked_members.clone()
+  // Replaces the pending expiry task of `member`, so that renewed blocks do not pile up tasks.
+  fn schedule_expiry(&self, member: String, expires_at: SystemTime) {
+    // The entry stays locked until the task is registered, in case it finishes right away.
+    let entry = self.expiry_tasks.entry(member.clone());
+    let cloned_self = self.clone();
+    let task = tokio::spawn(async move {
+      if let Ok(remaining) = expires_at.duration_since(SystemTime::now()) {
+        tokio::time::sleep(remaining).await;
+      }
+      // Deregistered first, so that nothing aborts the task while it publishes the expiry.
+      cloned_self
+        .expiry_tasks
+        .remove_if(&member, |_, (task_expires_at, _)| *task_expires_at == expires_at);
+      cloned_self.expire(&member, expires_at).await;
+    });
+    match entry {
+      Entry::Occupied(mut entry) => {
+        let (_, previous) = entry.insert((expires_at, task.abort_handle()));
+        previous.abort();
+      }
+      Entry::Vacant(entry) => {
+        entry.insert((expires_at, task.abort_handle()));
+      }
+    }
+  }
+
+  fn cancel_expiry(&self, member: &str) {
+    if let Some((_, (_, task))) = self.expiry_tasks.remove(member) {
+      task.abort();
+    }
+  }
+
+  // Lifts the block unless it was renewed or lifted in the meantime.
+  async fn expire(&self, member: &str, expires_at: SystemTime) {
+    if self
+      .blocked_members
+      .remove_if(member, |_, current| *current == Some(expires_at))
+      .is_some()
+    {
+      tracing::debug!("BlockList block expired: member = {}", member);
+      self
+        .publish(BlockListEvent::Unblocked {
+          member: member.to_string(),
+        })
+        .await;
+      self.persist().await;
+    }
+  }
+
+  async fn publish(&self, event: BlockListEvent) {
+    if let Some(actor_system) = &self.actor_system {
+      actor_system
+        .get_event_stream()
+        .await
+        .publish(MessageHandle::new(event))
+        .await;
+    }
+  }
+
+  async fn persist(&self) {
+    let Some(path) = &self.path else {
+      return;
+    };
+    let _guard = self.persist_lock.lock().await;
+    let blocks = self
+      .blocked_members
+      .iter()
+      .map(|entry| PersistedBlock {
+        member: entry.key().clone(),
+        expires_at: entry
+          .value()
+          .map(|expires_at| expires_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64),
+      })
+      .collect::<Vec<_>>();
+    // Written next to the file and renamed, so that a crash never leaves a truncated file behind.
+    let tmp_path = path.with_extension("tmp");
+    let result = match serde_json::to_vec_pretty(&blocks) {
+      Ok(bytes) => match tokio::fs::write(&tmp_path, bytes).await {
+        Ok(()) => tokio::fs::rename(&tmp_path, path).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+      },
+      Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = result {
+      tracing::error!("Failed to persist BlockList: path = {}, error = {}", path.display(), e);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::BlockList;
+  use crate::messages::BlockListEvent;
+  use nexus_actor_core_rs::actor::actor_system::ActorSystem;
+  use nexus_actor_core_rs::actor::message::Message;
+  use std::time::Duration;
+
+  #[tokio::test]
+  async fn test_block_expires() {
+    let block_list = BlockList::new();
+    block_list
+      .block_for("system-a".to_string(), Duration::from_millis(100))
+      .await;
+    block_list.block("system-b".to_string()).await;
+    assert!(block_list.is_blocked("system-a").await);
+    assert!(block_list.get_expiry("system-a").await.is_some());
+    assert_eq!(block_list.get_expiry("system-b").await, None);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!block_list.is_blocked("system-a").await);
+    assert_eq!(block_list.get_blocked_members().await, vec!["system-b".to_string()]);
+  }
+
+  #[tokio::test]
+  async fn test_renewed_block_outlives_the_first_expiry() {
+    let block_list = BlockList::new();
+    block_list
+      .block_for("system-a".to_string(), Duration::from_millis(100))
+      .await;
+    block_list
+      .block_for("system-a".to_string(), Duration::from_secs(60))
+      .await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(block_list.is_blocked("system-a").await);
+  }
+
+  #[tokio::test]
+  async fn test_renewed_block_keeps_a_single_expiry_task() {
+    let block_list = BlockList::new();
+    for _ in 0..10 {
+      block_list
+        .block_for("system-a".to_string(), Duration::from_secs(60))
+        .await;
+    }
+    assert_eq!(block_list.expiry_tasks.len(), 1);
+    block_list.unblock("system-a".to_string()).await;
+    assert!(block_list.expiry_tasks.is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_blocks_survive_a_restart() {
+    let path = std::env::temp_dir().join(format!("nexus-block-list-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let block_list = BlockList::new().with_file(&path).await.unwrap();
+    block_list.block("system-a".to_string()).await;
+    block_list
+      .block_for("system-b".to_string(), Duration::from_secs(60))
+      .await;
+    block_list
+      .block_for("system-c".to_string(), Duration::from_millis(1))
+      .await;
+    block_list.block("system-d".to_string()).await;
+    block_list.unblock("system-d".to_string()).await;
+
+    let restored = BlockList::new().with_file(&path).await.unwrap();
+    assert!(restored.is_blocked("system-a").await);
+    assert!(restored.is_blocked("system-b").await);
+    assert!(restored.get_expiry("system-b").await.is_some());
+    assert!(!restored.is_blocked("system-c").await);
+    assert!(!restored.is_blocked("system-d").await);
+    let _ = std::fs::remove_file(&path);
+  }
+
+  #[tokio::test]
+  async fn test_loaded_block_publishes_its_expiry() {
+    let path = std::env::temp_dir().join(format!("nexus-block-list-expiry-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let block_list = BlockList::new().with_file(&path).await.unwrap();
+    block_list
+      .block_for("system-a".to_string(), Duration::from_millis(200))
+      .await;
+
+    let system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<BlockListEvent>().cloned();
+        async move {
+          if let Some(evt) = evt {
+            let _ = tx.try_send(evt);
+          }
+        }
+      })
+      .await;
+    // Loaded before the actor system is attached.
+    let restored = BlockList::new()
+      .with_file(&path)
+      .await
+      .unwrap()
+      .with_actor_system(system.clone());
+    assert!(restored.is_blocked("system-a").await);
+
+    let event = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      event,
+      BlockListEvent::Unblocked {
+        member: "system-a".to_string()
+      }
+    );
+    assert!(!restored.is_blocked("system-a").await);
+    let _ = std::fs::remove_file(&path);
   }
 }
@@ -0,0 +1,230 @@
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Compression {
+  #[default]
+  None,
+  Gzip,
+  Zstd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CompressionError {
+  #[error("Unknown compression: {0}")]
+  Unknown(String),
+  #[error("Failed to compress payload: {0}")]
+  Compress(String),
+  #[error("Failed to decompress payload: {0}")]
+  Decompress(String),
+  #[error("Decompressed payload exceeds {0} bytes")]
+  TooLarge(usize),
+}
+
+impl Compression {
+  // Name exchanged during the handshake.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Compression::None => "",
+      Compression::Gzip => "gzip",
+      Compression::Zstd => "zstd",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Result<Self, CompressionError> {
+    match name {
+      "" => Ok(Compression::None),
+      "gzip" => Ok(Compression::Gzip),
+      "zstd" => Ok(Compression::Zstd),
+      _ => Err(CompressionError::Unknown(name.to_string())),
+    }
+  }
+
+  pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>, CompressionError> {
+    match self {
+      Compression::None => Ok(data.to_vec()),
+      Compression::Gzip => {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+          .write_all(data)
+          .map_err(|e| CompressionError::Compress(e.to_string()))?;
+        encoder.finish().map_err(|e| CompressionError::Compress(e.to_string()))
+      }
+      Compression::Zstd => {
+        zstd::encode_all(data, zstd::DEFAULT_COMPRESSION_LEVEL).map_err(|e| CompressionError::Compress(e.to_string()))
+      }
+    }
+  }
+
+  // Fails rather than allocating more than `max_size` bytes, since a small payload can expand enormously.
+  pub fn decompress(&self, data: &[u8], max_size: usize) -> Result<Vec<u8>, CompressionError> {
+    match self {
+      Compression::None => Ok(data.to_vec()),
+      Compression::Gzip => read_bounded(GzDecoder::new(data), max_size),
+      Compression::Zstd => read_bounded(
+        zstd::stream::read::Decoder::new(data).map_err(|e| CompressionError::Decompress(e.to_string()))?,
+        max_size,
+      ),
+    }
+  }
+}
+
+fn read_bounded(decoder: impl Read, max_size: usize) -> Result<Vec<u8>, CompressionError> {
+  let mut decoded = Vec::new();
+  decoder
+    .take(max_size as u64 + 1)
+    .read_to_end(&mut decoded)
+    .map_err(|e| CompressionError::Decompress(e.to_string()))?;
+  if decoded.len() > max_size {
+    return Err(CompressionError::TooLarge(max_size));
+  }
+  Ok(decoded)
+}
+
+impl Display for Compression {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Compression::None => write!(f, "none"),
+      _ => write!(f, "{}", self.name()),
+    }
+  }
+}
+
+impl From<Compression> for u32 {
+  fn from(compression: Compression) -> Self {
+    match compression {
+      Compression::None => 0,
+      Compression::Gzip => 1,
+      Compression::Zstd => 2,
+    }
+  }
+}
+
+impl TryFrom<u32> for Compression {
+  type Error = CompressionError;
+
+  fn try_from(value: u32) -> Result<Self, Self::Error> {
+    match value {
+      0 => Ok(Compression::None),
+      1 => Ok(Compression::Gzip),
+      2 => Ok(Compression::Zstd),
+      _ => Err(CompressionError::Unknown(value.to_string())),
+    }
+  }
+}
+
+// Payload sizes of the envelopes sent to a single peer.
+#[derive(Debug, Default)]
+pub struct CompressionMetrics {
+  messages: AtomicU64,
+  compressed_messages: AtomicU64,
+  uncompressed_bytes: AtomicU64,
+  compressed_bytes: AtomicU64,
+}
+
+impl CompressionMetrics {
+  pub(crate) fn record(&self, uncompressed_bytes: usize, sent_bytes: usize, compressed: bool) {
+    self.messages.fetch_add(1, Ordering::Relaxed);
+    if compressed {
+      self.compressed_messages.fetch_add(1, Ordering::Relaxed);
+    }
+    self
+      .uncompressed_bytes
+      .fetch_add(uncompressed_bytes as u64, Ordering::Relaxed);
+    self.compressed_bytes.fetch_add(sent_bytes as u64, Ordering::Relaxed);
+  }
+
+  pub fn snapshot(&self) -> CompressionStats {
+    CompressionStats {
+      messages: self.messages.load(Ordering::Relaxed),
+      compressed_messages: self.compressed_messages.load(Ordering::Relaxed),
+      uncompressed_bytes: self.uncompressed_bytes.load(Ordering::Relaxed),
+      compressed_bytes: self.compressed_bytes.load(Ordering::Relaxed),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompressionStats {
+  pub messages: u64,
+  pub compressed_messages: u64,
+  // Payload bytes before compression.
+  pub uncompressed_bytes: u64,
+  // Payload bytes actually sent, including payloads below the threshold.
+  pub compressed_bytes: u64,
+}
+
+impl CompressionStats {
+  // Sent bytes divided by original bytes; 1.0 when nothing was sent.
+  pub fn ratio(&self) -> f64 {
+    if self.uncompressed_bytes == 0 {
+      1.0
+    } else {
+      self.compressed_bytes as f64 / self.uncompressed_bytes as f64
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_compression_round_trip() {
+    let data = "{\"name\":\"nexus\",\"value\":42}".repeat(100).into_bytes();
+    for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+      let compressed = compression.compress(&data).unwrap();
+      if compression != Compression::None {
+        assert!(compressed.len() < data.len());
+      }
+      assert_eq!(compression.decompress(&compressed, data.len()).unwrap(), data);
+      assert_eq!(Compression::try_from(u32::from(compression)), Ok(compression));
+      assert_eq!(Compression::from_name(compression.name()), Ok(compression));
+    }
+  }
+
+  #[test]
+  fn test_decompress_invalid_payload() {
+    assert!(matches!(
+      Compression::Gzip.decompress(b"not gzip", 1024),
+      Err(CompressionError::Decompress(_))
+    ));
+    assert!(matches!(
+      Compression::Zstd.decompress(b"not zstd", 1024),
+      Err(CompressionError::Decompress(_))
+    ));
+    assert_eq!(
+      Compression::from_name("brotli"),
+      Err(CompressionError::Unknown("brotli".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_decompress_is_bounded() {
+    let data = vec![0u8; 1024 * 1024];
+    for compression in [Compression::Gzip, Compression::Zstd] {
+      let compressed = compression.compress(&data).unwrap();
+      assert_eq!(
+        compression.decompress(&compressed, data.len() - 1),
+        Err(CompressionError::TooLarge(data.len() - 1))
+      );
+    }
+  }
+
+  #[test]
+  fn test_compression_stats_ratio() {
+    let metrics = CompressionMetrics::default();
+    assert_eq!(metrics.snapshot().ratio(), 1.0);
+    metrics.record(1000, 250, true);
+    metrics.record(100, 100, false);
+    let stats = metrics.snapshot();
+    assert_eq!(stats.messages, 2);
+    assert_eq!(stats.compressed_messages, 1);
+    assert_eq!(stats.ratio(), 350.0 / 1100.0);
+  }
+}
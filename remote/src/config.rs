@@ -1,13 +1,35 @@
+use crate::config::activation_rate_limit::ActivationRateLimit;
+use crate::config::compression_config::CompressionConfig;
+use crate::config::heartbeat_config::HeartbeatConfig;
+use crate::config::kind_metadata::KindMetadata;
+use crate::config::outbound_queue_config::OutboundQueueConfig;
+use crate::config::reconnect_policy::ReconnectPolicy;
+use crate::config::reliable_delivery_config::ReliableDeliveryConfig;
 use crate::config::server_config::ServerConfig;
+use crate::config::tls_config::TlsConfig;
 use crate::config_option::ConfigOption;
+#[cfg(any(test, feature = "test-support"))]
+use crate::fault_injector::FaultInjector;
+use crate::remote_authenticator::RemoteAuthenticatorHandle;
+use crate::transport::tcp_transport::TcpTransport;
+use crate::transport::TransportHandle;
 use dashmap::DashMap;
 use nexus_actor_core_rs::actor::actor::Props;
 use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
+pub mod activation_rate_limit;
+pub mod compression_config;
+pub mod heartbeat_config;
+pub mod kind_metadata;
+pub mod outbound_queue_config;
+pub mod reconnect_policy;
+pub mod reliable_delivery_config;
 pub mod server_config;
+pub mod tls_config;
 
 #[derive(Debug)]
 struct ConfigInner {
@@ -15,13 +37,23 @@ struct ConfigInner {
   port: Option<u16>,
   advertised_host: Option<String>,
   endpoint_writer_batch_size: usize,
-  endpoint_writer_queue_size: usize,
+  outbound_queue_config: OutboundQueueConfig,
   endpoint_manager_batch_size: usize,
   endpoint_manager_queue_size: usize,
   kinds: DashMap<String, Props>,
-  max_retry_count: u32,
-  retry_interval: Duration,
+  kind_metadata: DashMap<String, KindMetadata>,
+  reconnect_policy: ReconnectPolicy,
   server_config: Option<ServerConfig>,
+  tls_config: Option<TlsConfig>,
+  authenticator: Option<RemoteAuthenticatorHandle>,
+  compression_config: Option<CompressionConfig>,
+  heartbeat_config: Option<HeartbeatConfig>,
+  reliable_delivery_config: Option<ReliableDeliveryConfig>,
+  transport: TransportHandle,
+  block_list_path: Option<PathBuf>,
+  activation_rate_limit: Option<ActivationRateLimit>,
+  #[cfg(any(test, feature = "test-support"))]
+  fault_injector: Option<FaultInjector>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,12 +70,22 @@ impl Default for Config {
         advertised_host: None,
         endpoint_writer_batch_size: 1000,
         endpoint_manager_batch_size: 1000,
-        endpoint_writer_queue_size: 1000000,
+        outbound_queue_config: OutboundQueueConfig::default(),
         endpoint_manager_queue_size: 1000000,
         kinds: DashMap::new(),
-        max_retry_count: 5,
-        retry_interval: Duration::from_secs(2),
+        kind_metadata: DashMap::new(),
+        reconnect_policy: ReconnectPolicy::default(),
         server_config: None,
+        tls_config: None,
+        authenticator: None,
+        compression_config: None,
+        heartbeat_config: None,
+        reliable_delivery_config: None,
+        transport: TransportHandle::new(TcpTransport),
+        block_list_path: None,
+        activation_rate_limit: None,
+        #[cfg(any(test, feature = "test-support"))]
+        fault_injector: None,
       })),
     }
   }
@@ -121,12 +163,24 @@ impl Config {
 
   pub async fn get_endpoint_writer_queue_size(&self) -> usize {
     let mg = self.inner.lock().await;
-    mg.endpoint_writer_queue_size
+    mg.outbound_queue_config.max_queued
   }
 
+  // Keeps the overflow policy and scales the high watermark with the new size.
   pub async fn set_endpoint_writer_queue_size(&mut self, endpoint_writer_queue_size: usize) {
     let mut mg = self.inner.lock().await;
-    mg.endpoint_writer_queue_size = endpoint_writer_queue_size;
+    mg.outbound_queue_config = OutboundQueueConfig::new(endpoint_writer_queue_size)
+      .with_overflow_policy(mg.outbound_queue_config.overflow_policy);
+  }
+
+  pub async fn get_outbound_queue_config(&self) -> OutboundQueueConfig {
+    let mg = self.inner.lock().await;
+    mg.outbound_queue_config.clone()
+  }
+
+  pub async fn set_outbound_queue_config(&mut self, outbound_queue_config: OutboundQueueConfig) {
+    let mut mg = self.inner.lock().await;
+    mg.outbound_queue_config = outbound_queue_config;
   }
 
   pub async fn get_endpoint_manager_batch_size(&self) -> usize {
@@ -151,12 +205,12 @@ impl Config {
 
   pub async fn get_max_retry_count(&self) -> u32 {
     let mg = self.inner.lock().await;
-    mg.max_retry_count
+    mg.reconnect_policy.max_attempts
   }
 
   pub async fn set_max_retry_count(&mut self, max_retry_count: u32) {
     let mut mg = self.inner.lock().await;
-    mg.max_retry_count = max_retry_count;
+    mg.reconnect_policy.max_attempts = max_retry_count;
   }
 
   pub async fn get_kinds(&self) -> DashMap<String, Props> {
@@ -174,14 +228,34 @@ impl Config {
     mg.kinds.insert(kind.to_string(), props);
   }
 
+  pub async fn get_kind_metadata(&self) -> DashMap<String, KindMetadata> {
+    let mg = self.inner.lock().await;
+    mg.kind_metadata.clone()
+  }
+
+  pub async fn put_kind_metadata(&mut self, kind: &str, kind_metadata: KindMetadata) {
+    let mg = self.inner.lock().await;
+    mg.kind_metadata.insert(kind.to_string(), kind_metadata);
+  }
+
   pub async fn get_retry_interval(&self) -> Duration {
     let mg = self.inner.lock().await;
-    mg.retry_interval
+    mg.reconnect_policy.initial_backoff
   }
 
   pub async fn set_retry_interval(&mut self, retry_interval: Duration) {
     let mut mg = self.inner.lock().await;
-    mg.retry_interval = retry_interval;
+    mg.reconnect_policy.initial_backoff = retry_interval;
+  }
+
+  pub async fn get_reconnect_policy(&self) -> ReconnectPolicy {
+    let mg = self.inner.lock().await;
+    mg.reconnect_policy.clone()
+  }
+
+  pub async fn set_reconnect_policy(&mut self, reconnect_policy: ReconnectPolicy) {
+    let mut mg = self.inner.lock().await;
+    mg.reconnect_policy = reconnect_policy;
   }
 
   pub async fn get_server_config(&self) -> Option<ServerConfig> {
@@ -193,4 +267,96 @@ impl Config {
     let mut mg = self.inner.lock().await;
     mg.server_config = Some(server_config);
   }
+
+  pub async fn get_tls_config(&self) -> Option<TlsConfig> {
+    let mg = self.inner.lock().await;
+    mg.tls_config.clone()
+  }
+
+  pub async fn set_tls_config(&mut self, tls_config: TlsConfig) {
+    let mut mg = self.inner.lock().await;
+    mg.tls_config = Some(tls_config);
+  }
+
+  pub async fn get_authenticator(&self) -> Option<RemoteAuthenticatorHandle> {
+    let mg = self.inner.lock().await;
+    mg.authenticator.clone()
+  }
+
+  pub async fn set_authenticator(&mut self, authenticator: RemoteAuthenticatorHandle) {
+    let mut mg = self.inner.lock().await;
+    mg.authenticator = Some(authenticator);
+  }
+
+  pub async fn get_compression_config(&self) -> Option<CompressionConfig> {
+    let mg = self.inner.lock().await;
+    mg.compression_config.clone()
+  }
+
+  pub async fn set_compression_config(&mut self, compression_config: CompressionConfig) {
+    let mut mg = self.inner.lock().await;
+    mg.compression_config = Some(compression_config);
+  }
+
+  pub async fn get_heartbeat_config(&self) -> Option<HeartbeatConfig> {
+    let mg = self.inner.lock().await;
+    mg.heartbeat_config.clone()
+  }
+
+  pub async fn set_heartbeat_config(&mut self, heartbeat_config: HeartbeatConfig) {
+    let mut mg = self.inner.lock().await;
+    mg.heartbeat_config = Some(heartbeat_config);
+  }
+
+  pub async fn get_reliable_delivery_config(&self) -> Option<ReliableDeliveryConfig> {
+    let mg = self.inner.lock().await;
+    mg.reliable_delivery_config.clone()
+  }
+
+  pub async fn set_reliable_delivery_config(&mut self, reliable_delivery_config: ReliableDeliveryConfig) {
+    let mut mg = self.inner.lock().await;
+    mg.reliable_delivery_config = Some(reliable_delivery_config);
+  }
+
+  pub async fn get_transport(&self) -> TransportHandle {
+    let mg = self.inner.lock().await;
+    mg.transport.clone()
+  }
+
+  pub async fn set_transport(&mut self, transport: TransportHandle) {
+    let mut mg = self.inner.lock().await;
+    mg.transport = transport;
+  }
+
+  pub async fn get_block_list_path(&self) -> Option<PathBuf> {
+    let mg = self.inner.lock().await;
+    mg.block_list_path.clone()
+  }
+
+  pub async fn set_block_list_path(&mut self, block_list_path: PathBuf) {
+    let mut mg = self.inner.lock().await;
+    mg.block_list_path = Some(block_list_path);
+  }
+
+  pub async fn get_activation_rate_limit(&self) -> Option<ActivationRateLimit> {
+    let mg = self.inner.lock().await;
+    mg.activation_rate_limit.clone()
+  }
+
+  pub async fn set_activation_rate_limit(&mut self, activation_rate_limit: ActivationRateLimit) {
+    let mut mg = self.inner.lock().await;
+    mg.activation_rate_limit = Some(activation_rate_limit);
+  }
+
+  #[cfg(any(test, feature = "test-support"))]
+  pub async fn get_fault_injector(&self) -> Option<FaultInjector> {
+    let mg = self.inner.lock().await;
+    mg.fault_injector.clone()
+  }
+
+  #[cfg(any(test, feature = "test-support"))]
+  pub async fn set_fault_injector(&mut self, fault_injector: FaultInjector) {
+    let mut mg = self.inner.lock().await;
+    mg.fault_injector = Some(fault_injector);
+  }
 }
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+// Token bucket bounding the ActorPidRequests the activator accepts from each peer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivationRateLimit {
+  // Requests a peer may send at once.
+  pub burst: u32,
+  // Time it takes a peer to regain one request of its burst.
+  pub refill_interval: Duration,
+}
+
+impl Default for ActivationRateLimit {
+  fn default() -> Self {
+    Self {
+      burst: 100,
+      refill_interval: Duration::from_millis(10),
+    }
+  }
+}
+
+impl ActivationRateLimit {
+  pub fn new(burst: u32, refill_interval: Duration) -> Self {
+    Self { burst, refill_interval }
+  }
+
+  pub fn with_burst(mut self, burst: u32) -> Self {
+    self.burst = burst;
+    self
+  }
+
+  pub fn with_refill_interval(mut self, refill_interval: Duration) -> Self {
+    self.refill_interval = refill_interval;
+    self
+  }
+}
@@ -0,0 +1,73 @@
+use crate::compression::Compression;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionConfig {
+  // Supported algorithms in order of preference. The first one also supported by the peer is used.
+  pub algorithms: Vec<Compression>,
+  // Payloads smaller than this many bytes are sent uncompressed.
+  pub threshold: usize,
+  // Received payloads that decompress to more than this many bytes are rejected.
+  pub max_decompressed_size: usize,
+}
+
+impl Default for CompressionConfig {
+  fn default() -> Self {
+    Self {
+      algorithms: vec![Compression::Zstd, Compression::Gzip],
+      threshold: 1024,
+      max_decompressed_size: 4 * 1024 * 1024,
+    }
+  }
+}
+
+impl CompressionConfig {
+  pub fn new(algorithms: impl IntoIterator<Item = Compression>) -> Self {
+    Self {
+      algorithms: algorithms
+        .into_iter()
+        .filter(|algorithm| *algorithm != Compression::None)
+        .collect(),
+      ..Self::default()
+    }
+  }
+
+  pub fn with_threshold(mut self, threshold: usize) -> Self {
+    self.threshold = threshold;
+    self
+  }
+
+  pub fn with_max_decompressed_size(mut self, max_decompressed_size: usize) -> Self {
+    self.max_decompressed_size = max_decompressed_size;
+    self
+  }
+
+  pub(crate) fn offered_names(&self) -> Vec<String> {
+    self.algorithms.iter().map(|a| a.name().to_string()).collect()
+  }
+
+  // Picks the peer's most preferred algorithm that this node also supports.
+  pub(crate) fn negotiate(&self, offered: &[String]) -> Compression {
+    offered
+      .iter()
+      .filter_map(|name| Compression::from_name(name).ok())
+      .find(|algorithm| *algorithm != Compression::None && self.algorithms.contains(algorithm))
+      .unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::CompressionConfig;
+  use crate::compression::Compression;
+
+  #[test]
+  fn test_negotiate() {
+    let config = CompressionConfig::new([Compression::Gzip]);
+    let offered = vec!["zstd".to_string(), "gzip".to_string()];
+    assert_eq!(config.negotiate(&offered), Compression::Gzip);
+    assert_eq!(CompressionConfig::default().negotiate(&offered), Compression::Zstd);
+    assert_eq!(config.negotiate(&["zstd".to_string()]), Compression::None);
+    assert_eq!(config.negotiate(&["brotli".to_string()]), Compression::None);
+    assert_eq!(config.negotiate(&[]), Compression::None);
+  }
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartbeatConfig {
+  // How often each endpoint pings the activator of its peer.
+  pub interval: Duration,
+  // How long a single ping waits for its pong.
+  pub timeout: Duration,
+  // Phi above which the peer is considered dead and the endpoint is terminated.
+  pub threshold: f64,
+  // Number of heartbeat intervals kept to estimate the distribution.
+  pub max_sample_size: usize,
+  pub min_std_deviation: Duration,
+  // Silence tolerated on top of the mean interval, e.g. for GC pauses.
+  pub acceptable_heartbeat_pause: Duration,
+}
+
+impl Default for HeartbeatConfig {
+  fn default() -> Self {
+    Self {
+      interval: Duration::from_secs(1),
+      timeout: Duration::from_secs(1),
+      threshold: 10.0,
+      max_sample_size: 200,
+      min_std_deviation: Duration::from_millis(100),
+      acceptable_heartbeat_pause: Duration::from_secs(3),
+    }
+  }
+}
+
+impl HeartbeatConfig {
+  pub fn new(interval: Duration) -> Self {
+    Self {
+      interval,
+      timeout: interval,
+      ..Self::default()
+    }
+  }
+
+  pub fn with_timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = timeout;
+    self
+  }
+
+  pub fn with_threshold(mut self, threshold: f64) -> Self {
+    self.threshold = threshold;
+    self
+  }
+
+  pub fn with_max_sample_size(mut self, max_sample_size: usize) -> Self {
+    self.max_sample_size = max_sample_size;
+    self
+  }
+
+  pub fn with_min_std_deviation(mut self, min_std_deviation: Duration) -> Self {
+    self.min_std_deviation = min_std_deviation;
+    self
+  }
+
+  pub fn with_acceptable_heartbeat_pause(mut self, acceptable_heartbeat_pause: Duration) -> Self {
+    self.acceptable_heartbeat_pause = acceptable_heartbeat_pause;
+    self
+  }
+}
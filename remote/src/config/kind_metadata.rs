@@ -0,0 +1,19 @@
+// Describes a kind to the peers that discover it through `Remote::list_kinds`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KindMetadata {
+  pub version: Option<String>,
+  // Instances of the kind the activator keeps alive at most.
+  pub max_instances: Option<u32>,
+}
+
+impl KindMetadata {
+  pub fn with_version(mut self, version: &str) -> Self {
+    self.version = Some(version.to_string());
+    self
+  }
+
+  pub fn with_max_instances(mut self, max_instances: u32) -> Self {
+    self.max_instances = Some(max_instances);
+    self
+  }
+}
@@ -0,0 +1,46 @@
+// What an EndpointWriter does with a message sent while its outbound queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+  // Evicts the longest queued message to make room.
+  DropOldest,
+  #[default]
+  DropNewest,
+  // Drops the message and answers its sender with a `DeadLetterResponse`, failing pending requests early.
+  Reject,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboundQueueConfig {
+  // Messages buffered per endpoint while the EndpointWriter is busy, e.g. reconnecting or sending to a slow peer.
+  pub max_queued: usize,
+  pub overflow_policy: OverflowPolicy,
+  // Queue depth at which an `EndpointHighWatermarkEvent` is published; it is published again only after the
+  // queue has drained below this.
+  pub high_watermark: usize,
+}
+
+impl Default for OutboundQueueConfig {
+  fn default() -> Self {
+    Self::new(1_000_000)
+  }
+}
+
+impl OutboundQueueConfig {
+  pub fn new(max_queued: usize) -> Self {
+    Self {
+      max_queued,
+      overflow_policy: OverflowPolicy::default(),
+      high_watermark: max_queued - max_queued / 5,
+    }
+  }
+
+  pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+    self.overflow_policy = overflow_policy;
+    self
+  }
+
+  pub fn with_high_watermark(mut self, high_watermark: usize) -> Self {
+    self.high_watermark = high_watermark;
+    self
+  }
+}
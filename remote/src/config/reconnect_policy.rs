@@ -0,0 +1,89 @@
+use rand::Rng;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+  pub initial_backoff: Duration,
+  pub max_backoff: Duration,
+  pub multiplier: f64,
+  // Fraction of the backoff, in 0.0..=1.0, that is randomly added or subtracted to spread out reconnecting peers.
+  pub jitter: f64,
+  // Number of connection attempts before the endpoint is terminated.
+  pub max_attempts: u32,
+}
+
+impl Default for ReconnectPolicy {
+  fn default() -> Self {
+    Self {
+      initial_backoff: Duration::from_secs(2),
+      max_backoff: Duration::from_secs(10),
+      multiplier: 2.0,
+      jitter: 0.2,
+      max_attempts: 5,
+    }
+  }
+}
+
+impl ReconnectPolicy {
+  pub fn new(initial_backoff: Duration, max_backoff: Duration, max_attempts: u32) -> Self {
+    Self {
+      initial_backoff,
+      max_backoff,
+      max_attempts,
+      ..Self::default()
+    }
+  }
+
+  pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+    self.multiplier = multiplier;
+    self
+  }
+
+  pub fn with_jitter(mut self, jitter: f64) -> Self {
+    self.jitter = jitter.clamp(0.0, 1.0);
+    self
+  }
+
+  pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+    self.max_attempts = max_attempts;
+    self
+  }
+
+  // Backoff before the given attempt, where attempt 1 is the first retry.
+  pub fn backoff(&self, attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+    let max_nanos = self.max_backoff.as_nanos() as f64;
+    let base = (self.initial_backoff.as_nanos() as f64 * self.multiplier.max(1.0).powi(exponent)).min(max_nanos);
+    let factor = if self.jitter > 0.0 {
+      1.0 + rand::rng().random_range(-self.jitter..=self.jitter)
+    } else {
+      1.0
+    };
+    Duration::from_nanos((base * factor).min(max_nanos).round() as u64)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ReconnectPolicy;
+  use std::time::Duration;
+
+  #[test]
+  fn test_backoff_grows_exponentially_up_to_max() {
+    let policy = ReconnectPolicy::new(Duration::from_millis(100), Duration::from_secs(1), 10).with_jitter(0.0);
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(5), Duration::from_secs(1));
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(1));
+  }
+
+  #[test]
+  fn test_backoff_with_jitter_stays_in_range() {
+    let policy = ReconnectPolicy::new(Duration::from_millis(100), Duration::from_secs(10), 10).with_jitter(0.5);
+    for _ in 0..100 {
+      let backoff = policy.backoff(2);
+      assert!(backoff >= Duration::from_millis(100) && backoff <= Duration::from_millis(300));
+    }
+  }
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+// Selects the remote sends that are acknowledged by the receiver and retransmitted until they are.
+// Everything else stays fire-and-forget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReliableDeliveryConfig {
+  // Every message sent to these addresses is delivered at least once.
+  pub addresses: HashSet<String>,
+  // Messages of these types are delivered at least once, whatever the address.
+  pub message_types: HashSet<String>,
+  // Unacknowledged envelopes kept per endpoint; the oldest are dropped beyond this.
+  pub max_unacked: usize,
+  // A batch that is not acknowledged within this is retransmitted.
+  pub ack_timeout: Duration,
+}
+
+impl Default for ReliableDeliveryConfig {
+  fn default() -> Self {
+    Self {
+      addresses: HashSet::new(),
+      message_types: HashSet::new(),
+      max_unacked: 10_000,
+      ack_timeout: Duration::from_secs(5),
+    }
+  }
+}
+
+impl ReliableDeliveryConfig {
+  pub fn with_address(mut self, address: impl Into<String>) -> Self {
+    self.addresses.insert(address.into());
+    self
+  }
+
+  pub fn with_message_type<T: 'static>(self) -> Self {
+    self.with_type_name(std::any::type_name::<T>())
+  }
+
+  pub fn with_type_name(mut self, type_name: impl Into<String>) -> Self {
+    self.message_types.insert(type_name.into());
+    self
+  }
+
+  pub fn with_max_unacked(mut self, max_unacked: usize) -> Self {
+    self.max_unacked = max_unacked;
+    self
+  }
+
+  pub fn with_ack_timeout(mut self, ack_timeout: Duration) -> Self {
+    self.ack_timeout = ack_timeout;
+    self
+  }
+
+  pub(crate) fn is_reliable(&self, address: &str, type_name: &str) -> bool {
+    self.addresses.contains(address) || self.message_types.contains(type_name)
+  }
+}
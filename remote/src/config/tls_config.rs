@@ -0,0 +1,154 @@
+use std::fmt::{Debug, Formatter};
+use std::net::SocketAddr;
+use std::path::Path;
+
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+use crate::transport::TransportError;
+
+#[derive(Clone)]
+pub struct TlsConfig {
+  // PEM encoded certificate chain presented by this node, both as a server and, with mTLS, as a client.
+  pub certificate: Vec<u8>,
+  // PEM encoded private key for `certificate`.
+  pub private_key: Vec<u8>,
+  // PEM encoded CA used to verify the peer. Servers are always verified against it when it is set;
+  // clients are only verified when `client_auth` is enabled, which requires it.
+  pub ca_certificate: Option<Vec<u8>>,
+  pub client_auth: bool,
+  // Overrides the name used to verify the server certificate. Defaults to the host of the remote address.
+  pub domain_name: Option<String>,
+  // Peer identities (certificate CN or DNS SAN) accepted by the EndpointReader. `None` accepts any verified peer.
+  pub allowed_peer_identities: Option<Vec<String>>,
+}
+
+impl TlsConfig {
+  pub fn new(certificate: impl Into<Vec<u8>>, private_key: impl Into<Vec<u8>>) -> Self {
+    Self {
+      certificate: certificate.into(),
+      private_key: private_key.into(),
+      ca_certificate: None,
+      client_auth: false,
+      domain_name: None,
+      allowed_peer_identities: None,
+    }
+  }
+
+  pub fn from_pem_files(
+    certificate_path: impl AsRef<Path>,
+    private_key_path: impl AsRef<Path>,
+  ) -> std::io::Result<Self> {
+    let certificate = std::fs::read(certificate_path)?;
+    let private_key = std::fs::read(private_key_path)?;
+    Ok(Self::new(certificate, private_key))
+  }
+
+  pub fn with_ca_certificate(mut self, ca_certificate: impl Into<Vec<u8>>) -> Self {
+    self.ca_certificate = Some(ca_certificate.into());
+    self
+  }
+
+  pub fn with_client_auth(mut self, client_auth: bool) -> Self {
+    self.client_auth = client_auth;
+    self
+  }
+
+  pub fn with_domain_name(mut self, domain_name: &str) -> Self {
+    self.domain_name = Some(domain_name.to_string());
+    self
+  }
+
+  pub fn with_allowed_peer_identities(mut self, identities: impl IntoIterator<Item = impl Into<String>>) -> Self {
+    self.allowed_peer_identities = Some(identities.into_iter().map(Into::into).collect());
+    self
+  }
+
+  pub fn is_mutual(&self) -> bool {
+    self.client_auth && self.ca_certificate.is_some()
+  }
+
+  // Client authentication without a CA to verify clients against would silently fall back to one-way TLS.
+  fn validate(&self) -> Result<(), TransportError> {
+    if self.client_auth && self.ca_certificate.is_none() {
+      return Err(TransportError::Tls(
+        "client_auth requires a CA certificate to verify clients".to_string(),
+      ));
+    }
+    Ok(())
+  }
+
+  pub(crate) fn server_tls_config(&self) -> Result<ServerTlsConfig, TransportError> {
+    self.validate()?;
+    let mut tls = ServerTlsConfig::new().identity(Identity::from_pem(&self.certificate, &self.private_key));
+    if let Some(ca_certificate) = self.ca_certificate.as_ref().filter(|_| self.client_auth) {
+      tls = tls.client_ca_root(Certificate::from_pem(ca_certificate));
+    }
+    Ok(tls)
+  }
+
+  pub(crate) fn client_tls_config(&self, address: &str) -> Result<ClientTlsConfig, TransportError> {
+    self.validate()?;
+    let domain_name = self.domain_name.clone().unwrap_or_else(|| host_of(address));
+    let mut tls = ClientTlsConfig::new().domain_name(domain_name);
+    if let Some(ca_certificate) = &self.ca_certificate {
+      tls = tls.ca_certificate(Certificate::from_pem(ca_certificate));
+    }
+    if self.client_auth {
+      tls = tls.identity(Identity::from_pem(&self.certificate, &self.private_key));
+    }
+    Ok(tls)
+  }
+}
+
+// The host of `host:port`, without the brackets of an IPv6 address.
+fn host_of(address: &str) -> String {
+  match address.parse::<SocketAddr>() {
+    Ok(socket_addr) => socket_addr.ip().to_string(),
+    Err(_) => address.rsplit_once(':').map_or(address, |(host, _)| host).to_string(),
+  }
+}
+
+impl Debug for TlsConfig {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("TlsConfig")
+      .field("certificate", &String::from_utf8_lossy(&self.certificate))
+      .field("private_key", &"<redacted>")
+      .field(
+        "ca_certificate",
+        &self.ca_certificate.as_ref().map(|c| String::from_utf8_lossy(c)),
+      )
+      .field("client_auth", &self.client_auth)
+      .field("domain_name", &self.domain_name)
+      .field("allowed_peer_identities", &self.allowed_peer_identities)
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{host_of, TlsConfig};
+  use crate::transport::TransportError;
+
+  #[test]
+  fn test_host_of_address() {
+    assert_eq!(host_of("localhost:8090"), "localhost");
+    assert_eq!(host_of("127.0.0.1:8090"), "127.0.0.1");
+    assert_eq!(host_of("[::1]:8090"), "::1");
+    assert_eq!(host_of("[fe80::1]:8090"), "fe80::1");
+    assert_eq!(host_of("localhost"), "localhost");
+  }
+
+  #[test]
+  fn test_client_auth_requires_a_ca_certificate() {
+    let tls_config = TlsConfig::new("certificate", "private key").with_client_auth(true);
+    assert!(matches!(tls_config.server_tls_config(), Err(TransportError::Tls(_))));
+    assert!(matches!(
+      tls_config.client_tls_config("localhost:8090"),
+      Err(TransportError::Tls(_))
+    ));
+
+    let tls_config = tls_config.with_ca_certificate("ca certificate");
+    assert!(tls_config.server_tls_config().is_ok());
+    assert!(tls_config.client_tls_config("localhost:8090").is_ok());
+  }
+}
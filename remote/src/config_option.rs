@@ -1,5 +1,18 @@
+use crate::config::activation_rate_limit::ActivationRateLimit;
+use crate::config::compression_config::CompressionConfig;
+use crate::config::heartbeat_config::HeartbeatConfig;
+use crate::config::kind_metadata::KindMetadata;
+use crate::config::outbound_queue_config::OutboundQueueConfig;
+use crate::config::reconnect_policy::ReconnectPolicy;
+use crate::config::reliable_delivery_config::ReliableDeliveryConfig;
+use crate::config::tls_config::TlsConfig;
 use crate::config::Config;
+#[cfg(any(test, feature = "test-support"))]
+use crate::fault_injector::FaultInjector;
+use crate::remote_authenticator::{RemoteAuthenticator, RemoteAuthenticatorHandle};
+use crate::transport::{Transport, TransportHandle};
 use nexus_actor_core_rs::actor::actor::Props;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub enum ConfigOption {
@@ -7,6 +20,19 @@ pub enum ConfigOption {
   SetPort(u16),
   SetAdvertisedHost(String),
   PutKind(String, Props),
+  PutKindMetadata(String, KindMetadata),
+  SetTlsConfig(TlsConfig),
+  SetAuthenticator(RemoteAuthenticatorHandle),
+  SetReconnectPolicy(ReconnectPolicy),
+  SetCompressionConfig(CompressionConfig),
+  SetHeartbeatConfig(HeartbeatConfig),
+  SetReliableDeliveryConfig(ReliableDeliveryConfig),
+  SetOutboundQueueConfig(OutboundQueueConfig),
+  SetTransport(TransportHandle),
+  SetBlockListPath(PathBuf),
+  SetActivationRateLimit(ActivationRateLimit),
+  #[cfg(any(test, feature = "test-support"))]
+  SetFaultInjector(FaultInjector),
 }
 
 impl ConfigOption {
@@ -24,6 +50,45 @@ impl ConfigOption {
       ConfigOption::PutKind(kind, props) => {
         config.put_kind(kind, props.clone()).await;
       }
+      ConfigOption::PutKindMetadata(kind, kind_metadata) => {
+        config.put_kind_metadata(kind, kind_metadata.clone()).await;
+      }
+      ConfigOption::SetTlsConfig(tls_config) => {
+        config.set_tls_config(tls_config.clone()).await;
+      }
+      ConfigOption::SetAuthenticator(authenticator) => {
+        config.set_authenticator(authenticator.clone()).await;
+      }
+      ConfigOption::SetReconnectPolicy(reconnect_policy) => {
+        config.set_reconnect_policy(reconnect_policy.clone()).await;
+      }
+      ConfigOption::SetCompressionConfig(compression_config) => {
+        config.set_compression_config(compression_config.clone()).await;
+      }
+      ConfigOption::SetHeartbeatConfig(heartbeat_config) => {
+        config.set_heartbeat_config(heartbeat_config.clone()).await;
+      }
+      ConfigOption::SetReliableDeliveryConfig(reliable_delivery_config) => {
+        config
+          .set_reliable_delivery_config(reliable_delivery_config.clone())
+          .await;
+      }
+      ConfigOption::SetOutboundQueueConfig(outbound_queue_config) => {
+        config.set_outbound_queue_config(outbound_queue_config.clone()).await;
+      }
+      ConfigOption::SetTransport(transport) => {
+        config.set_transport(transport.clone()).await;
+      }
+      ConfigOption::SetBlockListPath(block_list_path) => {
+        config.set_block_list_path(block_list_path.clone()).await;
+      }
+      ConfigOption::SetActivationRateLimit(activation_rate_limit) => {
+        config.set_activation_rate_limit(activation_rate_limit.clone()).await;
+      }
+      #[cfg(any(test, feature = "test-support"))]
+      ConfigOption::SetFaultInjector(fault_injector) => {
+        config.set_fault_injector(fault_injector.clone()).await;
+      }
     }
   }
 
@@ -42,4 +107,57 @@ impl ConfigOption {
   pub fn with_kind(kind: &str, props: Props) -> ConfigOption {
     ConfigOption::PutKind(kind.to_string(), props)
   }
+
+  pub fn with_kind_metadata(kind: &str, kind_metadata: KindMetadata) -> ConfigOption {
+    ConfigOption::PutKindMetadata(kind.to_string(), kind_metadata)
+  }
+
+  pub fn with_tls_config(tls_config: TlsConfig) -> ConfigOption {
+    ConfigOption::SetTlsConfig(tls_config)
+  }
+
+  pub fn with_authenticator(authenticator: impl RemoteAuthenticator) -> ConfigOption {
+    ConfigOption::SetAuthenticator(RemoteAuthenticatorHandle::new(authenticator))
+  }
+
+  pub fn with_reconnect_policy(reconnect_policy: ReconnectPolicy) -> ConfigOption {
+    ConfigOption::SetReconnectPolicy(reconnect_policy)
+  }
+
+  pub fn with_compression_config(compression_config: CompressionConfig) -> ConfigOption {
+    ConfigOption::SetCompressionConfig(compression_config)
+  }
+
+  pub fn with_heartbeat_config(heartbeat_config: HeartbeatConfig) -> ConfigOption {
+    ConfigOption::SetHeartbeatConfig(heartbeat_config)
+  }
+
+  pub fn with_reliable_delivery_config(reliable_delivery_config: ReliableDeliveryConfig) -> ConfigOption {
+    ConfigOption::SetReliableDeliveryConfig(reliable_delivery_config)
+  }
+
+  pub fn with_outbound_queue_config(outbound_queue_config: OutboundQueueConfig) -> ConfigOption {
+    ConfigOption::SetOutboundQueueConfig(outbound_queue_config)
+  }
+
+  pub fn with_transport(transport: impl Transport) -> ConfigOption {
+    ConfigOption::SetTransport(TransportHandle::new(transport))
+  }
+
+  // Keeps the BlockList in this file, so that blocks survive a restart.
+  pub fn with_block_list_path(block_list_path: impl Into<PathBuf>) -> ConfigOption {
+    ConfigOption::SetBlockListPath(block_list_path.into())
+  }
+
+  // Limits the ActorPidRequests the activator accepts from each peer; requests over the limit get QuotaExceeded.
+  pub fn with_activation_rate_limit(activation_rate_limit: ActivationRateLimit) -> ConfigOption {
+    ConfigOption::SetActivationRateLimit(activation_rate_limit)
+  }
+
+  // Applies the faults of `fault_injector` to the batches this node sends, and to the batches it receives over
+  // blocked links.
+  #[cfg(any(test, feature = "test-support"))]
+  pub fn with_fault_injector(fault_injector: FaultInjector) -> ConfigOption {
+    ConfigOption::SetFaultInjector(fault_injector)
+  }
 }
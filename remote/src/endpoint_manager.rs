@@ -13,10 +13,10 @@ use nexus_actor_core_rs::actor::actor_system::ActorSystem;
 use nexus_actor_core_rs::actor::context::{SenderPart, SpawnerPart, StopperPart};
 use nexus_actor_core_rs::actor::dispatch::future::ActorFutureError;
 use nexus_actor_core_rs::actor::dispatch::DeadLetterEvent;
-use nexus_actor_core_rs::actor::message::MessageHandle;
+use nexus_actor_core_rs::actor::message::{MessageHandle, SystemMessage};
 use nexus_actor_core_rs::actor::supervisor::{RestartingStrategy, SupervisorStrategyHandle};
 use nexus_actor_core_rs::event_stream::{EventHandler, Predicate, Subscription};
-use nexus_actor_core_rs::generated::actor::Pid;
+use nexus_actor_core_rs::generated::actor::{Pid, Terminated, TerminatedReason};
 use nexus_actor_utils_rs::collections::DashMapExtension;
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
@@ -316,6 +316,9 @@ impl EndpointManager {
       return;
     }
     let address = message.watchee.as_ref().expect("Not Found").address.clone();
+    if self.refuse_blocked(&address).await {
+      return;
+    }
     let endpoint = self.ensure_connected(&address).await;
     let pid = ExtendedPid::new(endpoint.get_watcher().clone());
     self
@@ -332,6 +335,21 @@ impl EndpointManager {
       return;
     }
     let address = message.watchee.address.clone();
+    if self.refuse_blocked(&address).await {
+      // The watchee is unreachable, as if its node had terminated.
+      let watcher = ExtendedPid::new(message.watcher.clone());
+      let terminated = Terminated {
+        who: Some(message.watchee),
+        why: TerminatedReason::AddressTerminated as i32,
+      };
+      watcher
+        .send_system_message(
+          self.get_actor_system().await,
+          MessageHandle::new(SystemMessage::Terminate(terminated)),
+        )
+        .await;
+      return;
+    }
     let endpoint = self.ensure_connected(&address).await;
     let pid = ExtendedPid::new(endpoint.get_watcher().clone());
     self
@@ -348,6 +366,9 @@ impl EndpointManager {
       return;
     }
     let address = message.watchee.address.clone();
+    if self.refuse_blocked(&address).await {
+      return;
+    }
     let endpoint = self.ensure_connected(&address).await;
     let pid = ExtendedPid::new(endpoint.get_watcher().clone());
     self
@@ -361,22 +382,14 @@ impl EndpointManager {
 
   pub(crate) async fn remote_deliver(&self, message: RemoteDeliver) {
     if self.stopped.load(Ordering::SeqCst) {
-      let pid = ExtendedPid::new(message.target.clone());
-      let sender = message.sender.map(ExtendedPid::new);
-      self
-        .get_actor_system()
-        .await
-        .get_event_stream()
-        .await
-        .publish(MessageHandle::new(DeadLetterEvent {
-          pid: Some(pid),
-          message_handle: message.message.clone(),
-          sender,
-        }))
-        .await;
+      self.dead_letter(message).await;
       return;
     }
     let address = message.target.address.clone();
+    if self.refuse_blocked(&address).await {
+      self.dead_letter(message).await;
+      return;
+    }
     let endpoint = self.ensure_connected(&address).await;
     let pid = ExtendedPid::new(endpoint.get_writer().clone());
     self
@@ -388,8 +401,43 @@ impl EndpointManager {
       .await;
   }
 
+  // The dead letter process answers senders with a DeadLetterResponse.
+  async fn dead_letter(&self, message: RemoteDeliver) {
+    let pid = ExtendedPid::new(message.target.clone());
+    let sender = message.sender.map(ExtendedPid::new);
+    self
+      .get_actor_system()
+      .await
+      .get_event_stream()
+      .await
+      .publish(MessageHandle::new(DeadLetterEvent {
+        pid: Some(pid),
+        message_handle: message.message.clone(),
+        sender,
+      }))
+      .await;
+  }
+
+  // Blocked addresses are never dialed, and an endpoint connected before the block is torn down.
+  async fn refuse_blocked(&self, address: &str) -> bool {
+    if !self.get_remote().await.get_block_list().is_blocked(address).await {
+      return false;
+    }
+    tracing::debug!("EndpointManager refused to connect to blocked address: {}", address);
+    if self.connections.contains_key(address) {
+      self
+        .remove_endpoint(&EndpointTerminatedEvent {
+          address: address.to_string(),
+        })
+        .await;
+    }
+    true
+  }
+
   async fn ensure_connected(&self, address: &str) -> Endpoint {
-    match self.connections.get(address) {
+    // Shard guards must not be held across awaits or while the map is modified.
+    let endpoint_lazy = self.connections.get(address).map(|v| v.value().clone());
+    match endpoint_lazy {
       None => {
         let el = EndpointLazy::new(self.clone(), address);
         let (el2, _) = self.connections.load_or_store(address.to_string(), el);
@@ -400,8 +448,8 @@ impl EndpointManager {
   }
 
   async fn remove_endpoint(&self, message: &EndpointTerminatedEvent) {
-    if let Some(v) = self.connections.get(&message.address) {
-      let le = v.value();
+    let endpoint_lazy = self.connections.get(&message.address).map(|v| v.value().clone());
+    if let Some(le) = endpoint_lazy {
       if le
         .get_unloaded()
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
@@ -443,6 +491,10 @@ impl EndpointManager {
       .clone()
   }
 
+  pub(crate) fn has_endpoint(&self, address: &str) -> bool {
+    self.connections.contains_key(address)
+  }
+
   #[allow(clippy::type_complexity)]
   pub(crate) fn get_endpoint_reader_connections(
     &self,
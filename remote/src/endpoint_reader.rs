@@ -1,28 +1,47 @@
-use nexus_actor_core_rs::actor::actor::ExtendedPid;
+use nexus_actor_core_rs::actor::actor::{ActorProcess, ExtendedPid};
 use nexus_actor_core_rs::actor::actor_system::ActorSystem;
 use nexus_actor_core_rs::actor::context::SenderPart;
-use nexus_actor_core_rs::actor::message::{MessageEnvelope, MessageHandle, MessageHeaders, SystemMessage};
+use nexus_actor_core_rs::actor::message::{Message, MessageEnvelope, MessageHandle, MessageHeaders, SystemMessage};
 use nexus_actor_core_rs::actor::process::Process;
-use nexus_actor_core_rs::generated::actor::{Pid, Stop, Terminated, Unwatch, Watch};
+use nexus_actor_core_rs::generated::actor::{DeadLetterResponse, Pid, Stop, Terminated, Unwatch, Watch};
+use prost::Message as ProstMessage;
+use regex::Regex;
 
+use crate::compression::Compression;
+use crate::config::compression_config::CompressionConfig;
 use crate::endpoint_manager::{EndpointManager, RequestKeyWrapper};
 use crate::generated::remote;
 use crate::generated::remote::connect_request::ConnectionType;
 use crate::generated::remote::remoting_server::Remoting;
 use crate::generated::remote::{
-  ConnectRequest, GetProcessDiagnosticsRequest, GetProcessDiagnosticsResponse, ListProcessesRequest,
-  ListProcessesResponse, MessageBatch, RemoteMessage, ServerConnection,
+  ActorPidRequest, ActorPidResponse, ConnectRequest, GetProcessDiagnosticsRequest, GetProcessDiagnosticsResponse,
+  ListKindsRequest, ListKindsResponse, ListProcessesMatchType, ListProcessesRequest, ListProcessesResponse,
+  MessageBatch, RemoteMessage,
 };
-use crate::remote::Remote;
-use crate::serializer::{deserialize_any, deserialize_message, SerializerId};
+use crate::messages::EndpointRejectedEvent;
+use crate::peer_identity::PeerIdentity;
+use crate::reliable_delivery::{last_sequence_number, DeliveredSessions};
+use crate::remote::{Remote, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use crate::remote_authenticator::{
+  AuthenticationError, AuthenticationRequest, RemoteAuthenticator, ADDRESS_METADATA_KEY, AUTH_TOKEN_METADATA_KEY,
+  SYSTEM_ID_METADATA_KEY,
+};
+use crate::remote_metrics::{ActivationRejection, Direction, RemoteMetrics, UNKNOWN_LABEL};
+use crate::response_status_code::ResponseStatusCode;
+use crate::serializer::{SerializerError, SerializerId};
+use crate::serializer_registry::SerializerRegistry;
+use std::any::Any;
+use std::net::IpAddr;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Weak};
+use std::time::Instant;
 use thiserror::Error;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, Mutex};
 use tonic::codegen::tokio_stream::wrappers::ReceiverStream;
 use tonic::codegen::tokio_stream::{Stream, StreamExt};
+use tonic::metadata::MetadataMap;
 use tonic::{Request, Response, Status, Streaming};
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -35,9 +54,21 @@ pub enum EndpointReaderError {
   Deserialization(String),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+enum HandshakeRejection {
+  #[error("blocked")]
+  Blocked,
+  #[error("unsupported protocol version: {0} (supported: {min}..={max})", min = MIN_PROTOCOL_VERSION, max = PROTOCOL_VERSION)]
+  UnsupportedProtocolVersion(u32),
+  #[error("authentication failed: {0}")]
+  Unauthenticated(AuthenticationError),
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct EndpointReader {
   suspended: Arc<AtomicBool>,
+  // Reliable envelopes already delivered, per peer and writer session.
+  delivered_sequences: Arc<DeliveredSessions>,
   remote: Weak<Remote>,
 }
 
@@ -45,6 +76,7 @@ impl EndpointReader {
   pub(crate) fn new(remote: Weak<Remote>) -> Self {
     EndpointReader {
       suspended: Arc::new(AtomicBool::new(false)),
+      delivered_sequences: Arc::new(DeliveredSessions::default()),
       remote,
     }
   }
@@ -54,22 +86,241 @@ impl EndpointReader {
     response_tx: &Sender<Result<RemoteMessage, Status>>,
     connect_req: &ConnectRequest,
   ) -> Result<bool, Box<dyn std::error::Error>> {
-    match &connect_req.connection_type {
-      Some(ConnectionType::ServerConnection(sc)) => {
-        self.on_server_connection(response_tx, sc).await;
-      }
-      Some(ConnectionType::ClientConnection(_)) => {
-        // TODO: implement me
-        tracing::error!("ClientConnection not implemented");
-      }
+    let (system_id, address, protocol_version, auth_token, compressions) = match &connect_req.connection_type {
+      Some(ConnectionType::ServerConnection(sc)) => (
+        &sc.system_id,
+        sc.address.as_str(),
+        sc.protocol_version,
+        &sc.auth_token,
+        sc.compressions.as_slice(),
+      ),
+      // Client connections have no address to dial back; they only go through the handshake.
+      Some(ConnectionType::ClientConnection(cc)) => (&cc.system_id, "", cc.protocol_version, &cc.auth_token, &[][..]),
       _ => {
         tracing::warn!("Received unknown connection type");
         return Ok(true);
       }
+    };
+
+    let result = self.handshake(system_id, address, protocol_version, auth_token).await;
+    let compression = match &result {
+      Ok(()) => self.negotiate_compression(compressions).await,
+      Err(_) => Compression::None,
+    };
+    let (blocked, rejection_reason) = match &result {
+      Ok(()) => {
+        tracing::debug!("EndpointReader accepted connection from {}", system_id);
+        (false, String::new())
+      }
+      Err(rejection) => {
+        tracing::warn!("EndpointReader rejected connection from {}: {}", system_id, rejection);
+        (matches!(rejection, HandshakeRejection::Blocked), rejection.to_string())
+      }
+    };
+
+    response_tx
+      .send(Ok(RemoteMessage {
+        message_type: Some(remote::remote_message::MessageType::ConnectResponse(
+          remote::ConnectResponse {
+            blocked,
+            member_id: system_id.clone(),
+            protocol_version: PROTOCOL_VERSION,
+            rejection_reason: rejection_reason.clone(),
+            compression: compression.name().to_string(),
+          },
+        )),
+      }))
+      .await?;
+
+    if result.is_err() {
+      self
+        .get_actor_system()
+        .await
+        .get_event_stream()
+        .await
+        .publish(MessageHandle::new(EndpointRejectedEvent {
+          system_id: system_id.clone(),
+          address: address.to_string(),
+          reason: rejection_reason,
+        }))
+        .await;
+      return Ok(true);
     }
     Ok(false)
   }
 
+  async fn negotiate_compression(&self, offered: &[String]) -> Compression {
+    match self.get_compression_config().await {
+      Some(compression_config) => compression_config.negotiate(offered),
+      None => Compression::None,
+    }
+  }
+
+  async fn handshake(
+    &self,
+    system_id: &str,
+    address: &str,
+    protocol_version: u32,
+    auth_token: &str,
+  ) -> Result<(), HandshakeRejection> {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    if Self::is_blocked(&remote, system_id, address).await {
+      return Err(HandshakeRejection::Blocked);
+    }
+    if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&protocol_version) {
+      return Err(HandshakeRejection::UnsupportedProtocolVersion(protocol_version));
+    }
+    self.authenticate(system_id, address, auth_token).await
+  }
+
+  async fn is_blocked(remote: &Remote, system_id: &str, address: &str) -> bool {
+    let block_list = remote.get_block_list();
+    block_list.is_blocked(system_id).await || (!address.is_empty() && block_list.is_blocked(address).await)
+  }
+
+  async fn authenticate(&self, system_id: &str, address: &str, auth_token: &str) -> Result<(), HandshakeRejection> {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    match remote.get_config().get_authenticator().await {
+      Some(authenticator) => authenticator
+        .authenticate(&AuthenticationRequest {
+          system_id: system_id.to_string(),
+          address: address.to_string(),
+          auth_token: auth_token.to_string(),
+        })
+        .await
+        .map_err(HandshakeRejection::Unauthenticated),
+      None => Ok(()),
+    }
+  }
+
+  // Message batches are sent on their own streams, so they carry the credentials as metadata. Returns the system id
+  // of the peer when accepted.
+  async fn authenticate_metadata(&self, metadata: &MetadataMap) -> Option<String> {
+    let get = |key: &str| {
+      metadata
+        .get(key)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+    };
+    let system_id = get(SYSTEM_ID_METADATA_KEY);
+    let address = get(ADDRESS_METADATA_KEY);
+    // Connections established before the peer was blocked are cut off here.
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    if Self::is_blocked(&remote, &system_id, &address).await {
+      tracing::debug!("EndpointReader rejected stream from blocked peer {}", system_id);
+      return None;
+    }
+    self
+      .authenticate(&system_id, &address, &get(AUTH_TOKEN_METADATA_KEY))
+      .await
+      .map_err(|rejection| tracing::debug!("EndpointReader rejected stream from {}: {}", system_id, rejection))
+      .ok()
+      .map(|_| system_id)
+  }
+
+  // Activation rate limits are keyed on who is behind a connection rather than on the senders it names: its TLS
+  // identity, else the system id an authenticator accepted, else its IP address.
+  async fn get_peer_key(
+    &self,
+    peer_identity: Option<&PeerIdentity>,
+    system_id: Option<&str>,
+    socket_address: Option<IpAddr>,
+    peer_address: &str,
+  ) -> String {
+    if let Some(peer_identity) = peer_identity {
+      return peer_identity.to_string();
+    }
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    if let Some(system_id) = system_id {
+      if remote.get_config().get_authenticator().await.is_some() {
+        return system_id.to_string();
+      }
+    }
+    socket_address.map_or_else(|| peer_address.to_string(), |address| address.to_string())
+  }
+
+  // Refuses an activation request once the peer has used up its rate limit, answering for the Activator.
+  async fn admit_activation(&self, peer_key: &str, request: &ActorPidRequest, sender: Option<&Pid>) -> bool {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    if remote.get_activations().try_acquire(peer_key) {
+      return true;
+    }
+    tracing::warn!(
+      "Activation rate limit exceeded: peer = {}, kind = {}",
+      peer_key,
+      request.kind
+    );
+    if let Some(metrics) = remote.get_metrics() {
+      // The kind is chosen by the peer, so only registered kinds are labelled by name.
+      let kind = if remote.get_kinds().contains_key(&request.kind) {
+        request.kind.as_str()
+      } else {
+        UNKNOWN_LABEL
+      };
+      metrics.increment_activation_rejections(kind, ActivationRejection::RateLimit);
+    }
+    if let Some(sender) = sender {
+      self
+        .get_actor_system()
+        .await
+        .get_root_context()
+        .await
+        .send(
+          ExtendedPid::new(sender.clone()),
+          MessageHandle::new(ActorPidResponse {
+            pid: None,
+            status_code: ResponseStatusCode::QuotaExceeded as i32,
+          }),
+        )
+        .await;
+    }
+    false
+  }
+
+  async fn authorize_peer(&self, peer_identity: Option<&PeerIdentity>) -> Result<(), Status> {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    let allowed_peer_identities = remote
+      .get_config()
+      .get_tls_config()
+      .await
+      .and_then(|tls_config| tls_config.allowed_peer_identities);
+    match peer_identity {
+      Some(peer_identity) => {
+        for name in peer_identity.names() {
+          if remote.get_block_list().is_blocked(name).await {
+            tracing::debug!("EndpointReader blocked peer {}", peer_identity);
+            return Err(Status::permission_denied(format!("Peer {} is blocked", peer_identity)));
+          }
+        }
+        if let Some(allowed_peer_identities) = allowed_peer_identities {
+          if !allowed_peer_identities.iter().any(|name| peer_identity.matches(name)) {
+            tracing::debug!("EndpointReader rejected peer {} not in allow-list", peer_identity);
+            return Err(Status::permission_denied(format!(
+              "Peer {} is not allowed",
+              peer_identity
+            )));
+          }
+        }
+        Ok(())
+      }
+      None if allowed_peer_identities.is_some() => Err(Status::unauthenticated("Peer identity is required")),
+      None => Ok(()),
+    }
+  }
+
+  // Unary requests pass the same checks as the message streams of a peer.
+  async fn authorize_request<T>(&self, request: &Request<T>) -> Result<(), Status> {
+    let peer_identity = request
+      .peer_certs()
+      .and_then(|certificates| PeerIdentity::from_certificates(&certificates));
+    self.authorize_peer(peer_identity.as_ref()).await?;
+    match self.authenticate_metadata(request.metadata()).await {
+      Some(_) => Ok(()),
+      None => Err(Status::unauthenticated("Peer is not authenticated")),
+    }
+  }
+
   async fn get_actor_system(&self) -> ActorSystem {
     self
       .remote
@@ -79,202 +330,330 @@ impl EndpointReader {
       .clone()
   }
 
-  async fn on_server_connection(&self, response_tx: &Sender<Result<RemoteMessage, Status>>, sc: &ServerConnection) {
-    if self
+  fn get_serializer_registry(&self) -> SerializerRegistry {
+    self
       .remote
       .upgrade()
       .expect("Remote has been dropped")
-      .get_block_list()
-      .is_blocked(&sc.system_id)
-      .await
-    {
-      tracing::debug!("EndpointReader blocked connection from {}", sc.system_id);
-      if let Err(e) = response_tx
-        .send(Ok(RemoteMessage {
-          message_type: Some(remote::remote_message::MessageType::ConnectResponse(
-            remote::ConnectResponse {
-              blocked: true,
-              member_id: sc.system_id.clone(),
-            },
-          )),
-        }))
-        .await
-      {
-        tracing::error!("EndpointReader failed to send ConnectResponse message: {}", e);
-      }
+      .get_serializer_registry()
+      .clone()
+  }
+
+  // Peers only compress with an algorithm negotiated from this configuration.
+  async fn get_compression_config(&self) -> Option<CompressionConfig> {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    remote.get_config().get_compression_config().await
+  }
+
+  fn get_metrics(&self) -> Option<RemoteMetrics> {
+    self.remote.upgrade().and_then(|remote| remote.get_metrics().cloned())
+  }
+
+  // Metrics label of a peer. Its address comes from metadata the peer chose, so it is only used when an authenticator
+  // vouched for it or this node has an endpoint to it; otherwise any peer could create unbounded metric series.
+  async fn get_metrics_address(&self, peer_address: &str, advertised: bool) -> String {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    let authenticated = advertised && remote.get_config().get_authenticator().await.is_some();
+    if authenticated || self.get_endpoint_manager().await.has_endpoint(peer_address) {
+      peer_address.to_string()
     } else {
-      tracing::debug!("EndpointReader accepted connection from {}", sc.system_id);
-      if let Err(e) = response_tx
-        .send(Ok(RemoteMessage {
-          message_type: Some(remote::remote_message::MessageType::ConnectResponse(
-            remote::ConnectResponse {
-              blocked: false,
-              member_id: sc.system_id.clone(),
-            },
-          )),
-        }))
-        .await
-      {
-        tracing::error!("EndpointReader failed to send ConnectResponse message: {}", e);
+      UNKNOWN_LABEL.to_string()
+    }
+  }
+
+  #[cfg(any(test, feature = "test-support"))]
+  async fn is_blocked_by_fault_injector(&self, peer_address: &str) -> bool {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    match remote.get_config().get_fault_injector().await {
+      Some(fault_injector) => {
+        let address = self.get_actor_system().await.get_address().await;
+        fault_injector.is_blocked(peer_address, &address).await
       }
+      None => false,
     }
   }
 
-  async fn on_message_batch(&self, message_batch: &MessageBatch) -> Result<(), EndpointReaderError> {
+  async fn on_message_batch(
+    &self,
+    message_batch: &MessageBatch,
+    metrics_address: &str,
+    peer_key: &str,
+  ) -> Result<(), EndpointReaderError> {
     tracing::info!("EndpointReader received message batch: {:?}", message_batch);
-    for envelope in &message_batch.envelopes {
-      let data = &envelope.message_data;
-      let mut sender = Pid::default();
-      let mut target = Pid::default();
-
-      let sender_opt = deserialize_sender(
-        &mut sender,
-        envelope.sender,
-        envelope.sender_request_id,
-        &message_batch.senders,
+    if let Some(metrics) = self.get_metrics() {
+      metrics.record_batch(
+        metrics_address,
+        Direction::Received,
+        message_batch.encoded_len(),
+        message_batch.envelopes.len(),
       );
+    }
+    for envelope in &message_batch.envelopes {
+      if envelope.sequence_number == 0 {
+        self
+          .on_message_envelope(message_batch, envelope, metrics_address, peer_key)
+          .await?;
+        continue;
+      }
+      // Retransmitted after the acknowledgement was lost.
+      if self.is_delivered(peer_key, message_batch.session_id, envelope.sequence_number) {
+        tracing::debug!(
+          "EndpointReader dropped duplicate envelope: session_id = {}, sequence_number = {}",
+          message_batch.session_id,
+          envelope.sequence_number
+        );
+        continue;
+      }
+      self
+        .on_message_envelope(message_batch, envelope, metrics_address, peer_key)
+        .await?;
+      self
+        .delivered_sequences
+        .insert(peer_key, message_batch.session_id, envelope.sequence_number);
+    }
+    Ok(())
+  }
 
-      tracing::info!("envelope.sender = {:?}", envelope.sender);
-      tracing::info!("envelope.senders = {:?}", message_batch.senders);
-      tracing::info!("sender_opt = {:?}", sender_opt);
-
-      let target = deserialize_target(
-        &mut target,
-        envelope.target,
-        envelope.target_request_id,
-        &message_batch.targets,
-      )
-      .map(ExtendedPid::new)
-      .ok_or_else(|| {
-        tracing::error!("EndpointReader received message with unknown target");
-        EndpointReaderError::UnknownTarget
-      })?;
-
-      // TODO
-      let serializer_id = SerializerId::try_from(envelope.serializer_id).expect("Invalid serializer id");
-
-      let result = match deserialize_any(
-        data,
-        &serializer_id,
-        "nexus_actor_remote_rs::generated::cluster::PubSubBatchTransport",
-      ) {
-        Ok(v) => Some(v),
-        Err(_) => match deserialize_any(
-          data,
-          &serializer_id,
-          "nexus_actor_remote_rs::generated::cluster::DeliverBatchRequestTransport",
-        ) {
-          Ok(v) => Some(v),
-          Err(_) => match deserialize_any(
-            data,
-            &serializer_id,
-            "nexus_actor_remote_rs::generated::cluster::PubSubAutoRespondBatchTransport",
-          ) {
-            Ok(v) => Some(v),
-            Err(_) => None,
-          },
-        },
-      };
+  fn is_delivered(&self, peer_key: &str, session_id: u64, sequence_number: u64) -> bool {
+    self.delivered_sequences.contains(peer_key, session_id, sequence_number)
+  }
 
-      match result {
-        Some(message) => {
-          if let Some(t) = message.downcast_ref::<Terminated>() {
-            let terminated = SystemMessage::of_terminate(t.clone());
-            self
-              .get_actor_system()
-              .await
-              .get_root_context()
-              .await
-              .send(target.clone(), MessageHandle::new(terminated))
-              .await;
-          }
-          if message.downcast_ref::<Stop>().is_some() {
-            let system_message = SystemMessage::of_stop();
-            let ref_process = self
-              .get_actor_system()
-              .await
-              .get_process_registry()
-              .await
-              .get_local_process(target.id())
-              .await
-              .ok_or(EndpointReaderError::UnknownTarget)?;
-            ref_process
-              .send_system_message(&target, MessageHandle::new(system_message))
-              .await;
-          }
-          if let Some(watch) = message.downcast_ref::<Watch>() {
-            let system_message = SystemMessage::of_watch(watch.clone());
-            let ref_process = self
-              .get_actor_system()
-              .await
-              .get_process_registry()
-              .await
-              .get_local_process(target.id())
-              .await
-              .ok_or(EndpointReaderError::UnknownTarget)?;
-            ref_process
-              .send_system_message(&target, MessageHandle::new(system_message))
-              .await;
-          }
-          if let Some(unwatch) = message.downcast_ref::<Unwatch>() {
-            let system_message = SystemMessage::of_unwatch(unwatch.clone());
-            let ref_process = self
-              .get_actor_system()
-              .await
-              .get_process_registry()
-              .await
-              .get_local_process(target.id())
-              .await
-              .ok_or(EndpointReaderError::UnknownTarget)?;
-            ref_process
-              .send_system_message(&target, MessageHandle::new(system_message))
-              .await;
-          }
+  async fn on_message_envelope(
+    &self,
+    message_batch: &MessageBatch,
+    envelope: &remote::MessageEnvelope,
+    metrics_address: &str,
+    peer_key: &str,
+  ) -> Result<(), EndpointReaderError> {
+    let decompressed;
+    let data = match Compression::try_from(envelope.compression) {
+      Ok(Compression::None) => &envelope.message_data,
+      Ok(compression) => {
+        let compression_config = self
+          .get_compression_config()
+          .await
+          .filter(|compression_config| compression_config.algorithms.contains(&compression))
+          .ok_or_else(|| {
+            EndpointReaderError::Deserialization(format!("compression was not negotiated: {}", compression))
+          })?;
+        decompressed = compression
+          .decompress(&envelope.message_data, compression_config.max_decompressed_size)
+          .map_err(|e| EndpointReaderError::Deserialization(e.to_string()))?;
+        &decompressed
+      }
+      Err(e) => return Err(EndpointReaderError::Deserialization(e.to_string())),
+    };
+    let mut sender = Pid::default();
+    let mut target = Pid::default();
+
+    let sender_opt = deserialize_sender(
+      &mut sender,
+      envelope.sender,
+      envelope.sender_request_id,
+      &message_batch.senders,
+    );
+
+    tracing::info!("envelope.sender = {:?}", envelope.sender);
+    tracing::info!("envelope.senders = {:?}", message_batch.senders);
+    tracing::info!("sender_opt = {:?}", sender_opt);
+
+    let target = deserialize_target(
+      &mut target,
+      envelope.target,
+      envelope.target_request_id,
+      &message_batch.targets,
+    )
+    .map(ExtendedPid::new)
+    .ok_or_else(|| {
+      tracing::error!("EndpointReader received message with unknown target");
+      EndpointReaderError::UnknownTarget
+    })?;
+
+    let serializer_id = SerializerId::try_from(envelope.serializer_id).map_err(|_| {
+      EndpointReaderError::Deserialization(format!("invalid serializer id: {}", envelope.serializer_id))
+    })?;
+    let manifest = message_batch
+      .type_names
+      .get(envelope.type_id as usize)
+      .map(String::as_str)
+      .unwrap_or_default();
+    let deserialize_started = Instant::now();
+    let deserialized = self
+      .get_serializer_registry()
+      .deserialize_manifest(data, &serializer_id, manifest);
+    if let Some(metrics) = self.get_metrics() {
+      // Only registered types are labelled by name, since the manifest is chosen by the peer.
+      let message_type = if deserialized.is_ok() { manifest } else { UNKNOWN_LABEL };
+      metrics.record_deserialize_duration(metrics_address, message_type, deserialize_started.elapsed());
+    }
+    let message = match deserialized {
+      Ok(message) => message,
+      Err(e @ (SerializerError::UnknownType(_) | SerializerError::SerializerNotFound { .. })) => {
+        tracing::warn!(
+          "EndpointReader received undeliverable message: type = {}, target = {}, error = {}",
+          manifest,
+          target,
+          e
+        );
+        if let Some(sender) = sender_opt {
+          self.send_undeliverable(ExtendedPid::new(sender), &target).await;
         }
-        None => {
-          let type_name = message_batch.type_names.get(envelope.type_id as usize).unwrap();
-          let data_arc = deserialize_message(data, &serializer_id, type_name)
-            .map_err(|e| EndpointReaderError::Deserialization(e.to_string()))?;
-          let msg_handle = MessageHandle::new_arc(data_arc.clone());
-          tracing::info!("EndpointReader received message: {:?}", data_arc);
-
-          if sender_opt.is_none() && envelope.message_header.is_none() {
-            tracing::info!("EndpointReader received message with no sender and no header");
-            self
-              .get_actor_system()
-              .await
-              .get_root_context()
-              .await
-              .send(target, msg_handle)
-              .await;
-            continue;
-          }
+        return Ok(());
+      }
+      Err(e) => return Err(EndpointReaderError::Deserialization(e.to_string())),
+    };
+    tracing::info!("EndpointReader received message: {:?}", message);
+
+    if self
+      .get_actor_system()
+      .await
+      .get_process_registry()
+      .await
+      .find_local_process(target.id())
+      .is_none()
+    {
+      return self
+        .on_unknown_target(target, sender_opt.map(ExtendedPid::new), message)
+        .await;
+    }
+    if let Some(request) = message.as_any().downcast_ref::<ActorPidRequest>() {
+      if target.id() == "activator" && !self.admit_activation(peer_key, request, sender_opt.as_ref()).await {
+        return Ok(());
+      }
+    }
+    // The peer may address this node through another name (e.g. a proxy), so deliver to the local address.
+    let target = ExtendedPid::new(Pid {
+      address: self.get_actor_system().await.get_address().await,
+      ..target.inner_pid
+    });
 
-          let headers = if envelope.message_header.is_some() {
-            MessageHeaders::with_values(envelope.message_header.as_ref().unwrap().header_data.clone())
-          } else {
-            MessageHeaders::default()
-          };
-
-          let sender = ExtendedPid::new(sender_opt.expect("Sender not found"));
-          let local_me = MessageEnvelope::new(msg_handle)
-            .with_header(headers)
-            .with_sender(sender);
-          tracing::info!("EndpointReader received message: {:?}", local_me);
-          tracing::info!("EndpointReader: target: {:?}", target);
+    if let Some(system_message) = Self::to_system_message(message.as_any()) {
+      match system_message {
+        SystemMessage::Terminate(_) => {
           self
             .get_actor_system()
             .await
             .get_root_context()
             .await
-            .send(target, MessageHandle::new(local_me))
+            .send(target, MessageHandle::new(system_message))
+            .await;
+        }
+        _ => {
+          let ref_process = self
+            .get_actor_system()
+            .await
+            .get_process_registry()
+            .await
+            .get_local_process(target.id())
+            .await
+            .ok_or(EndpointReaderError::UnknownTarget)?;
+          ref_process
+            .send_system_message(&target, MessageHandle::new(system_message))
             .await;
         }
       }
+      return Ok(());
+    }
+
+    let msg_handle = MessageHandle::new_arc(message);
+    if sender_opt.is_none() && envelope.message_header.is_none() {
+      tracing::info!("EndpointReader received message with no sender and no header");
+      self
+        .get_actor_system()
+        .await
+        .get_root_context()
+        .await
+        .send(target, msg_handle)
+        .await;
+      return Ok(());
+    }
+
+    let headers = if envelope.message_header.is_some() {
+      MessageHeaders::with_values(envelope.message_header.as_ref().unwrap().header_data.clone())
+    } else {
+      MessageHeaders::default()
+    };
+
+    let mut local_me = MessageEnvelope::new(msg_handle).with_header(headers);
+    if let Some(sender) = sender_opt {
+      local_me = local_me.with_sender(ExtendedPid::new(sender));
     }
+    tracing::info!("EndpointReader received message: {:?}", local_me);
+    tracing::info!("EndpointReader: target: {:?}", target);
+    self
+      .get_actor_system()
+      .await
+      .get_root_context()
+      .await
+      .send(target, MessageHandle::new(local_me))
+      .await;
     Ok(())
   }
 
+  // Dead letters the message locally; the sender is told directly so that a pending request future fails fast.
+  async fn on_unknown_target(
+    &self,
+    target: ExtendedPid,
+    sender: Option<ExtendedPid>,
+    message: Arc<dyn Message>,
+  ) -> Result<(), EndpointReaderError> {
+    tracing::warn!(
+      "EndpointReader received message for unknown target: target = {}",
+      target
+    );
+    let dead_letter = self.get_actor_system().await.get_dead_letter().await;
+    match Self::to_system_message(message.as_any()) {
+      // The dead letter process answers a Watch with Terminated(NotFound) itself.
+      Some(system_message) => {
+        dead_letter
+          .send_system_message(&target, MessageHandle::new(system_message))
+          .await;
+      }
+      None => {
+        // Without the sender envelope, so the dead letter process does not answer a second time.
+        dead_letter
+          .send_user_message(Some(&target), MessageHandle::new_arc(message))
+          .await;
+        if let Some(sender) = sender {
+          self.send_undeliverable(sender, &target).await;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  fn to_system_message(message: &(dyn Any + Send + Sync)) -> Option<SystemMessage> {
+    if let Some(terminated) = message.downcast_ref::<Terminated>() {
+      return Some(SystemMessage::of_terminate(terminated.clone()));
+    }
+    if message.downcast_ref::<Stop>().is_some() {
+      return Some(SystemMessage::of_stop());
+    }
+    if let Some(watch) = message.downcast_ref::<Watch>() {
+      return Some(SystemMessage::of_watch(watch.clone()));
+    }
+    if let Some(unwatch) = message.downcast_ref::<Unwatch>() {
+      return Some(SystemMessage::of_unwatch(unwatch.clone()));
+    }
+    None
+  }
+
+  // Tells the sender that the message could not be delivered, which fails a pending request future.
+  async fn send_undeliverable(&self, sender: ExtendedPid, target: &ExtendedPid) {
+    self
+      .get_actor_system()
+      .await
+      .get_root_context()
+      .await
+      .send(
+        sender,
+        MessageHandle::new(DeadLetterResponse {
+          target: Some(target.inner_pid.clone()),
+        }),
+      )
+      .await;
+  }
+
   pub fn set_suspend(&mut self, suspend: bool) {
     self.suspended.store(suspend, std::sync::atomic::Ordering::SeqCst);
   }
@@ -307,11 +686,19 @@ impl EndpointReader {
   }
 }
 
+fn connect_request_system_id(connect_req: &ConnectRequest) -> Option<&str> {
+  match &connect_req.connection_type {
+    Some(ConnectionType::ServerConnection(sc)) => Some(&sc.system_id),
+    Some(ConnectionType::ClientConnection(cc)) => Some(&cc.system_id),
+    None => None,
+  }
+}
+
 fn deserialize_sender(pid: &mut Pid, index: i32, request_id: u32, arr: &[Pid]) -> Option<Pid> {
   if index == 0 {
     None
   } else {
-    *pid = arr[index as usize - 1].clone();
+    *pid = arr.get(index as usize - 1)?.clone();
     if request_id > 0 {
       *pid = pid.clone();
       pid.request_id = request_id;
@@ -323,7 +710,7 @@ fn deserialize_sender(pid: &mut Pid, index: i32, request_id: u32, arr: &[Pid]) -
 }
 
 fn deserialize_target(pid: &mut Pid, index: i32, request_id: u32, arr: &[Pid]) -> Option<Pid> {
-  *pid = arr[index as usize].clone();
+  *pid = arr.get(index as usize)?.clone();
   if request_id > 0 {
     *pid = pid.clone();
     pid.request_id = request_id;
@@ -339,6 +726,24 @@ impl Remoting for EndpointReader {
 
   async fn receive(&self, request: Request<Streaming<RemoteMessage>>) -> Result<Response<Self::ReceiveStream>, Status> {
     tracing::info!("EndpointReader is starting");
+    // Writers send their advertised address, older peers are identified by their socket.
+    let advertised_address = request
+      .metadata()
+      .get(ADDRESS_METADATA_KEY)
+      .and_then(|address| address.to_str().ok())
+      .map(str::to_string);
+    let advertised = advertised_address.is_some();
+    let peer_address = advertised_address
+      .or_else(|| request.remote_addr().map(|address| address.to_string()))
+      .unwrap_or_default();
+    let socket_address = request.remote_addr().map(|address| address.ip());
+    let peer_identity = request
+      .peer_certs()
+      .and_then(|certificates| PeerIdentity::from_certificates(&certificates));
+    self.authorize_peer(peer_identity.as_ref()).await?;
+    if let Some(peer_identity) = &peer_identity {
+      tracing::debug!("EndpointReader verified peer identity: {}", peer_identity);
+    }
     let suspended = self.suspended.clone();
 
     let request_arc = Arc::new(Mutex::new(request));
@@ -382,6 +787,20 @@ impl Remoting for EndpointReader {
       }
     });
 
+    let (mut authenticated, mut peer_key) = {
+      let request_mg = request_arc.lock().await;
+      let system_id = self.authenticate_metadata(request_mg.metadata()).await;
+      let peer_key = self
+        .get_peer_key(
+          peer_identity.as_ref(),
+          system_id.as_deref(),
+          socket_address,
+          &peer_address,
+        )
+        .await;
+      (system_id.is_some(), peer_key)
+    };
+
     tokio::spawn({
       let cloned_self = self.clone();
       let cloned_request_arc = request_arc.clone();
@@ -398,16 +817,52 @@ impl Remoting for EndpointReader {
               match remote_msg.message_type {
                 Some(message_type) => match message_type {
                   remote::remote_message::MessageType::ConnectRequest(connect_req) => {
-                    if let Err(e) = cloned_self.on_connect_request(&cloned_response_tx, &connect_req).await {
-                      tracing::error!("Failed to handle connect request, {}", e);
-                      break;
+                    match cloned_self.on_connect_request(&cloned_response_tx, &connect_req).await {
+                      Ok(true) => break,
+                      Ok(false) => authenticated = true,
+                      Err(e) => {
+                        tracing::error!("Failed to handle connect request, {}", e);
+                        break;
+                      }
                     }
+                    peer_key = cloned_self
+                      .get_peer_key(
+                        peer_identity.as_ref(),
+                        connect_request_system_id(&connect_req),
+                        socket_address,
+                        &peer_address,
+                      )
+                      .await;
                   }
                   remote::remote_message::MessageType::MessageBatch(message_batch) => {
-                    if let Err(e) = cloned_self.on_message_batch(&message_batch).await {
+                    if !authenticated {
+                      tracing::warn!("EndpointReader dropped message batch from unauthenticated peer");
+                      break;
+                    }
+                    #[cfg(any(test, feature = "test-support"))]
+                    if cloned_self.is_blocked_by_fault_injector(&peer_address).await {
+                      continue;
+                    }
+                    let metrics_address = cloned_self.get_metrics_address(&peer_address, advertised).await;
+                    if let Err(e) = cloned_self
+                      .on_message_batch(&message_batch, &metrics_address, &peer_key)
+                      .await
+                    {
                       tracing::error!("Failed to handle message batch, {}", e);
                       break;
                     }
+                    if let Some(sequence_number) = last_sequence_number(&message_batch) {
+                      if let Err(e) = cloned_response_tx
+                        .send(Ok(RemoteMessage {
+                          message_type: Some(remote::remote_message::MessageType::MessageAck(remote::MessageAck {
+                            sequence_number,
+                          })),
+                        }))
+                        .await
+                      {
+                        tracing::error!("EndpointReader failed to send message ack: {}", e);
+                      }
+                    }
                   }
                   _ => {
                     tracing::warn!("Received unknown message type");
@@ -436,14 +891,85 @@ impl Remoting for EndpointReader {
     Ok(Response::new(Box::pin(output_stream) as Self::ReceiveStream))
   }
 
-  async fn list_processes(&self, _: Request<ListProcessesRequest>) -> Result<Response<ListProcessesResponse>, Status> {
-    Err(Status::unimplemented("Method not implemented"))
+  async fn list_processes(
+    &self,
+    request: Request<ListProcessesRequest>,
+  ) -> Result<Response<ListProcessesResponse>, Status> {
+    self.authorize_request(&request).await?;
+    let request = request.into_inner();
+    let match_type = ListProcessesMatchType::try_from(request.r#type)
+      .map_err(|_| Status::invalid_argument(format!("Unknown match type: {}", request.r#type)))?;
+    let matcher: Box<dyn Fn(&str) -> bool + Send + Sync> = match match_type {
+      ListProcessesMatchType::MatchPartOfString => {
+        let pattern = request.pattern.clone();
+        Box::new(move |id| id.contains(&pattern))
+      }
+      ListProcessesMatchType::MatchExactString => {
+        let pattern = request.pattern.clone();
+        Box::new(move |id| id == pattern)
+      }
+      ListProcessesMatchType::MatchRegex => {
+        let regex =
+          Regex::new(&request.pattern).map_err(|e| Status::invalid_argument(format!("Invalid pattern: {}", e)))?;
+        Box::new(move |id| regex.is_match(id))
+      }
+    };
+
+    let pids = self
+      .get_actor_system()
+      .await
+      .get_process_registry()
+      .await
+      .list_local_pids()
+      .await
+      .into_iter()
+      .filter(|pid| matcher(pid.id()))
+      .map(|pid| pid.inner_pid)
+      .collect();
+    Ok(Response::new(ListProcessesResponse { pids }))
   }
 
   async fn get_process_diagnostics(
     &self,
-    _: Request<GetProcessDiagnosticsRequest>,
+    request: Request<GetProcessDiagnosticsRequest>,
   ) -> Result<Response<GetProcessDiagnosticsResponse>, Status> {
-    Err(Status::unimplemented("Method not implemented"))
+    self.authorize_request(&request).await?;
+    let pid = request
+      .into_inner()
+      .pid
+      .ok_or_else(|| Status::invalid_argument("Pid is required"))?;
+    let process = self
+      .get_actor_system()
+      .await
+      .get_process_registry()
+      .await
+      .find_local_process(&pid.id)
+      .ok_or_else(|| Status::not_found(format!("Process not found: {}", pid.id)))?;
+    let actor_process = process
+      .as_any()
+      .downcast_ref::<ActorProcess>()
+      .ok_or_else(|| Status::failed_precondition(format!("Process is not an actor: {}", pid.id)))?;
+    let mut diagnostics_string = actor_process.get_diagnostics().await.to_string();
+    // The activator also reports the live actors it spawned per kind.
+    if pid.id == "activator" {
+      let remote = self.remote.upgrade().expect("Remote has been dropped");
+      let mut activations = remote
+        .get_activations()
+        .get_counts()
+        .iter()
+        .map(|entry| format!("{} = {}", entry.key(), entry.value().load(Ordering::Relaxed)))
+        .collect::<Vec<_>>();
+      activations.sort();
+      diagnostics_string.push_str(&format!("\nactivations: [{}]", activations.join(", ")));
+    }
+    Ok(Response::new(GetProcessDiagnosticsResponse { diagnostics_string }))
+  }
+
+  async fn list_kinds(&self, request: Request<ListKindsRequest>) -> Result<Response<ListKindsResponse>, Status> {
+    self.authorize_request(&request).await?;
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    let mut kinds = remote.get_kind_infos();
+    kinds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Response::new(ListKindsResponse { kinds }))
   }
 }
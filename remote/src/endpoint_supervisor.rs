@@ -14,7 +14,8 @@ use nexus_actor_core_rs::actor::supervisor::{
   Supervisor, SupervisorHandle, SupervisorStrategy, SupervisorStrategyHandle,
 };
 use std::any::Any;
-use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Weak};
 
 #[derive(Debug, Clone)]
 pub struct EndpointSupervisor {
@@ -36,10 +37,12 @@ impl EndpointSupervisor {
       .clone()
   }
 
-  fn endpoint_writer_mailbox_producer(&self) -> MailboxProducer {
+  fn endpoint_writer_mailbox_producer(&self, address: String, connecting: Arc<AtomicBool>) -> MailboxProducer {
     let cloned_remote = self.remote.clone();
     MailboxProducer::new(move || {
       let cloned_remote = cloned_remote.clone();
+      let cloned_address = address.clone();
+      let cloned_connecting = connecting.clone();
       async move {
         let config = cloned_remote
           .upgrade()
@@ -47,8 +50,12 @@ impl EndpointSupervisor {
           .get_config()
           .clone();
         MailboxHandle::new(EndpointWriterMailbox::new(
+          cloned_remote,
+          cloned_address,
           config.get_endpoint_writer_batch_size().await,
           10,
+          config.get_outbound_queue_config().await,
+          cloned_connecting,
         ))
       }
     })
@@ -61,14 +68,18 @@ impl EndpointSupervisor {
     mut ctx: ContextHandle,
   ) -> ExtendedPid {
     let config = self.get_config().await;
+    // Messages wait in the mailbox until the EndpointWriter has connected.
+    let connecting = Arc::new(AtomicBool::new(true));
+    let mailbox_producer = self.endpoint_writer_mailbox_producer(address.clone(), connecting.clone());
     let props = Props::from_async_actor_producer_with_opts(
       move |_| {
         let cloned_remote = remote.clone();
         let cloned_address = address.clone();
         let cloned_config = config.clone();
-        async move { EndpointWriter::new(cloned_remote, cloned_address, cloned_config) }
+        let cloned_connecting = connecting.clone();
+        async move { EndpointWriter::new(cloned_remote, cloned_address, cloned_config, cloned_connecting) }
       },
-      [Props::with_mailbox_producer(self.endpoint_writer_mailbox_producer())],
+      [Props::with_mailbox_producer(mailbox_producer)],
     )
     .await;
     ctx.spawn(props).await
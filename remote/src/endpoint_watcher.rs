@@ -1,16 +1,22 @@
-use crate::messages::{EndpointEvent, RemoteTerminate, RemoteUnwatch, RemoteWatch};
+use crate::config::heartbeat_config::HeartbeatConfig;
+use crate::failure_detector::PhiAccrualFailureDetector;
+use crate::messages::{
+  EndpointEvent, EndpointTerminatedEvent, Ping, Pong, RemoteTerminate, RemoteUnwatch, RemoteWatch,
+};
 use crate::remote::Remote;
 use crate::serializer::SerializerId;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use nexus_actor_core_rs::actor::actor::{Actor, ActorError, ExtendedPid, PidSet};
 use nexus_actor_core_rs::actor::actor_system::ActorSystem;
-use nexus_actor_core_rs::actor::context::{ContextHandle, InfoPart, MessagePart, StopperPart};
+use nexus_actor_core_rs::actor::context::{ContextHandle, InfoPart, MessagePart, SenderPart, StopperPart};
 use nexus_actor_core_rs::actor::message::{MessageHandle, SystemMessage};
 use nexus_actor_core_rs::actor::process::Process;
-use nexus_actor_core_rs::generated::actor::{Terminated, TerminatedReason, Unwatch, Watch};
+use nexus_actor_core_rs::generated::actor::{Pid, Terminated, TerminatedReason, Unwatch, Watch};
 use std::sync::{Arc, Weak};
-use tokio::sync::RwLock;
+use std::time::Instant;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone)]
 pub struct EndpointWatcher {
@@ -18,6 +24,7 @@ pub struct EndpointWatcher {
   address: String,
   watched: Arc<DashMap<String, PidSet>>,
   state: Arc<RwLock<State>>,
+  heartbeat: Arc<Mutex<Option<JoinHandle<()>>>>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -33,6 +40,7 @@ impl EndpointWatcher {
       address,
       watched: Arc::new(DashMap::new()),
       state: Arc::new(RwLock::new(State::Connected)),
+      heartbeat: Arc::new(Mutex::new(None)),
     }
   }
 
@@ -54,9 +62,75 @@ impl EndpointWatcher {
   }
 
   async fn initialize(&mut self) -> Result<(), ActorError> {
+    let remote = self.remote.upgrade().expect("Remote has been dropped");
+    if let Some(heartbeat_config) = remote.get_config().get_heartbeat_config().await {
+      let task = tokio::spawn(Self::run_heartbeat(
+        self.remote.clone(),
+        self.address.clone(),
+        heartbeat_config,
+      ));
+      let mut mg = self.heartbeat.lock().await;
+      *mg = Some(task);
+    }
     Ok(())
   }
 
+  async fn stop_heartbeat(&self) {
+    let mut mg = self.heartbeat.lock().await;
+    if let Some(task) = mg.take() {
+      task.abort();
+    }
+  }
+
+  // Pings the activator of the peer and terminates the endpoint once the failure detector suspects it, so that
+  // remote watchers learn about hung peers and partitions that never reset the connection.
+  async fn run_heartbeat(remote: Weak<Remote>, address: String, config: HeartbeatConfig) {
+    let mut detector = PhiAccrualFailureDetector::new(&config);
+    detector.heartbeat(Instant::now());
+    let activator = ExtendedPid::new(Pid::new(&address, "activator"));
+    let mut interval = tokio::time::interval(config.interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    interval.tick().await;
+    loop {
+      interval.tick().await;
+      let Some(remote) = remote.upgrade() else {
+        return;
+      };
+      let system = remote.get_actor_system().clone();
+      let future = system
+        .get_root_context()
+        .await
+        .request_future(activator.clone(), MessageHandle::new(Ping), config.timeout)
+        .await;
+      match future.result().await {
+        Ok(response) if response.is_typed::<Pong>() => detector.heartbeat(Instant::now()),
+        Ok(response) => tracing::warn!(
+          "Unexpected heartbeat response: address = {}, response = {:?}",
+          address,
+          response
+        ),
+        Err(err) => tracing::debug!("Heartbeat failed: address = {}, error = {}", address, err),
+      }
+      let phi = detector.phi(Instant::now());
+      if phi >= config.threshold {
+        tracing::warn!(
+          "EndpointWatcher suspects peer is unreachable: address = {}, phi = {:.2}",
+          address,
+          phi
+        );
+        let terminated = EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
+          address: address.clone(),
+        });
+        system
+          .get_event_stream()
+          .await
+          .publish(MessageHandle::new(terminated))
+          .await;
+        return;
+      }
+    }
+  }
+
   async fn connected(&mut self, mut ctx: ContextHandle) -> Result<(), ActorError> {
     let system = self.get_actor_system();
     let msg = ctx.get_message_handle().await;
@@ -109,6 +183,7 @@ impl EndpointWatcher {
           let mut state = self.state.write().await;
           *state = State::Terminated;
         }
+        self.stop_heartbeat().await;
         ctx.stop(&ctx.get_self().await).await;
       }
     }
@@ -201,4 +276,9 @@ impl Actor for EndpointWatcher {
   async fn post_start(&mut self, _: ContextHandle) -> Result<(), ActorError> {
     self.initialize().await
   }
+
+  async fn post_stop(&mut self, _: ContextHandle) -> Result<(), ActorError> {
+    self.stop_heartbeat().await;
+    Ok(())
+  }
 }
@@ -1,29 +1,42 @@
+use crate::compression::Compression;
 use crate::config::Config;
+#[cfg(any(test, feature = "test-support"))]
+use crate::fault_injector::BatchFate;
 use crate::generated::remote::connect_request::ConnectionType;
 use crate::generated::remote::remote_message::MessageType;
 use crate::generated::remote::remoting_client::RemotingClient;
 use crate::generated::remote::{
   ConnectRequest, ConnectResponse, MessageBatch, MessageEnvelope, MessageHeader, RemoteMessage, ServerConnection,
 };
-use crate::messages::{EndpointConnectedEvent, EndpointEvent, EndpointTerminatedEvent, RemoteDeliver};
-use crate::remote::Remote;
-use crate::serializer::RootSerializable;
-use crate::serializer::{serialize_any, SerializerId};
+use crate::messages::{
+  EndpointConnectedEvent, EndpointEvent, EndpointReconnectingEvent, EndpointTerminatedEvent, RemoteDeliver,
+};
+use crate::reliable_delivery::{last_sequence_number, retain_envelopes, UnackedBatches};
+use crate::remote::{Remote, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use crate::remote_authenticator::{attach_credentials, RemoteAuthenticator};
+use crate::remote_metrics::{Direction, RemoteMetrics};
+use crate::serializer::{RootSerializable, SerializerId};
+use crate::serializer_registry::SerializerRegistry;
+use crate::transport::{Transport, TransportError};
 use async_trait::async_trait;
 use dashmap::DashMap;
-use futures::{StreamExt, TryFutureExt};
-use nexus_actor_core_rs::actor::actor::{Actor, ActorError, ErrorReason, ExtendedPid};
+use futures::StreamExt;
+use nexus_actor_core_rs::actor::actor::{Actor, ActorError, ExtendedPid};
 use nexus_actor_core_rs::actor::actor_system::ActorSystem;
-use nexus_actor_core_rs::actor::context::{BasePart, ContextHandle, InfoPart, MessagePart, SenderPart, StopperPart};
+use nexus_actor_core_rs::actor::context::{ContextHandle, InfoPart, MessagePart, SenderPart, StopperPart};
 use nexus_actor_core_rs::actor::dispatch::DeadLetterEvent;
 use nexus_actor_core_rs::actor::message::{Message, MessageHandle, ReadonlyMessageHeaders};
 use nexus_actor_core_rs::generated::actor::{DeadLetterResponse, Pid};
+use nexus_actor_message_derive_rs::Message;
+use prost::Message as ProstMessage;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Weak};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tonic::codegen::tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::Channel;
-use tonic::{Code, Response, Streaming};
+use tonic::{Code, Response, Status, Streaming};
 
 #[derive(Debug, Clone)]
 pub struct EndpointWriter {
@@ -31,30 +44,92 @@ pub struct EndpointWriter {
   address: String,
   conn: Arc<RwLock<Option<Channel>>>,
   stream: Arc<RwLock<Option<RemotingClient<Channel>>>>,
+  // Keeps the request stream of the handshake open, which the peer takes as the connection being alive.
+  handshake: Arc<Mutex<Option<mpsc::Sender<RemoteMessage>>>>,
+  // Incremented on every connection and disconnection so that a lost-connection notice from a closed or replaced
+  // stream is ignored.
+  generation: Arc<AtomicU64>,
+  // Shared with the EndpointWriterMailbox, which holds the RemoteDeliver messages while it is set.
+  connecting: Arc<AtomicBool>,
+  // Set while an EndpointWriterConnect is on its way, so another lost-connection notice doesn't start a second series.
+  connect_scheduled: bool,
+  // Fire-and-forget envelopes of the batches that failed to send, sent again once reconnected.
+  unsent: Vec<MessageBatch>,
+  // Negotiated with the peer during the handshake.
+  compression: Arc<RwLock<Compression>>,
+  // Reliable envelopes are numbered within a session that outlives reconnects, so the receiver can drop the
+  // ones it has already delivered when they are retransmitted.
+  session_id: u64,
+  next_sequence_number: Arc<AtomicU64>,
+  unacked: Arc<Mutex<UnackedBatches>>,
   remote: Weak<Remote>,
 }
 
+// Sent to the EndpointWriter itself when the connection with the given generation is lost.
+#[derive(Debug, Clone, PartialEq, Message)]
+struct EndpointWriterReconnect {
+  generation: u64,
+}
+
+// Sent to the EndpointWriter itself for every connection attempt, once the backoff of the previous one has passed.
+#[derive(Debug, Clone, PartialEq, Message)]
+struct EndpointWriterConnect {
+  attempt: u32,
+  reconnecting: bool,
+}
+
+// Sent to the EndpointWriter itself when the batch ending with the given sequence number was not acknowledged in time.
+#[derive(Debug, Clone, PartialEq, Message)]
+struct EndpointWriterRetransmit {
+  sequence_number: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum EndpointWriterError {
   #[error("Invalid URL: {0}")]
   InvalidUrl(String),
   #[error("Failed to connect to remote: {0}")]
   Connection(String),
+  #[error("Invalid TLS configuration: {0}")]
+  Tls(String),
   #[error("Failed to send connect request: {code}, {message}")]
   Response { code: Code, message: String },
   #[error("No response")]
   NoResponse,
   #[error("No field")]
   NoField,
+  #[error("Connection rejected by remote: {0}")]
+  Rejected(String),
+}
+
+impl From<TransportError> for EndpointWriterError {
+  fn from(error: TransportError) -> Self {
+    match error {
+      TransportError::InvalidAddress(message) => EndpointWriterError::InvalidUrl(message),
+      TransportError::Tls(message) => EndpointWriterError::Tls(message),
+      TransportError::Bind(message) | TransportError::Connection(message) | TransportError::Server(message) => {
+        EndpointWriterError::Connection(message)
+      }
+    }
+  }
 }
 
 impl EndpointWriter {
-  pub fn new(remote: Weak<Remote>, address: String, config: Config) -> Self {
+  pub fn new(remote: Weak<Remote>, address: String, config: Config, connecting: Arc<AtomicBool>) -> Self {
     Self {
       config,
       address,
       conn: Arc::new(RwLock::new(None)),
       stream: Arc::new(RwLock::new(None)),
+      handshake: Arc::new(Mutex::new(None)),
+      generation: Arc::new(AtomicU64::new(0)),
+      connecting,
+      connect_scheduled: false,
+      unsent: Vec::new(),
+      compression: Arc::new(RwLock::new(Compression::None)),
+      session_id: rand::random(),
+      next_sequence_number: Arc::new(AtomicU64::new(0)),
+      unacked: Arc::new(Mutex::new(UnackedBatches::default())),
       remote,
     }
   }
@@ -98,74 +173,248 @@ impl EndpointWriter {
       .clone()
   }
 
-  pub async fn initialize(&mut self, _: ContextHandle) {
-    let now = Instant::now();
+  fn get_metrics(&self) -> Option<RemoteMetrics> {
+    self.remote.upgrade().and_then(|remote| remote.get_metrics().cloned())
+  }
+
+  fn get_serializer_registry(&self) -> SerializerRegistry {
+    self
+      .remote
+      .upgrade()
+      .expect("Remote has been dropped")
+      .get_serializer_registry()
+      .clone()
+  }
+
+  pub async fn initialize(&mut self, ctx: ContextHandle) {
     tracing::info!("Started EndpointWriter. connecting: to {}", self.address);
-    let _i = 0;
-    let mut error: Option<EndpointWriterError> = None;
-    for i in 0..self.config.get_max_retry_count().await {
-      match self.initialize_internal().await {
-        Ok(_) => {
-          break;
+    self.connect(&ctx, false).await;
+  }
+
+  // Holds the RemoteDeliver messages in the mailbox and starts the connection attempts, unless they have started.
+  async fn connect(&mut self, ctx: &ContextHandle, reconnecting: bool) {
+    if self.connect_scheduled {
+      return;
+    }
+    self.connecting.store(true, Ordering::SeqCst);
+    if reconnecting {
+      tracing::warn!(
+        "EndpointWriter lost connection: address = {}, reconnecting",
+        self.address
+      );
+      if let Some(metrics) = self.get_metrics() {
+        metrics.increment_reconnect_count(&self.address);
+      }
+    }
+    self.schedule_connect(&ctx.get_self().await, 1, reconnecting).await;
+  }
+
+  // The backoff is waited out by a task rather than in receive, so the writer keeps handling its control messages.
+  async fn schedule_connect(&mut self, self_pid: &ExtendedPid, attempt: u32, reconnecting: bool) {
+    let policy = self.config.get_reconnect_policy().await;
+    let delay = if reconnecting {
+      let backoff = policy.backoff(attempt);
+      self
+        .publish_stream(MessageHandle::new(EndpointReconnectingEvent {
+          address: self.address.clone(),
+          attempt,
+          max_attempts: policy.max_attempts.max(1),
+          backoff,
+        }))
+        .await;
+      backoff
+    } else if attempt > 1 {
+      policy.backoff(attempt - 1)
+    } else {
+      Duration::ZERO
+    };
+    self.connect_scheduled = true;
+    let actor_system = self.get_actor_system().await;
+    let self_pid = self_pid.clone();
+    tokio::spawn(async move {
+      tokio::time::sleep(delay).await;
+      actor_system
+        .get_root_context()
+        .await
+        .send(
+          self_pid,
+          MessageHandle::new(EndpointWriterConnect { attempt, reconnecting }),
+        )
+        .await;
+    });
+  }
+
+  async fn on_connect(&mut self, ctx: &ContextHandle, connect: &EndpointWriterConnect) {
+    self.connect_scheduled = false;
+    let now = Instant::now();
+    let self_pid = ctx.get_self().await;
+    let max_attempts = self.config.get_reconnect_policy().await.max_attempts.max(1);
+    match self.initialize_internal(&self_pid).await {
+      Ok(_) => {
+        tracing::info!(
+          "EndpointWriter connected to remote: address = {}, cost = {} millis",
+          self.address,
+          now.elapsed().as_millis()
+        );
+        if connect.reconnecting && !(self.retransmit_unacked(&self_pid).await && self.send_unsent(&self_pid).await) {
+          self.connect(ctx, true).await;
+          return;
         }
-        Err(e) => {
-          error = Some(e.clone());
-          tracing::error!(
-            "Failed to connect to remote: address = {}, retrying... {}",
+        self.connecting.store(false, Ordering::SeqCst);
+      }
+      Err(e) => {
+        let give_up = match &e {
+          EndpointWriterError::Rejected(reason) => {
+            tracing::error!(
+              "Remote rejected connection: address = {}, reason = {}",
+              self.address,
+              reason
+            );
+            true
+          }
+          e if connect.attempt >= max_attempts => {
+            tracing::error!(
+              "Failed to connect to remote: address = {}, giving up after {} attempts: {}",
+              self.address,
+              connect.attempt,
+              e
+            );
+            true
+          }
+          e => {
+            tracing::error!(
+              "Failed to connect to remote: address = {}, retrying... {}/{}: {}",
+              self.address,
+              connect.attempt,
+              max_attempts,
+              e
+            );
+            false
+          }
+        };
+        if !give_up {
+          self
+            .schedule_connect(&self_pid, connect.attempt + 1, connect.reconnecting)
+            .await;
+          return;
+        }
+        self.close_client_conn().await;
+        if !self.unsent.is_empty() {
+          tracing::warn!(
+            "EndpointWriter dropped unsent message batches: address = {}, batches = {}",
             self.address,
-            i
+            self.unsent.len()
           );
-          tokio::time::sleep(self.config.get_retry_interval().await).await;
-          continue;
+          self.unsent.clear();
         }
+        self.publish_terminated().await;
+        // Without a stream, the held messages are answered with dead letters until the writer stops.
+        self.connecting.store(false, Ordering::SeqCst);
       }
     }
+  }
 
-    if error.is_some() {
-      let terminated = EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
-        address: self.address.clone(),
-      });
-      self
-        .get_actor_system()
-        .await
-        .get_event_stream()
-        .await
-        .publish(MessageHandle::new(terminated))
-        .await;
+  // Returns false if a batch could not be sent; it stays unsent until the next reconnect.
+  async fn send_unsent(&mut self, self_pid: &ExtendedPid) -> bool {
+    while let Some(batch) = self.unsent.first().cloned() {
+      if let Err(e) = self.send_batch(self_pid, batch).await {
+        tracing::error!("Failed to send message: address = {}, error = {:?}", self.address, e);
+        return false;
+      }
+      self.unsent.remove(0);
+    }
+    true
+  }
+
+  async fn retransmit(&mut self, ctx: &ContextHandle, sequence_number: u64) {
+    let Some(batch) = self.unacked.lock().await.get(sequence_number) else {
       return;
+    };
+    tracing::debug!(
+      "EndpointWriter retransmitting unacknowledged batch: address = {}, sequence_number = {}",
+      self.address,
+      sequence_number
+    );
+    let self_pid = ctx.get_self().await;
+    if let Err(e) = self.send_batch(&self_pid, batch).await {
+      tracing::error!(
+        "Failed to retransmit message batch: address = {}, error = {:?}",
+        self.address,
+        e
+      );
+      self.connect(ctx, true).await;
     }
+  }
 
+  // Returns false if a batch could not be sent.
+  async fn retransmit_unacked(&self, self_pid: &ExtendedPid) -> bool {
+    let pending = self.unacked.lock().await.pending();
+    if pending.is_empty() {
+      return true;
+    }
     tracing::info!(
-      "EndpointWriter connected to remote: address = {}, cost = {} millis",
+      "EndpointWriter retransmitting unacknowledged batches: address = {}, batches = {}",
       self.address,
-      now.elapsed().as_millis()
+      pending.len()
     );
+    for batch in pending {
+      // Whatever is not sent now stays unacknowledged until the next reconnect.
+      if let Err(e) = self.send_batch(self_pid, batch).await {
+        tracing::error!(
+          "Failed to retransmit message batch: address = {}, error = {:?}",
+          self.address,
+          e
+        );
+        return false;
+      }
+    }
+    true
+  }
+
+  async fn publish_terminated(&mut self) {
+    let terminated = EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
+      address: self.address.clone(),
+    });
+    self.publish_stream(MessageHandle::new(terminated)).await;
   }
 
   async fn create_channel(&self) -> Result<Channel, EndpointWriterError> {
-    let address = format!("http://{}", self.address);
-    let endpoint = Channel::from_shared(address).map_err(|e| EndpointWriterError::InvalidUrl(e.to_string()))?;
-    endpoint
-      .connect()
-      .map_err(|e| EndpointWriterError::Connection(e.to_string()))
-      .await
+    create_channel(&self.config, &self.address).await
   }
 
   async fn connect_request(
     &self,
     remote_client: &mut RemotingClient<Channel>,
   ) -> Result<Response<Streaming<RemoteMessage>>, EndpointWriterError> {
+    let system_id = self.get_actor_system().await.get_id().await;
+    let address = self.get_actor_system().await.get_address().await;
+    let auth_token = self.create_auth_token(&system_id, &address).await;
+    let compressions = self
+      .config
+      .get_compression_config()
+      .await
+      .map(|compression_config| compression_config.offered_names())
+      .unwrap_or_default();
     let request = RemoteMessage {
       message_type: Some(MessageType::ConnectRequest(ConnectRequest {
         connection_type: Some(ConnectionType::ServerConnection(ServerConnection {
-          system_id: self.get_actor_system().await.get_id().await,
-          address: self.get_actor_system().await.get_address().await,
+          system_id,
+          address,
+          protocol_version: PROTOCOL_VERSION,
+          auth_token,
+          compressions,
         })),
       })),
     };
-    let request = tonic::Request::new(futures::stream::once(futures::future::ready(request)));
+    // The request stream stays open until close_client_conn, since the peer closes the response stream with it.
+    let (handshake_tx, handshake_rx) = mpsc::channel(1);
+    handshake_tx
+      .send(request)
+      .await
+      .expect("The handshake receiver is not dropped yet");
+    *self.handshake.lock().await = Some(handshake_tx);
     remote_client
-      .receive(request)
+      .receive(tonic::Request::new(ReceiverStream::new(handshake_rx)))
       .await
       .map_err(|status| EndpointWriterError::Response {
         code: status.code(),
@@ -173,6 +422,24 @@ impl EndpointWriter {
       })
   }
 
+  async fn create_auth_token(&self, system_id: &str, address: &str) -> String {
+    match self.config.get_authenticator().await {
+      Some(authenticator) => authenticator.create_token(system_id, address).await,
+      None => String::new(),
+    }
+  }
+
+  async fn attach_credentials<T>(&self, request: &mut tonic::Request<T>) {
+    let actor_system = self.get_actor_system().await;
+    attach_credentials(
+      self.config.get_authenticator().await,
+      actor_system.get_id().await,
+      actor_system.get_address().await,
+      request,
+    )
+    .await;
+  }
+
   async fn get_remote_message_in_response(
     response: &mut Response<Streaming<RemoteMessage>>,
   ) -> Result<RemoteMessage, EndpointWriterError> {
@@ -190,55 +457,78 @@ impl EndpointWriter {
     }
   }
 
-  async fn initialize_internal(&mut self) -> Result<(), EndpointWriterError> {
+  async fn initialize_internal(&mut self, self_pid: &ExtendedPid) -> Result<(), EndpointWriterError> {
+    // A previous attempt may have failed after the channel was set up.
+    self.close_client_conn().await;
+    let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
     let cloned_self = self.clone();
+    let cloned_self_pid = self_pid.clone();
 
     let channel = self.create_channel().await?;
-    assert!(self.get_conn().await.is_none(), "Connection is already set");
     self.set_conn(channel.clone()).await;
 
     let mut remote_client = RemotingClient::new(channel.clone());
-    assert!(self.get_stream().await.is_none(), "Stream is already set");
     self.set_stream(remote_client.clone()).await;
 
     let mut streaming_response = self.connect_request(&mut remote_client).await?;
     let remote_message = Self::get_remote_message_in_response(&mut streaming_response).await?;
     // FIXME
     let connect_response = Self::get_connect_response(remote_message)?;
+    Self::check_connect_response(&connect_response)?;
+    self.set_compression(&connect_response).await;
     tracing::info!(
       "Connected to remote: address = {}, connect_response = {:?}",
       self.address,
       connect_response
     );
 
+    if let Some(metrics) = self.get_metrics() {
+      metrics.increment_connections(&self.address);
+    }
     tokio::spawn(async move {
       let mut cloned_self = cloned_self.clone();
       let mut streaming = streaming_response.into_inner();
+      let metrics = cloned_self.get_metrics();
 
       while let Some(result) = streaming.next().await {
-        let terminated_event = match result {
+        match result {
           Err(e) => {
             tracing::error!("EndpointWriter failed to receive message: {}", e);
-            Some(EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
-              address: cloned_self.address.clone(),
-            }))
+            break;
           }
           Ok(msg) => {
             if let Some(MessageType::DisconnectRequest(_)) = msg.message_type {
-              Some(EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
+              if let Some(metrics) = &metrics {
+                metrics.decrement_connections(&cloned_self.address);
+              }
+              let terminated = EndpointEvent::EndpointTerminated(EndpointTerminatedEvent {
                 address: cloned_self.address.clone(),
-              }))
-            } else {
-              None
+              });
+              cloned_self.publish_stream(MessageHandle::new(terminated)).await;
+              return;
             }
           }
-        };
-
-        if let Some(event) = terminated_event {
-          cloned_self.publish_stream(MessageHandle::new(event)).await;
-          return;
         }
       }
+
+      // The stream ended without a DisconnectRequest, so the connection was lost rather than closed by the peer,
+      // unless the writer closed or replaced it itself.
+      if let Some(metrics) = &metrics {
+        metrics.decrement_connections(&cloned_self.address);
+      }
+      if cloned_self.generation.load(Ordering::SeqCst) != generation {
+        return;
+      }
+      cloned_self
+        .get_actor_system()
+        .await
+        .get_root_context()
+        .await
+        .send(
+          cloned_self_pid,
+          MessageHandle::new(EndpointWriterReconnect { generation }),
+        )
+        .await;
     });
 
     let connected = EndpointEvent::EndpointConnected(EndpointConnectedEvent {
@@ -249,6 +539,48 @@ impl EndpointWriter {
     Ok(())
   }
 
+  async fn set_compression(&self, connect_response: &ConnectResponse) {
+    let compression = Compression::from_name(&connect_response.compression).unwrap_or_else(|e| {
+      tracing::warn!(
+        "Remote selected {}, sending uncompressed: address = {}",
+        e,
+        self.address
+      );
+      Compression::None
+    });
+    let mut mg = self.compression.write().await;
+    *mg = compression;
+  }
+
+  async fn compress_payload(&self, bytes: Vec<u8>) -> (Vec<u8>, Compression) {
+    let compression = *self.compression.read().await;
+    let threshold = self
+      .config
+      .get_compression_config()
+      .await
+      .map_or(usize::MAX, |compression_config| compression_config.threshold);
+    let original_len = bytes.len();
+    let (bytes, compression) = if compression == Compression::None || original_len < threshold {
+      (bytes, Compression::None)
+    } else {
+      match compression.compress(&bytes) {
+        // Incompressible payloads are sent as they are.
+        Ok(compressed) if compressed.len() < original_len => (compressed, compression),
+        Ok(_) => (bytes, Compression::None),
+        Err(e) => {
+          tracing::error!("Failed to compress message: {:?}", e);
+          (bytes, Compression::None)
+        }
+      }
+    };
+    if let Some(remote) = self.remote.upgrade() {
+      remote
+        .get_compression_metrics(&self.address)
+        .record(original_len, bytes.len(), compression != Compression::None);
+    }
+    (bytes, compression)
+  }
+
   async fn publish_stream(&mut self, msg: MessageHandle) {
     self
       .get_actor_system()
@@ -275,6 +607,22 @@ impl EndpointWriter {
     }
   }
 
+  fn check_connect_response(connect_response: &ConnectResponse) -> Result<(), EndpointWriterError> {
+    if !connect_response.rejection_reason.is_empty() {
+      return Err(EndpointWriterError::Rejected(connect_response.rejection_reason.clone()));
+    }
+    if connect_response.blocked {
+      return Err(EndpointWriterError::Rejected("blocked".to_string()));
+    }
+    if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&connect_response.protocol_version) {
+      return Err(EndpointWriterError::Rejected(format!(
+        "unsupported protocol version: {} (supported: {}..={})",
+        connect_response.protocol_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+      )));
+    }
+    Ok(())
+  }
+
   async fn send_envelopes(
     &mut self,
     msg_list: impl IntoIterator<Item = MessageHandle>,
@@ -292,10 +640,10 @@ impl EndpointWriter {
     let mut sender_names = DashMap::new();
     let mut sender_names_arr = vec![];
 
-    let serializer_id = SerializerId::None;
+    let reliable_delivery = self.config.get_reliable_delivery_config().await;
 
     for msg in msg_list {
-      let typed_msg = msg.to_typed::<EndpointEvent>();
+      let typed_msg = msg.as_typed::<EndpointEvent>();
       if let Some(EndpointEvent::EndpointTerminated(_)) = typed_msg {
         tracing::info!("EndpointWriter received EndpointTerminated");
         ctx.stop(&ctx.get_self().await).await;
@@ -303,7 +651,7 @@ impl EndpointWriter {
       }
 
       let rd = msg
-        .to_typed::<RemoteDeliver>()
+        .take_or_clone::<RemoteDeliver>()
         .expect("Failed to convert to RemoteDeliver");
 
       tracing::info!("EndpointWriter: get {:?}", rd);
@@ -349,56 +697,57 @@ impl EndpointWriter {
 
       tracing::info!("message = {:?}", message);
 
-      let s_id = u32::from(serializer_id.clone());
-      tracing::info!("EndpointWriter: serializer_id = {:?}", s_id);
-
-      let request_opt = message.to_typed::<Arc<dyn RootSerializable>>();
-      let v = request_opt.map(|request| request.serialize());
-
-      // let deliver_batch_request_opt = message.to_typed::<DeliverBatchRequest>();
-      // let pub_sub_auto_respond_batch_opt = message.to_typed::<PubSubAutoResponseBatch>();
-      // let pub_sub_batch_opt = message.to_typed::<PubSubBatch>();
-      //
-      // let v = match (
-      //   deliver_batch_request_opt,
-      //   pub_sub_auto_respond_batch_opt,
-      //   pub_sub_batch_opt,
-      // ) {
-      //   (Some(deliver_batch_request), _, _) => Some(deliver_batch_request.serialize()),
-      //   (_, Some(pub_sub_auto_respond_batch), _) => Some(pub_sub_auto_respond_batch.serialize()),
-      //   (_, _, Some(pub_sub_batch)) => Some(pub_sub_batch.serialize()),
-      //   _ => None,
-      // };
-
-      if let Some(Err(e)) = &v {
-        tracing::error!("Failed to serialize message: {:?}", e);
-        continue;
-      }
-
-      let result = if let Some(Ok(msg)) = v {
-        tracing::info!("EndpointWriter: serialize message");
-        let result = serialize_any(msg.as_any(), &serializer_id, &msg.get_type_name());
-        if let Err(e) = &result {
-          tracing::error!("Failed to serialize message: {:?}", e);
-          continue;
-        }
-        Some(result)
-      } else {
-        tracing::info!("EndpointWriter: serialize_any message");
-        Some(serialize_any(
-          message.as_any(),
-          &serializer_id,
-          &message.get_type_name(),
-        ))
+      // Root serializable messages are sent in their transport form.
+      let transport = match message.to_typed_arc::<Arc<dyn RootSerializable>>() {
+        Some(root) => match root.serialize() {
+          Ok(transport) => Some(transport),
+          Err(e) => {
+            tracing::error!("Failed to serialize message: {:?}", e);
+            continue;
+          }
+        },
+        None => None,
+      };
+      let (msg_any, type_name) = match &transport {
+        Some(transport) => (transport.as_any(), transport.get_type_name()),
+        None => (message.as_any(), message.get_type_name()),
       };
 
-      tracing::info!("EndpointWriter: serialized message: {:?}", result);
-
-      let bytes = result.expect("Not found message").expect("Failed to serialize message");
+      let requested_serializer_id = SerializerId::try_from(rd.serializer_id).unwrap_or(SerializerId::None);
+      let serialize_started = Instant::now();
+      let (serializer_id, bytes) =
+        match self
+          .get_serializer_registry()
+          .serialize_any(msg_any, &requested_serializer_id, &type_name)
+        {
+          Ok(serialized) => serialized,
+          Err(e) => {
+            tracing::error!(
+              "EndpointWriter failed to serialize message: address = {}, type = {}, error = {}",
+              self.address,
+              type_name,
+              e
+            );
+            self
+              .publish_stream(MessageHandle::new(DeadLetterEvent {
+                message_handle: message.clone(),
+                pid: Some(ExtendedPid::new(rd.target.clone())),
+                sender: rd.sender.clone().map(ExtendedPid::new),
+              }))
+              .await;
+            continue;
+          }
+        };
+      if let Some(metrics) = self.get_metrics() {
+        metrics.record_serialize_duration(&self.address, &type_name, serialize_started.elapsed());
+      }
+      tracing::info!("EndpointWriter: serializer_id = {}", serializer_id);
+      let (bytes, compression) = self.compress_payload(bytes).await;
 
       tracing::info!("EndpointWriter: get bytes");
 
-      let type_id = add_to_lookup(&mut type_names, message.get_type_name(), &mut type_names_arr);
+      let manifest = self.get_serializer_registry().get_manifest(&type_name).to_string();
+      let type_id = add_to_lookup(&mut type_names, manifest, &mut type_names_arr);
       let target_id = add_to_target_lookup(&mut target_names, &rd.target, &mut target_names_arr);
       let target_request_id = rd.target.request_id;
 
@@ -409,15 +758,24 @@ impl EndpointWriter {
         rd.sender.as_ref().unwrap().request_id
       };
 
+      let sequence_number = match &reliable_delivery {
+        Some(reliable_delivery) if reliable_delivery.is_reliable(&self.address, &type_name) => {
+          self.next_sequence_number.fetch_add(1, Ordering::SeqCst) + 1
+        }
+        _ => 0,
+      };
+
       let me = MessageEnvelope {
         type_id,
         message_data: bytes,
         target: target_id,
         sender: sender_id,
-        serializer_id: s_id,
+        serializer_id: serializer_id.into(),
         message_header: header,
         target_request_id,
         sender_request_id,
+        compression: compression.into(),
+        sequence_number,
       };
 
       tracing::info!("EndpointWriter: message envelope = {:?}", me);
@@ -430,36 +788,149 @@ impl EndpointWriter {
       return Ok(());
     }
 
-    let mut stream = self.get_stream().await.expect("Stream is not set");
-
     tracing::info!("EndpointWriter: envelopes = {:?}", envelopes);
 
-    let request = RemoteMessage {
-      message_type: Some(MessageType::MessageBatch(MessageBatch {
-        type_names: type_names_arr,
-        targets: target_names_arr,
-        envelopes,
-        senders: sender_names_arr,
-      })),
+    let batch = MessageBatch {
+      type_names: type_names_arr,
+      targets: target_names_arr,
+      envelopes,
+      senders: sender_names_arr,
+      session_id: self.session_id,
     };
 
-    let request = tonic::Request::new(futures::stream::once(futures::future::ready(request)));
-    tracing::info!("EndpointWriter sending message batch: {:?}", request);
-    let response = stream.receive(request).await;
+    if let Some(reliable_delivery) = &reliable_delivery {
+      let reliable_batch = retain_envelopes(&batch, true);
+      let dropped = self
+        .unacked
+        .lock()
+        .await
+        .push(reliable_batch, reliable_delivery.max_unacked);
+      if dropped > 0 {
+        tracing::warn!(
+          "EndpointWriter dropped unacknowledged envelopes: address = {}, dropped = {}",
+          self.address,
+          dropped
+        );
+      }
+    }
 
-    if let Err(e) = &response {
-      ctx.stash().await;
-      tracing::error!("Failed to send message: {:?}", e);
-      ctx.stop(&ctx.get_self().await).await;
+    let self_pid = ctx.get_self().await;
+    if let Err(e) = self.send_batch(&self_pid, batch.clone()).await {
+      tracing::error!("Failed to send message: address = {}, error = {:?}", self.address, e);
+      // The reliable envelopes are retransmitted once reconnected, and the others are sent after them.
+      let unsent = retain_envelopes(&batch, false);
+      if !unsent.envelopes.is_empty() {
+        self.unsent.push(unsent);
+      }
+      self.connect(ctx, true).await;
     }
+    Ok(())
+  }
 
-    response
-      .map(|_| ())
-      .map_err(|e| ActorError::ReceiveError(ErrorReason::from(e.to_string())))?;
+  async fn send_batch(&self, self_pid: &ExtendedPid, batch: MessageBatch) -> Result<(), Status> {
+    let Some(mut stream) = self.get_stream().await else {
+      return Err(Status::unavailable("stream is not set"));
+    };
+    let sequence_number = last_sequence_number(&batch);
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(fault_injector) = self.config.get_fault_injector().await {
+      let from = self.get_actor_system().await.get_address().await;
+      match fault_injector.on_send(&from, &self.address).await {
+        BatchFate::Deliver => {}
+        BatchFate::Delay(delay) => tokio::time::sleep(delay).await,
+        // Lost like on a real network; reliable envelopes are retransmitted after the ack timeout.
+        BatchFate::Drop => {
+          tracing::debug!("FaultInjector dropped message batch: address = {}", self.address);
+          if let Some(sequence_number) = sequence_number {
+            self.spawn_ack_timeout(self_pid, None, sequence_number).await;
+          }
+          return Ok(());
+        }
+      }
+    }
+    let (bytes, envelopes) = (batch.encoded_len(), batch.envelopes.len());
+    let batch = RemoteMessage {
+      message_type: Some(MessageType::MessageBatch(batch)),
+    };
+    let mut request = tonic::Request::new(futures::stream::once(futures::future::ready(batch)));
+    self.attach_credentials(&mut request).await;
+    tracing::info!("EndpointWriter sending message batch: {:?}", request);
+    let response = stream.receive(request).await?;
+    if let Some(metrics) = self.get_metrics() {
+      metrics.record_batch(&self.address, Direction::Sent, bytes, envelopes);
+    }
+    if let Some(sequence_number) = sequence_number {
+      self
+        .spawn_ack_timeout(self_pid, Some(response.into_inner()), sequence_number)
+        .await;
+    }
     Ok(())
   }
 
+  // Acknowledges the batch when the ack arrives in time, and asks the writer to retransmit it otherwise.
+  async fn spawn_ack_timeout(
+    &self,
+    self_pid: &ExtendedPid,
+    streaming: Option<Streaming<RemoteMessage>>,
+    sequence_number: u64,
+  ) {
+    let Some(reliable_delivery) = self.config.get_reliable_delivery_config().await else {
+      return;
+    };
+    let cloned_self = self.clone();
+    let cloned_self_pid = self_pid.clone();
+    tokio::spawn(async move {
+      let deadline = tokio::time::Instant::now() + reliable_delivery.ack_timeout;
+      if let Some(streaming) = streaming {
+        if let Ok(true) = tokio::time::timeout_at(deadline, Self::await_ack(streaming, sequence_number)).await {
+          cloned_self.unacked.lock().await.ack(sequence_number);
+          return;
+        }
+      }
+      tokio::time::sleep_until(deadline).await;
+      cloned_self
+        .get_actor_system()
+        .await
+        .get_root_context()
+        .await
+        .send(
+          cloned_self_pid,
+          MessageHandle::new(EndpointWriterRetransmit { sequence_number }),
+        )
+        .await;
+    });
+  }
+
+  // The receiver acknowledges a batch on the response stream of the request that carried it.
+  async fn await_ack(mut streaming: Streaming<RemoteMessage>, sequence_number: u64) -> bool {
+    while let Some(Ok(msg)) = streaming.next().await {
+      if let Some(MessageType::MessageAck(ack)) = msg.message_type {
+        return ack.sequence_number == sequence_number;
+      }
+    }
+    false
+  }
+
+  // Gives the unacknowledged batches one more chance before the writer stops, waiting up to the ack timeout.
+  async fn drain_unacked(&self, self_pid: &ExtendedPid) {
+    let Some(reliable_delivery) = self.config.get_reliable_delivery_config().await else {
+      return;
+    };
+    if self.unacked.lock().await.len() == 0 || self.get_stream().await.is_none() {
+      return;
+    }
+    if !self.retransmit_unacked(self_pid).await {
+      return;
+    }
+    let deadline = Instant::now() + reliable_delivery.ack_timeout;
+    while self.unacked.lock().await.len() > 0 && Instant::now() < deadline {
+      tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+  }
+
   async fn close_client_conn(&mut self) {
+    self.generation.fetch_add(1, Ordering::SeqCst);
+    self.handshake.lock().await.take();
     if self.get_stream().await.is_some() {
       let Some(s) = self.take_stream().await else {
         panic!("Stream is already taken")
@@ -534,11 +1005,33 @@ fn add_to_sender_lookup(m: &mut DashMap<String, i32>, pid: Option<&Pid>, arr: &m
   id.expect("not found value") + 1
 }
 
+pub(crate) async fn create_channel(config: &Config, address: &str) -> Result<Channel, EndpointWriterError> {
+  let transport = config.get_transport().await;
+  transport
+    .connect(config, address)
+    .await
+    .map_err(EndpointWriterError::from)
+}
+
 #[async_trait]
 impl Actor for EndpointWriter {
   async fn receive(&mut self, mut context_handle: ContextHandle) -> Result<(), ActorError> {
     tracing::info!("EndpointWriter received message");
     let msg = context_handle.get_message_handle().await;
+    if let Some(reconnect) = msg.to_typed::<EndpointWriterReconnect>() {
+      if reconnect.generation == self.generation.load(Ordering::SeqCst) {
+        self.connect(&context_handle, true).await;
+      }
+      return Ok(());
+    }
+    if let Some(connect) = msg.to_typed::<EndpointWriterConnect>() {
+      self.on_connect(&context_handle, &connect).await;
+      return Ok(());
+    }
+    if let Some(retransmit) = msg.to_typed::<EndpointWriterRetransmit>() {
+      self.retransmit(&context_handle, retransmit.sequence_number).await;
+      return Ok(());
+    }
     let endpoint_event = msg.to_typed::<EndpointEvent>();
     match endpoint_event {
       Some(EndpointEvent::EndpointTerminated(_)) => {
@@ -561,7 +1054,16 @@ impl Actor for EndpointWriter {
     Ok(())
   }
 
-  async fn pre_stop(&mut self, _: ContextHandle) -> Result<(), ActorError> {
+  async fn pre_stop(&mut self, ctx: ContextHandle) -> Result<(), ActorError> {
+    self.drain_unacked(&ctx.get_self().await).await;
+    let unacked = self.unacked.lock().await.len();
+    if unacked > 0 {
+      tracing::warn!(
+        "EndpointWriter stopped with unacknowledged envelopes: address = {}, unacked = {}",
+        self.address,
+        unacked
+      );
+    }
     self.close_client_conn().await;
     Ok(())
   }
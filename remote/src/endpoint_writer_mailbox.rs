@@ -1,37 +1,73 @@
+use crate::config::outbound_queue_config::{OutboundQueueConfig, OverflowPolicy};
+use crate::messages::{EndpointHighWatermarkEvent, RemoteDeliver};
+use crate::remote::Remote;
 use async_trait::async_trait;
+use nexus_actor_core_rs::actor::actor::ExtendedPid;
+use nexus_actor_core_rs::actor::context::SenderPart;
 use nexus_actor_core_rs::actor::dispatch::{
-  Dispatcher, DispatcherHandle, Mailbox, MailboxHandle, MailboxMessage, MessageInvoker, MessageInvokerHandle, Runnable,
+  DeadLetterEvent, Dispatcher, DispatcherHandle, Mailbox, MailboxHandle, MailboxMessage, MessageInvoker,
+  MessageInvokerHandle, Runnable,
 };
 use nexus_actor_core_rs::actor::message::MessageHandle;
+use nexus_actor_core_rs::generated::actor::DeadLetterResponse;
 use nexus_actor_utils_rs::collections::{
   MpscUnboundedChannelQueue, QueueBase, QueueError, QueueReader, QueueWriter, RingQueue,
 };
 use std::sync::atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone)]
 pub struct EndpointWriterMailbox {
+  // Holds only RemoteDeliver messages, bounded by the OutboundQueueConfig.
   user_mailbox: Arc<RwLock<RingQueue<MessageHandle>>>,
+  // Messages of the EndpointWriter to itself, which are processed even while it is connecting.
+  control_mailbox: Arc<RwLock<MpscUnboundedChannelQueue<MessageHandle>>>,
   system_mailbox: Arc<RwLock<MpscUnboundedChannelQueue<MessageHandle>>>,
+  // Shared with the EndpointWriter; RemoteDeliver messages wait in the mailbox while it is set.
+  connecting: Arc<AtomicBool>,
   scheduler_status: Arc<AtomicBool>,
   has_more_messages: Arc<AtomicI32>,
   batch_size: Arc<AtomicUsize>,
+  address: String,
+  queue_config: OutboundQueueConfig,
+  // Shared with Remote, which reports it per address.
+  queue_depth: Arc<AtomicUsize>,
+  above_high_watermark: Arc<AtomicBool>,
+  remote: Weak<Remote>,
   suspended: Arc<AtomicBool>,
   invoker_opt: Arc<RwLock<Option<MessageInvokerHandle>>>,
   dispatcher_opt: Arc<RwLock<Option<DispatcherHandle>>>,
 }
 
 impl EndpointWriterMailbox {
-  pub fn new(batch_size: usize, initial_size: usize) -> Self {
+  pub fn new(
+    remote: Weak<Remote>,
+    address: String,
+    batch_size: usize,
+    initial_size: usize,
+    queue_config: OutboundQueueConfig,
+    connecting: Arc<AtomicBool>,
+  ) -> Self {
+    let queue_depth = remote
+      .upgrade()
+      .expect("Remote has been dropped")
+      .get_outbound_queue_depth_gauge(&address);
     let user_mailbox = Arc::new(RwLock::new(RingQueue::new(initial_size)));
     let system_mailbox = Arc::new(RwLock::new(MpscUnboundedChannelQueue::new()));
     Self {
       user_mailbox,
+      control_mailbox: Arc::new(RwLock::new(MpscUnboundedChannelQueue::new())),
       system_mailbox,
+      connecting,
       scheduler_status: Arc::new(AtomicBool::new(false)),
       has_more_messages: Arc::new(AtomicI32::new(0)),
       batch_size: Arc::new(AtomicUsize::new(batch_size)),
+      address,
+      queue_config,
+      queue_depth,
+      above_high_watermark: Arc::new(AtomicBool::new(false)),
+      remote,
       suspended: Arc::new(AtomicBool::new(false)),
       invoker_opt: Arc::new(RwLock::new(None)),
       dispatcher_opt: Arc::new(RwLock::new(None)),
@@ -61,9 +97,78 @@ impl EndpointWriterMailbox {
     mg.poll().await
   }
 
+  async fn poll_control_mailbox(&self) -> Result<Option<MessageHandle>, QueueError<MessageHandle>> {
+    let mut mg = self.control_mailbox.write().await;
+    mg.poll().await
+  }
+
   async fn poll_user_mailbox(&self) -> Result<Option<MessageHandle>, QueueError<MessageHandle>> {
     let mut mg = self.user_mailbox.write().await;
-    mg.poll().await
+    let result = mg.poll().await;
+    let queued = mg.len().await.to_usize();
+    self.queue_depth.store(queued, Ordering::Relaxed);
+    if queued < self.queue_config.high_watermark {
+      self.above_high_watermark.store(false, Ordering::SeqCst);
+    }
+    result
+  }
+
+  async fn publish_high_watermark(&self, queued: usize) {
+    if self.above_high_watermark.swap(true, Ordering::SeqCst) {
+      return;
+    }
+    tracing::warn!(
+      "EndpointWriterMailbox reached its high watermark: address = {}, queued = {}",
+      self.address,
+      queued
+    );
+    let Some(remote) = self.remote.upgrade() else {
+      return;
+    };
+    remote
+      .get_actor_system()
+      .get_event_stream()
+      .await
+      .publish(MessageHandle::new(EndpointHighWatermarkEvent {
+        address: self.address.clone(),
+        queued,
+        high_watermark: self.queue_config.high_watermark,
+      }))
+      .await;
+  }
+
+  // Tells the sender that the message was not queued or was dropped from the queue, like the EndpointWriter does when
+  // it is not connected.
+  async fn reject(&self, message_handle: MessageHandle) {
+    let (Some(rd), Some(remote)) = (message_handle.to_typed::<RemoteDeliver>(), self.remote.upgrade()) else {
+      return;
+    };
+    let actor_system = remote.get_actor_system();
+    match rd.sender {
+      Some(sender) => {
+        actor_system
+          .get_root_context()
+          .await
+          .send(
+            ExtendedPid::new(sender),
+            MessageHandle::new(DeadLetterResponse {
+              target: Some(rd.target),
+            }),
+          )
+          .await;
+      }
+      None => {
+        actor_system
+          .get_event_stream()
+          .await
+          .publish(MessageHandle::new(DeadLetterEvent {
+            message_handle: rd.message,
+            pid: Some(ExtendedPid::new(rd.target)),
+            sender: None,
+          }))
+          .await;
+      }
+    }
   }
 
   async fn schedule(&self) {
@@ -104,7 +209,7 @@ impl EndpointWriterMailbox {
 
     loop {
       if let Ok(Some(msg)) = self.poll_system_mailbox().await {
-        let mailbox_message = msg.to_typed::<MailboxMessage>();
+        let mailbox_message = msg.as_typed::<MailboxMessage>();
         match mailbox_message {
           Some(MailboxMessage::SuspendMailbox) => {
             self.set_suspended(true);
@@ -127,7 +232,12 @@ impl EndpointWriterMailbox {
         break;
       }
 
-      if let Ok(Some(msg)) = self.poll_user_mailbox().await {
+      let msg = match self.poll_control_mailbox().await {
+        Ok(Some(msg)) => Some(msg),
+        _ if self.connecting.load(Ordering::SeqCst) => None,
+        _ => self.poll_user_mailbox().await.ok().flatten(),
+      };
+      if let Some(msg) = msg {
         if let Err(err) = message_invoker.invoke_user_message(msg.clone()).await {
           message_invoker
             .escalate_failure(err.reason().cloned().unwrap(), msg.clone())
@@ -145,8 +255,9 @@ impl EndpointWriterMailbox {
 #[async_trait]
 impl Mailbox for EndpointWriterMailbox {
   async fn get_user_messages_count(&self) -> i32 {
-    let mg = self.user_mailbox.read().await;
-    mg.len().await.to_usize() as i32
+    let user_messages = self.user_mailbox.read().await.len().await.to_usize();
+    let control_messages = self.control_mailbox.read().await.len().await.to_usize();
+    (user_messages + control_messages) as i32
   }
 
   async fn get_system_messages_count(&self) -> i32 {
@@ -173,9 +284,61 @@ impl Mailbox for EndpointWriterMailbox {
 
   async fn post_user_message(&self, message_handle: MessageHandle) {
     tracing::info!("EndpointWriterMailbox::post_user_message: {:?}", message_handle);
-    {
+    if !message_handle.is_typed::<RemoteDeliver>() {
+      self.control_mailbox.write().await.offer(message_handle).await.unwrap();
+      self.schedule().await;
+      return;
+    }
+    let mut dropped = None;
+    let queued = {
       let mut mg = self.user_mailbox.write().await;
+      let mut queued = mg.len().await.to_usize();
+      if queued >= self.queue_config.max_queued {
+        match self.queue_config.overflow_policy {
+          OverflowPolicy::DropNewest => {
+            tracing::warn!(
+              "EndpointWriterMailbox is full, dropping message: address = {}, max_queued = {}, message = {:?}",
+              self.address,
+              self.queue_config.max_queued,
+              message_handle
+            );
+            drop(mg);
+            self.reject(message_handle).await;
+            return;
+          }
+          OverflowPolicy::DropOldest => {
+            if let Ok(Some(oldest)) = mg.poll().await {
+              tracing::warn!(
+                "EndpointWriterMailbox is full, dropping oldest message: address = {}, max_queued = {}, message = {:?}",
+                self.address,
+                self.queue_config.max_queued,
+                oldest
+              );
+              dropped = Some(oldest);
+              queued -= 1;
+            }
+          }
+          OverflowPolicy::Reject => {
+            drop(mg);
+            tracing::warn!(
+              "EndpointWriterMailbox is full, rejecting message: address = {}, max_queued = {}",
+              self.address,
+              self.queue_config.max_queued
+            );
+            self.reject(message_handle).await;
+            return;
+          }
+        }
+      }
       mg.offer(message_handle).await.unwrap();
+      queued + 1
+    };
+    if let Some(dropped) = dropped {
+      self.reject(dropped).await;
+    }
+    self.queue_depth.store(queued, Ordering::Relaxed);
+    if queued >= self.queue_config.high_watermark {
+      self.publish_high_watermark(queued).await;
     }
     self.schedule().await;
   }
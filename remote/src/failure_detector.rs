@@ -0,0 +1,142 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::config::heartbeat_config::HeartbeatConfig;
+
+// Phi accrual failure detector (Hayashibara et al.). Instead of a fixed deadline it reports how suspicious the
+// silence since the last heartbeat is, given the distribution of the intervals observed so far.
+#[derive(Debug, Clone)]
+pub struct PhiAccrualFailureDetector {
+  threshold: f64,
+  max_sample_size: usize,
+  min_std_deviation: Duration,
+  acceptable_heartbeat_pause: Duration,
+  first_heartbeat_estimate: Duration,
+  intervals: VecDeque<Duration>,
+  last_heartbeat: Option<Instant>,
+}
+
+impl PhiAccrualFailureDetector {
+  pub fn new(config: &HeartbeatConfig) -> Self {
+    Self {
+      threshold: config.threshold,
+      max_sample_size: config.max_sample_size.max(1),
+      min_std_deviation: config.min_std_deviation,
+      acceptable_heartbeat_pause: config.acceptable_heartbeat_pause,
+      first_heartbeat_estimate: config.interval,
+      intervals: VecDeque::new(),
+      last_heartbeat: None,
+    }
+  }
+
+  pub fn heartbeat(&mut self, now: Instant) {
+    match self.last_heartbeat {
+      None => {
+        // Seed the history so that phi is meaningful before real intervals have been observed.
+        let std_deviation = self.first_heartbeat_estimate / 4;
+        self.record(self.first_heartbeat_estimate.saturating_sub(std_deviation));
+        self.record(self.first_heartbeat_estimate + std_deviation);
+      }
+      Some(last_heartbeat) => self.record(now.saturating_duration_since(last_heartbeat)),
+    }
+    self.last_heartbeat = Some(now);
+  }
+
+  // 0.0 until the first heartbeat.
+  pub fn phi(&self, now: Instant) -> f64 {
+    let Some(last_heartbeat) = self.last_heartbeat else {
+      return 0.0;
+    };
+    let elapsed = now.saturating_duration_since(last_heartbeat).as_secs_f64();
+    let count = self.intervals.len() as f64;
+    let mean = self.intervals.iter().map(Duration::as_secs_f64).sum::<f64>() / count;
+    let variance = self
+      .intervals
+      .iter()
+      .map(|interval| (interval.as_secs_f64() - mean).powi(2))
+      .sum::<f64>()
+      / count;
+    let std_deviation = variance.sqrt().max(self.min_std_deviation.as_secs_f64());
+    Self::phi_of(
+      elapsed,
+      mean + self.acceptable_heartbeat_pause.as_secs_f64(),
+      std_deviation,
+    )
+  }
+
+  pub fn is_available(&self, now: Instant) -> bool {
+    self.phi(now) < self.threshold
+  }
+
+  fn record(&mut self, interval: Duration) {
+    if self.intervals.len() >= self.max_sample_size {
+      self.intervals.pop_front();
+    }
+    self.intervals.push_back(interval);
+  }
+
+  // Logistic approximation of the normal CDF, as used by Akka and Cassandra.
+  fn phi_of(elapsed: f64, mean: f64, std_deviation: f64) -> f64 {
+    let y = (elapsed - mean) / std_deviation;
+    let e = (-y * (1.5976 + 0.070566 * y * y)).exp();
+    if elapsed > mean {
+      -(e / (1.0 + e)).log10()
+    } else {
+      -(1.0 - 1.0 / (1.0 + e)).log10()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PhiAccrualFailureDetector;
+  use crate::config::heartbeat_config::HeartbeatConfig;
+  use std::time::{Duration, Instant};
+
+  fn detector() -> PhiAccrualFailureDetector {
+    PhiAccrualFailureDetector::new(
+      &HeartbeatConfig::new(Duration::from_secs(1))
+        .with_threshold(8.0)
+        .with_acceptable_heartbeat_pause(Duration::ZERO)
+        .with_min_std_deviation(Duration::from_millis(100)),
+    )
+  }
+
+  #[test]
+  fn test_available_before_first_heartbeat() {
+    let detector = detector();
+    assert_eq!(detector.phi(Instant::now()), 0.0);
+    assert!(detector.is_available(Instant::now()));
+  }
+
+  #[test]
+  fn test_phi_grows_with_silence() {
+    let mut detector = detector();
+    let start = Instant::now();
+    for i in 0..10 {
+      detector.heartbeat(start + Duration::from_secs(i));
+    }
+    let last = start + Duration::from_secs(9);
+    let phi_on_time = detector.phi(last + Duration::from_secs(1));
+    let phi_late = detector.phi(last + Duration::from_millis(1500));
+    let phi_silent = detector.phi(last + Duration::from_secs(5));
+    assert!(phi_on_time < 1.0, "phi_on_time = {}", phi_on_time);
+    assert!(phi_on_time < phi_late && phi_late < phi_silent);
+    assert!(detector.is_available(last + Duration::from_secs(1)));
+    assert!(!detector.is_available(last + Duration::from_secs(5)));
+  }
+
+  #[test]
+  fn test_acceptable_pause_delays_suspicion() {
+    let config = HeartbeatConfig::new(Duration::from_secs(1))
+      .with_threshold(8.0)
+      .with_acceptable_heartbeat_pause(Duration::from_secs(5));
+    let mut detector = PhiAccrualFailureDetector::new(&config);
+    let start = Instant::now();
+    for i in 0..10 {
+      detector.heartbeat(start + Duration::from_secs(i));
+    }
+    assert!(detector.is_available(start + Duration::from_secs(14)));
+    assert!(!detector.is_available(start + Duration::from_secs(30)));
+  }
+}
@@ -0,0 +1,160 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::block_list::BlockList;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BatchFate {
+  Deliver,
+  Delay(Duration),
+  Drop,
+}
+
+// Simulates an unreliable network between `Remote`s in tests. Every rule applies to one direction of a link,
+// from the address of the sending node to the address of the receiving node. Share one injector between the nodes
+// of a test, or give each node its own to fault only its traffic.
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+  drop_ratios: Arc<DashMap<(String, String), f64>>,
+  delays: Arc<DashMap<(String, String), Duration>>,
+  // Holds `from->to` links.
+  blocked_links: BlockList,
+  rng: Arc<Mutex<StdRng>>,
+}
+
+impl Default for FaultInjector {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl FaultInjector {
+  pub fn new() -> Self {
+    Self::with_rng(StdRng::from_os_rng())
+  }
+
+  // Drops the same batches on every run, so a test with a drop ratio is reproducible.
+  pub fn with_seed(seed: u64) -> Self {
+    Self::with_rng(StdRng::seed_from_u64(seed))
+  }
+
+  fn with_rng(rng: StdRng) -> Self {
+    Self {
+      drop_ratios: Arc::new(DashMap::new()),
+      delays: Arc::new(DashMap::new()),
+      blocked_links: BlockList::new(),
+      rng: Arc::new(Mutex::new(rng)),
+    }
+  }
+
+  // Drops the given share of the batches, between 0.0 and 1.0.
+  pub fn set_drop_ratio(&self, from: &str, to: &str, ratio: f64) {
+    self
+      .drop_ratios
+      .insert((from.to_string(), to.to_string()), ratio.clamp(0.0, 1.0));
+  }
+
+  pub fn set_delay(&self, from: &str, to: &str, delay: Duration) {
+    self.delays.insert((from.to_string(), to.to_string()), delay);
+  }
+
+  // Drops every batch sent from `from` to `to`, while the opposite direction keeps working.
+  pub async fn block(&self, from: &str, to: &str) {
+    self.blocked_links.block(Self::link(from, to)).await;
+  }
+
+  pub async fn unblock(&self, from: &str, to: &str) {
+    self.blocked_links.unblock(Self::link(from, to)).await;
+  }
+
+  pub async fn is_blocked(&self, from: &str, to: &str) -> bool {
+    self.blocked_links.is_blocked(&Self::link(from, to)).await
+  }
+
+  pub async fn clear(&self) {
+    self.drop_ratios.clear();
+    self.delays.clear();
+    self.blocked_links.clear().await;
+  }
+
+  pub(crate) async fn on_send(&self, from: &str, to: &str) -> BatchFate {
+    if self.is_blocked(from, to).await {
+      return BatchFate::Drop;
+    }
+    let key = (from.to_string(), to.to_string());
+    let drop_ratio = self.drop_ratios.get(&key).map_or(0.0, |ratio| *ratio);
+    if drop_ratio > 0.0 && self.rng.lock().unwrap().random::<f64>() < drop_ratio {
+      return BatchFate::Drop;
+    }
+    match self.delays.get(&key) {
+      Some(delay) => BatchFate::Delay(*delay),
+      None => BatchFate::Deliver,
+    }
+  }
+
+  fn link(from: &str, to: &str) -> String {
+    format!("{}->{}", from, to)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{BatchFate, FaultInjector};
+  use std::time::Duration;
+
+  #[tokio::test]
+  async fn test_block_is_one_way() {
+    let fault_injector = FaultInjector::new();
+    fault_injector.block("a", "b").await;
+    assert_eq!(fault_injector.on_send("a", "b").await, BatchFate::Drop);
+    assert_eq!(fault_injector.on_send("b", "a").await, BatchFate::Deliver);
+    fault_injector.unblock("a", "b").await;
+    assert_eq!(fault_injector.on_send("a", "b").await, BatchFate::Deliver);
+  }
+
+  #[tokio::test]
+  async fn test_drop_ratio_and_delay() {
+    let fault_injector = FaultInjector::new();
+    fault_injector.set_drop_ratio("a", "b", 1.0);
+    fault_injector.set_delay("b", "a", Duration::from_millis(100));
+    for _ in 0..10 {
+      assert_eq!(fault_injector.on_send("a", "b").await, BatchFate::Drop);
+      assert_eq!(
+        fault_injector.on_send("b", "a").await,
+        BatchFate::Delay(Duration::from_millis(100))
+      );
+    }
+    fault_injector.set_drop_ratio("a", "b", 0.5);
+    let dropped = futures::future::join_all((0..1000).map(|_| fault_injector.on_send("a", "b")))
+      .await
+      .into_iter()
+      .filter(|fate| *fate == BatchFate::Drop)
+      .count();
+    assert!((300..700).contains(&dropped), "dropped = {}", dropped);
+
+    fault_injector.clear().await;
+    assert_eq!(fault_injector.on_send("a", "b").await, BatchFate::Deliver);
+    assert_eq!(fault_injector.on_send("b", "a").await, BatchFate::Deliver);
+  }
+
+  #[tokio::test]
+  async fn test_seeded_injectors_drop_the_same_batches() {
+    let mut fates = vec![];
+    for _ in 0..2 {
+      let fault_injector = FaultInjector::with_seed(7);
+      fault_injector.set_drop_ratio("a", "b", 0.5);
+      let mut run = vec![];
+      for _ in 0..100 {
+        run.push(fault_injector.on_send("a", "b").await);
+      }
+      fates.push(run);
+    }
+    assert_eq!(fates[0], fates[1]);
+    assert!(fates[0].contains(&BatchFate::Drop));
+    assert!(fates[0].contains(&BatchFate::Deliver));
+  }
+}
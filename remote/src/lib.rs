@@ -1,8 +1,9 @@
 mod activator_actor;
 mod block_list;
 mod cluster;
-mod config;
-mod config_option;
+mod compression;
+pub mod config;
+pub mod config_option;
 mod endpoint;
 mod endpoint_lazy;
 mod endpoint_manager;
@@ -11,9 +12,25 @@ mod endpoint_supervisor;
 mod endpoint_watcher;
 mod endpoint_writer;
 mod endpoint_writer_mailbox;
+mod failure_detector;
+#[cfg(any(test, feature = "test-support"))]
+pub mod fault_injector;
 mod generated;
 mod messages;
-mod remote;
+mod peer_identity;
+mod reliable_delivery;
+pub mod remote;
+mod remote_authenticator;
+mod remote_metrics;
 mod remote_process;
+mod remote_spawn;
 mod response_status_code;
-mod serializer;
+#[cfg(any(feature = "msgpack", feature = "cbor", feature = "bincode"))]
+mod serde_serializer;
+pub mod serializer;
+pub mod serializer_registry;
+mod transport;
+mod type_manifest;
+
+#[cfg(feature = "test-support")]
+pub use fault_injector::FaultInjector;
@@ -1,15 +1,17 @@
 use crate::generated::remote::connect_request::ConnectionType;
 use crate::generated::remote::remote_message::MessageType;
 use crate::generated::remote::{
-  ClientConnection, ConnectRequest, ConnectResponse, DisconnectRequest, MessageBatch, MessageEnvelope, MessageHeader,
-  RemoteMessage, ServerConnection,
+  ClientConnection, ConnectRequest, ConnectResponse, DisconnectRequest, MessageAck, MessageBatch, MessageEnvelope,
+  MessageHeader, RemoteMessage, ServerConnection,
 };
 use nexus_actor_core_rs::actor::message::ReadonlyMessageHeadersHandle;
 use nexus_actor_core_rs::actor::message::{Message, MessageHandle};
 use nexus_actor_core_rs::generated::actor::Pid;
 use nexus_actor_core_rs::Message;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct EndpointTerminatedEvent {
@@ -37,6 +39,43 @@ impl EndpointEvent {
   }
 }
 
+// Published by the EndpointWriter before each attempt to re-establish a lost connection.
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub struct EndpointReconnectingEvent {
+  pub address: String,
+  pub attempt: u32,
+  pub max_attempts: u32,
+  pub backoff: Duration,
+}
+
+// Published when this node refuses a connecting peer during the handshake.
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub struct EndpointRejectedEvent {
+  pub system_id: String,
+  pub address: String,
+  pub reason: String,
+}
+
+// Published when a member is added to or removed from the BlockList of this node, including when a block expires.
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub enum BlockListEvent {
+  Blocked {
+    member: String,
+    expires_at: Option<SystemTime>,
+  },
+  Unblocked {
+    member: String,
+  },
+}
+
+// Published when the outbound queue of an endpoint fills up to its high watermark.
+#[derive(Debug, Clone, PartialEq, Eq, Message)]
+pub struct EndpointHighWatermarkEvent {
+  pub address: String,
+  pub queued: usize,
+  pub high_watermark: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Message)]
 pub struct RemoteWatch {
   pub watcher: Pid,
@@ -64,10 +103,10 @@ pub struct JsonMessage {
   pub json: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Message)]
+#[derive(Debug, Clone, PartialEq, Eq, Message, Serialize, Deserialize)]
 pub struct Ping;
 
-#[derive(Debug, Clone, PartialEq, Eq, Message)]
+#[derive(Debug, Clone, PartialEq, Eq, Message, Serialize, Deserialize)]
 pub struct Pong;
 
 #[derive(Debug, Clone, PartialEq, Message)]
@@ -87,6 +126,8 @@ impl Hash for RemoteMessage {
 impl Hash for ClientConnection {
   fn hash<H: Hasher>(&self, state: &mut H) {
     self.system_id.hash(state);
+    self.protocol_version.hash(state);
+    self.auth_token.hash(state);
   }
 }
 
@@ -94,6 +135,9 @@ impl Hash for ServerConnection {
   fn hash<H: Hasher>(&self, state: &mut H) {
     self.system_id.hash(state);
     self.address.hash(state);
+    self.protocol_version.hash(state);
+    self.auth_token.hash(state);
+    self.compressions.hash(state);
   }
 }
 
@@ -132,6 +176,14 @@ impl Hash for MessageEnvelope {
     self.message_header.hash(state);
     self.target_request_id.hash(state);
     self.sender_request_id.hash(state);
+    self.compression.hash(state);
+    self.sequence_number.hash(state);
+  }
+}
+
+impl Hash for MessageAck {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.sequence_number.hash(state);
   }
 }
 
@@ -149,6 +201,7 @@ impl Hash for MessageBatch {
     for sender in &self.senders {
       sender.hash(state);
     }
+    self.session_id.hash(state);
   }
 }
 
@@ -162,6 +215,9 @@ impl Hash for ConnectResponse {
   fn hash<H: Hasher>(&self, state: &mut H) {
     self.member_id.hash(state);
     self.blocked.hash(state);
+    self.protocol_version.hash(state);
+    self.rejection_reason.hash(state);
+    self.compression.hash(state);
   }
 }
 
@@ -176,6 +232,7 @@ impl Hash for MessageType {
       MessageType::ConnectRequest(m) => m.hash(state),
       MessageType::ConnectResponse(m) => m.hash(state),
       MessageType::DisconnectRequest(m) => m.hash(state),
+      MessageType::MessageAck(m) => m.hash(state),
     }
   }
 }
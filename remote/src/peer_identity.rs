@@ -0,0 +1,91 @@
+use std::fmt::{Display, Formatter};
+
+use tonic::transport::CertificateDer;
+use x509_parser::extensions::GeneralName;
+use x509_parser::parse_x509_certificate;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PeerIdentity {
+  pub common_name: Option<String>,
+  pub dns_names: Vec<String>,
+}
+
+impl PeerIdentity {
+  // Builds the identity from the leaf of a certificate chain verified by the TLS layer.
+  pub fn from_certificates(certificates: &[CertificateDer<'_>]) -> Option<Self> {
+    Self::from_der(certificates.first()?.as_ref())
+  }
+
+  pub fn from_der(der: &[u8]) -> Option<Self> {
+    let (_, certificate) = parse_x509_certificate(der).ok()?;
+    let common_name = certificate
+      .subject()
+      .iter_common_name()
+      .next()
+      .and_then(|cn| cn.as_str().ok())
+      .map(|cn| cn.to_string());
+    let dns_names = certificate
+      .subject_alternative_name()
+      .ok()
+      .flatten()
+      .map(|san| {
+        san
+          .value
+          .general_names
+          .iter()
+          .filter_map(|name| match name {
+            GeneralName::DNSName(dns_name) => Some(dns_name.to_string()),
+            _ => None,
+          })
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+    Some(Self { common_name, dns_names })
+  }
+
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.common_name.iter().chain(self.dns_names.iter()).map(String::as_str)
+  }
+
+  pub fn matches(&self, name: &str) -> bool {
+    self.names().any(|n| n == name)
+  }
+}
+
+impl Display for PeerIdentity {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match &self.common_name {
+      Some(common_name) => write!(f, "{}", common_name),
+      None => write!(f, "{}", self.dns_names.join(",")),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::PeerIdentity;
+
+  #[test]
+  fn test_peer_identity_from_der() {
+    let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string(), "node-a.local".to_string()]).unwrap();
+    params.distinguished_name.push(rcgen::DnType::CommonName, "node-a");
+    let key = rcgen::KeyPair::generate().unwrap();
+    let certificate = params.self_signed(&key).unwrap();
+
+    let identity = PeerIdentity::from_der(certificate.der()).unwrap();
+    assert_eq!(identity.common_name.as_deref(), Some("node-a"));
+    assert_eq!(
+      identity.dns_names,
+      vec!["localhost".to_string(), "node-a.local".to_string()]
+    );
+    assert!(identity.matches("node-a"));
+    assert!(identity.matches("node-a.local"));
+    assert!(!identity.matches("node-b"));
+    assert_eq!(identity.to_string(), "node-a");
+  }
+
+  #[test]
+  fn test_peer_identity_from_invalid_der() {
+    assert_eq!(PeerIdentity::from_der(&[0x30, 0x00]), None);
+  }
+}
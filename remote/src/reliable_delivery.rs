@@ -0,0 +1,279 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+
+use crate::generated::remote::MessageBatch;
+
+// Sequence numbers above the contiguous prefix that are remembered before the receiver gives up on the gaps.
+const MAX_PENDING_SEQUENCES: usize = 4096;
+// Writer sessions remembered per peer, e.g. for several nodes behind one IP address.
+const MAX_SESSIONS_PER_PEER: usize = 16;
+// Peers that sent no reliable envelope for this long are forgotten, as their writers have stopped retransmitting.
+const PEER_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+
+// Sequence numbers of one writer session that have already been delivered.
+// Batches travel as separate requests and may arrive out of order, so gaps are tracked until they are filled.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DeliveredSequences {
+  contiguous: u64,
+  pending: BTreeSet<u64>,
+}
+
+impl DeliveredSequences {
+  pub(crate) fn contains(&self, sequence_number: u64) -> bool {
+    sequence_number <= self.contiguous || self.pending.contains(&sequence_number)
+  }
+
+  pub(crate) fn insert(&mut self, sequence_number: u64) {
+    if self.contains(sequence_number) {
+      return;
+    }
+    self.pending.insert(sequence_number);
+    // Gaps left by envelopes the writer dropped are never filled.
+    if self.pending.len() > MAX_PENDING_SEQUENCES {
+      self.contiguous = *self.pending.first().unwrap() - 1;
+    }
+    while self.pending.remove(&(self.contiguous + 1)) {
+      self.contiguous += 1;
+    }
+  }
+}
+
+// Delivered sequence numbers per peer key. Session ids are chosen by the peer, so they only count within the
+// sessions of that peer.
+#[derive(Debug, Default)]
+pub(crate) struct DeliveredSessions {
+  peers: DashMap<String, PeerSessions>,
+  swept_at: Mutex<Option<Instant>>,
+}
+
+impl DeliveredSessions {
+  pub(crate) fn contains(&self, peer: &str, session_id: u64, sequence_number: u64) -> bool {
+    self
+      .peers
+      .get(peer)
+      .map_or(false, |sessions| sessions.contains(session_id, sequence_number))
+  }
+
+  pub(crate) fn insert(&self, peer: &str, session_id: u64, sequence_number: u64) {
+    let now = Instant::now();
+    self.sweep_idle_peers(now);
+    let mut sessions = self.peers.entry(peer.to_string()).or_default();
+    sessions.insert(session_id, sequence_number);
+    sessions.used_at = Some(now);
+  }
+
+  // Drops the peers idle for PEER_IDLE_TIMEOUT, at most once per PEER_IDLE_TIMEOUT.
+  fn sweep_idle_peers(&self, now: Instant) {
+    let mut swept_at = self.swept_at.lock().unwrap();
+    if swept_at.map_or(false, |swept_at| now.duration_since(swept_at) < PEER_IDLE_TIMEOUT) {
+      return;
+    }
+    *swept_at = Some(now);
+    self.peers.retain(|_, sessions| {
+      sessions
+        .used_at
+        .map_or(false, |used_at| now.duration_since(used_at) < PEER_IDLE_TIMEOUT)
+    });
+  }
+}
+
+// Delivered sequence numbers of the latest writer sessions of one peer. A writer keeps its session until it stops, so
+// the least recently used sessions are forgotten once the peer has started more than MAX_SESSIONS_PER_PEER.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PeerSessions {
+  sessions: VecDeque<(u64, DeliveredSequences)>,
+  used_at: Option<Instant>,
+}
+
+impl PeerSessions {
+  pub(crate) fn contains(&self, session_id: u64, sequence_number: u64) -> bool {
+    self
+      .sessions
+      .iter()
+      .any(|(id, delivered)| *id == session_id && delivered.contains(sequence_number))
+  }
+
+  pub(crate) fn insert(&mut self, session_id: u64, sequence_number: u64) {
+    let mut session = match self.sessions.iter().position(|(id, _)| *id == session_id) {
+      Some(index) => self.sessions.remove(index).unwrap(),
+      None => (session_id, DeliveredSequences::default()),
+    };
+    session.1.insert(sequence_number);
+    self.sessions.push_back(session);
+    while self.sessions.len() > MAX_SESSIONS_PER_PEER {
+      self.sessions.pop_front();
+    }
+  }
+}
+
+// Reliable batches sent by an EndpointWriter that the receiver has not acknowledged yet, keyed by their last
+// sequence number.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UnackedBatches {
+  batches: BTreeMap<u64, MessageBatch>,
+  envelopes: usize,
+}
+
+impl UnackedBatches {
+  // Returns the number of envelopes dropped to stay within `max_unacked`.
+  pub(crate) fn push(&mut self, batch: MessageBatch, max_unacked: usize) -> usize {
+    let Some(sequence_number) = last_sequence_number(&batch) else {
+      return 0;
+    };
+    self.envelopes += batch.envelopes.len();
+    self.batches.insert(sequence_number, batch);
+    let mut dropped = 0;
+    while self.envelopes > max_unacked {
+      let Some((_, oldest)) = self.batches.pop_first() else {
+        break;
+      };
+      self.envelopes -= oldest.envelopes.len();
+      dropped += oldest.envelopes.len();
+    }
+    dropped
+  }
+
+  pub(crate) fn ack(&mut self, sequence_number: u64) {
+    if let Some(batch) = self.batches.remove(&sequence_number) {
+      self.envelopes -= batch.envelopes.len();
+    }
+  }
+
+  pub(crate) fn get(&self, sequence_number: u64) -> Option<MessageBatch> {
+    self.batches.get(&sequence_number).cloned()
+  }
+
+  pub(crate) fn pending(&self) -> Vec<MessageBatch> {
+    self.batches.values().cloned().collect()
+  }
+
+  pub(crate) fn len(&self) -> usize {
+    self.envelopes
+  }
+}
+
+// The highest sequence number in the batch, or None if every envelope is fire-and-forget.
+pub(crate) fn last_sequence_number(batch: &MessageBatch) -> Option<u64> {
+  batch
+    .envelopes
+    .iter()
+    .map(|envelope| envelope.sequence_number)
+    .filter(|sequence_number| *sequence_number != 0)
+    .max()
+}
+
+// Keeps the lookup tables as they are, since envelopes refer to them by index.
+pub(crate) fn retain_envelopes(batch: &MessageBatch, reliable: bool) -> MessageBatch {
+  MessageBatch {
+    envelopes: batch
+      .envelopes
+      .iter()
+      .filter(|envelope| (envelope.sequence_number != 0) == reliable)
+      .cloned()
+      .collect(),
+    ..batch.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    last_sequence_number, retain_envelopes, DeliveredSequences, DeliveredSessions, PeerSessions, UnackedBatches,
+    MAX_SESSIONS_PER_PEER, PEER_IDLE_TIMEOUT,
+  };
+  use crate::generated::remote::{MessageBatch, MessageEnvelope};
+  use std::time::Instant;
+
+  fn batch(sequence_numbers: &[u64]) -> MessageBatch {
+    MessageBatch {
+      envelopes: sequence_numbers
+        .iter()
+        .map(|sequence_number| MessageEnvelope {
+          sequence_number: *sequence_number,
+          ..MessageEnvelope::default()
+        })
+        .collect(),
+      session_id: 7,
+      ..MessageBatch::default()
+    }
+  }
+
+  #[test]
+  fn test_delivered_sequences_out_of_order() {
+    let mut delivered = DeliveredSequences::default();
+    delivered.insert(2);
+    assert!(!delivered.contains(1));
+    assert!(delivered.contains(2));
+    delivered.insert(1);
+    delivered.insert(3);
+    assert_eq!(delivered.contiguous, 3);
+    assert!(delivered.pending.is_empty());
+    assert!(delivered.contains(1));
+    assert!(!delivered.contains(4));
+  }
+
+  #[test]
+  fn test_peer_sessions_forget_the_least_recently_used() {
+    let mut sessions = PeerSessions::default();
+    sessions.insert(1, 1);
+    for session_id in 2..=MAX_SESSIONS_PER_PEER as u64 {
+      sessions.insert(session_id, 1);
+    }
+    // Session 1 is used again, so session 2 is the least recently used one.
+    sessions.insert(1, 2);
+    sessions.insert(MAX_SESSIONS_PER_PEER as u64 + 1, 1);
+    assert_eq!(sessions.sessions.len(), MAX_SESSIONS_PER_PEER);
+    assert!(sessions.contains(1, 1));
+    assert!(sessions.contains(1, 2));
+    assert!(!sessions.contains(2, 1));
+    assert!(sessions.contains(3, 1));
+  }
+
+  #[test]
+  fn test_idle_peers_are_dropped() {
+    let delivered = DeliveredSessions::default();
+    delivered.insert("idle", 1, 1);
+    delivered.insert("busy", 1, 1);
+    assert!(delivered.contains("idle", 1, 1));
+    assert!(!delivered.contains("busy", 2, 1));
+
+    let later = Instant::now() + PEER_IDLE_TIMEOUT;
+    delivered.peers.get_mut("busy").unwrap().used_at = Some(later);
+    delivered.sweep_idle_peers(later);
+    assert!(!delivered.contains("idle", 1, 1));
+    assert!(delivered.contains("busy", 1, 1));
+  }
+
+  #[test]
+  fn test_unacked_batches_ack_and_overflow() {
+    let mut unacked = UnackedBatches::default();
+    assert_eq!(unacked.push(batch(&[0]), 3), 0);
+    assert_eq!(unacked.push(batch(&[1, 2]), 3), 0);
+    assert_eq!(unacked.push(batch(&[3]), 3), 0);
+    assert_eq!(unacked.len(), 3);
+    unacked.ack(2);
+    assert_eq!(unacked.len(), 1);
+    assert_eq!(unacked.push(batch(&[4, 5, 6]), 3), 1);
+    assert_eq!(
+      unacked
+        .pending()
+        .iter()
+        .filter_map(last_sequence_number)
+        .collect::<Vec<_>>(),
+      vec![6]
+    );
+  }
+
+  #[test]
+  fn test_retain_envelopes() {
+    let batch = batch(&[0, 1, 0, 2]);
+    assert_eq!(retain_envelopes(&batch, true).envelopes.len(), 2);
+    assert_eq!(retain_envelopes(&batch, false).envelopes.len(), 2);
+    assert_eq!(last_sequence_number(&retain_envelopes(&batch, false)), None);
+    assert_eq!(last_sequence_number(&batch), Some(2));
+    assert_eq!(retain_envelopes(&batch, true).session_id, 7);
+  }
+}
@@ -1,38 +1,60 @@
+use crate::activator_actor::ActivationState;
 use crate::block_list::BlockList;
+use crate::compression::{CompressionMetrics, CompressionStats};
+use crate::config::kind_metadata::KindMetadata;
 use crate::config::server_config::ServerConfig;
 use crate::config::Config;
 use crate::endpoint_manager::EndpointManager;
 use crate::endpoint_reader::EndpointReader;
+use crate::endpoint_writer::create_channel;
+use crate::generated::remote::remoting_client::RemotingClient;
 use crate::generated::remote::remoting_server::RemotingServer;
-use crate::messages::RemoteDeliver;
+use crate::generated::remote::{
+  ActorPidRequest, ActorPidResponse, GetProcessDiagnosticsRequest, KindInfo, ListKindsRequest, ListProcessesMatchType,
+  ListProcessesRequest, SpawnInit,
+};
+use crate::messages::{Ping, Pong, RemoteDeliver};
+use crate::remote_authenticator::attach_credentials;
+use crate::remote_metrics::RemoteMetrics;
 use crate::remote_process::RemoteProcess;
+use crate::remote_spawn::RemoteSpawnError;
 use crate::serializer::SerializerId;
+use crate::serializer_registry::SerializerRegistry;
+use crate::transport::Transport;
 use dashmap::DashMap;
-use nexus_actor_core_rs::actor::actor::Props;
+use nexus_actor_core_rs::actor::actor::{ExtendedPid, Props};
 use nexus_actor_core_rs::actor::actor_system::ActorSystem;
-use nexus_actor_core_rs::actor::message::{MessageHandle, ReadonlyMessageHeadersHandle};
+use nexus_actor_core_rs::actor::context::SenderPart;
+use nexus_actor_core_rs::actor::message::{Message, MessageHandle, ReadonlyMessageHeadersHandle};
 use nexus_actor_core_rs::actor::process::process_registry::AddressResolver;
 use nexus_actor_core_rs::actor::process::ProcessHandle;
 use nexus_actor_core_rs::extensions::{next_extension_id, Extension, ExtensionId};
-use nexus_actor_core_rs::generated::actor::Pid;
+use nexus_actor_core_rs::generated::actor::{DeadLetterResponse, Pid, Stop};
 use once_cell::sync::Lazy;
 use std::any::Any;
 use std::future::Future;
-use std::net::ToSocketAddrs;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::oneshot;
 use tokio::sync::Mutex;
-use tonic::transport::Server;
+use tonic::transport::{Channel, Server};
 
 #[derive(Debug, Clone, Error)]
 pub enum RemoteError {
   #[error("Server error")]
   ServerError,
+  #[error("Client error: {0}")]
+  ClientError(String),
 }
 
 pub static EXTENSION_ID: Lazy<ExtensionId> = Lazy::new(next_extension_id);
 
+// Version of the remoting wire protocol exchanged during the handshake.
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
 #[derive(Debug, Clone)]
 struct Shutdown {
   tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
@@ -63,25 +85,66 @@ pub struct Remote {
   endpoint_manager: Arc<Mutex<Option<EndpointManager>>>,
   config: Config,
   kinds: Arc<DashMap<String, Props>>,
+  kind_metadata: Arc<DashMap<String, KindMetadata>>,
+  activations: ActivationState,
   block_list: BlockList,
+  compression_metrics: Arc<DashMap<String, Arc<CompressionMetrics>>>,
+  outbound_queue_depths: Arc<DashMap<String, Arc<AtomicUsize>>>,
+  // Present when the actor system is configured with a metrics provider.
+  metrics: Option<RemoteMetrics>,
+  serializer_registry: SerializerRegistry,
   shutdown: Option<Shutdown>,
 }
 
 impl Remote {
   pub async fn new(actor_system: ActorSystem, config: Config) -> Self {
-    let block_list = BlockList::new();
+    let mut block_list = BlockList::new().with_actor_system(actor_system.clone());
+    if let Some(path) = config.get_block_list_path().await {
+      match block_list.clone().with_file(&path).await {
+        Ok(loaded) => block_list = loaded,
+        Err(e) => tracing::error!("Failed to load BlockList: path = {}, error = {}", path.display(), e),
+      }
+    }
+    let outbound_queue_depths = Arc::new(DashMap::new());
+    let activations = ActivationState::new(config.get_activation_rate_limit().await);
+    let metrics = match actor_system.get_config().await.metrics_provider {
+      Some(metrics_provider) => {
+        match RemoteMetrics::new(
+          metrics_provider,
+          outbound_queue_depths.clone(),
+          activations.get_counts(),
+        ) {
+          Ok(metrics) => Some(metrics),
+          Err(e) => {
+            tracing::error!("Failed to initialize remote metrics: {}", e);
+            None
+          }
+        }
+      }
+      None => None,
+    };
     let mut r = Remote {
       actor_system: actor_system.clone(),
       endpoint_reader: Arc::new(Mutex::new(None)),
       endpoint_manager: Arc::new(Mutex::new(None)),
       config: config.clone(),
       kinds: Arc::new(DashMap::new()),
+      kind_metadata: Arc::new(DashMap::new()),
+      activations,
       block_list,
+      compression_metrics: Arc::new(DashMap::new()),
+      outbound_queue_depths,
+      metrics,
+      serializer_registry: SerializerRegistry::new(),
       shutdown: None,
     };
+    r.register_builtin_messages();
     for (k, v) in config.get_kinds().await {
       r.register(&k, v);
     }
+    for (k, v) in config.get_kind_metadata().await {
+      r.kind_metadata.insert(k, v);
+    }
     actor_system
       .get_extensions()
       .await
@@ -100,12 +163,12 @@ impl Remote {
     *mg = Some(endpoint_reader);
   }
 
-  pub async fn get_endpoint_manager_opt(&self) -> Option<EndpointManager> {
+  pub(crate) async fn get_endpoint_manager_opt(&self) -> Option<EndpointManager> {
     let mg = self.endpoint_manager.lock().await;
     mg.clone()
   }
 
-  pub async fn get_endpoint_manager(&self) -> EndpointManager {
+  pub(crate) async fn get_endpoint_manager(&self) -> EndpointManager {
     let mg = self.endpoint_manager.lock().await;
     mg.as_ref().expect("EndpointManager is not found").clone()
   }
@@ -127,6 +190,10 @@ impl Remote {
     &self.actor_system
   }
 
+  pub fn get_serializer_registry(&self) -> &SerializerRegistry {
+    &self.serializer_registry
+  }
+
   pub fn get_block_list(&self) -> &BlockList {
     &self.block_list
   }
@@ -135,21 +202,123 @@ impl Remote {
     &mut self.block_list
   }
 
+  // Compression statistics of the envelopes sent to the given address.
+  pub fn get_compression_stats(&self, address: &str) -> Option<CompressionStats> {
+    self.compression_metrics.get(address).map(|metrics| metrics.snapshot())
+  }
+
+  pub(crate) fn get_compression_metrics(&self, address: &str) -> Arc<CompressionMetrics> {
+    self
+      .compression_metrics
+      .entry(address.to_string())
+      .or_default()
+      .value()
+      .clone()
+  }
+
+  // Messages waiting in the outbound queue of the endpoint for the given address.
+  pub fn get_outbound_queue_depth(&self, address: &str) -> Option<usize> {
+    self
+      .outbound_queue_depths
+      .get(address)
+      .map(|depth| depth.load(Ordering::Relaxed))
+  }
+
+  pub(crate) fn get_outbound_queue_depth_gauge(&self, address: &str) -> Arc<AtomicUsize> {
+    self
+      .outbound_queue_depths
+      .entry(address.to_string())
+      .or_default()
+      .value()
+      .clone()
+  }
+
+  pub(crate) fn get_metrics(&self) -> Option<&RemoteMetrics> {
+    self.metrics.as_ref()
+  }
+
+  pub(crate) fn get_activations(&self) -> &ActivationState {
+    &self.activations
+  }
+
+  // Live actors the activator of this node spawned for `kind`.
+  pub fn get_activation_count(&self, kind: &str) -> usize {
+    self.activations.get_count(kind)
+  }
+
+  // Messages exchanged between remote nodes themselves.
+  fn register_builtin_messages(&self) {
+    self
+      .serializer_registry
+      .register_json::<Ping>()
+      .expect("Failed to register Ping");
+    self
+      .serializer_registry
+      .register_json::<Pong>()
+      .expect("Failed to register Pong");
+    self
+      .serializer_registry
+      .register_proto::<ActorPidRequest>()
+      .expect("Failed to register ActorPidRequest");
+    self
+      .serializer_registry
+      .register_proto::<ActorPidResponse>()
+      .expect("Failed to register ActorPidResponse");
+    self
+      .serializer_registry
+      .register_proto::<DeadLetterResponse>()
+      .expect("Failed to register DeadLetterResponse");
+    self
+      .serializer_registry
+      .register_proto::<Stop>()
+      .expect("Failed to register Stop");
+  }
+
   pub fn register(&mut self, kind: &str, props: Props) {
     self.kinds.insert(kind.to_string(), props);
   }
 
+  pub fn register_with_metadata(&mut self, kind: &str, props: Props, kind_metadata: KindMetadata) {
+    self.kind_metadata.insert(kind.to_string(), kind_metadata);
+    self.register(kind, props);
+  }
+
+  // Actors spawned from the kind before keep running; new activation requests fail.
+  pub fn unregister(&mut self, kind: &str) -> Option<Props> {
+    self.kind_metadata.remove(kind);
+    self.kinds.remove(kind).map(|(_, props)| props)
+  }
+
   pub fn get_known_kinds(&self) -> Vec<String> {
     self.kinds.iter().map(|kv| kv.key().clone()).collect()
   }
 
+  pub fn get_kind_metadata(&self, kind: &str) -> Option<KindMetadata> {
+    self.kind_metadata.get(kind).map(|kind_metadata| kind_metadata.clone())
+  }
+
+  pub(crate) fn get_kind_infos(&self) -> Vec<KindInfo> {
+    self
+      .kinds
+      .iter()
+      .map(|kv| {
+        let kind_metadata = self.get_kind_metadata(kv.key()).unwrap_or_default();
+        KindInfo {
+          name: kv.key().clone(),
+          version: kind_metadata.version.unwrap_or_default(),
+          max_instances: kind_metadata.max_instances.unwrap_or_default(),
+        }
+      })
+      .collect()
+  }
+
   pub async fn start(&mut self) -> Result<(), RemoteError> {
     self.start_with_callback(|| async {}).await
   }
 
   pub async fn start_with_callback<F, Fut>(&mut self, on_start: F) -> Result<(), RemoteError>
   where
-    F: FnOnce() -> Fut,
+    F: FnOnce() -> Fut + Send,
     Fut: Future<Output = ()> + Send + Sync, {
     let (shutdown, rx) = Shutdown::new();
     self.shutdown = Some(shutdown);
@@ -158,24 +327,24 @@ impl Remote {
     let cloned_self = my_self.clone();
     let mut server = Server::builder();
     if let Some(sc) = &self.config.get_server_config().await {
-      Self::configure_server(server.clone(), sc);
+      server = Self::configure_server(server, sc);
+    }
+    if let Some(tls_config) = &self.config.get_tls_config().await {
+      let server_tls_config = tls_config.server_tls_config().map_err(|e| {
+        tracing::error!("Failed to configure TLS: {:?}", e);
+        RemoteError::ServerError
+      })?;
+      server = server.tls_config(server_tls_config).map_err(|e| {
+        tracing::error!("Failed to configure TLS: {:?}", e);
+        RemoteError::ServerError
+      })?;
     }
 
-    let host_str = if let Some(advertise_host) = self.config.get_advertised_host().await {
-      advertise_host
-    } else {
-      self.config.get_host().await.expect("Host is not found")
-    };
-    tracing::debug!("Host: {}", host_str);
-    let port = self.config.get_port().await.expect("Port is not found");
-    let socket_addrs = (host_str.clone(), port)
-      .to_socket_addrs()
-      .expect("Invalid host")
-      .collect::<Vec<_>>();
-    let socket_addr = socket_addrs
-      .into_iter()
-      .find(|addr| addr.is_ipv4())
-      .expect("Failed to resolve hostname");
+    let transport = self.config.get_transport().await;
+    let address = transport.local_address(&self.config).await.map_err(|e| {
+      tracing::error!("Failed to determine the address of this node: {:?}", e);
+      RemoteError::ServerError
+    })?;
 
     let mut process_registry = self.actor_system.get_process_registry().await;
     process_registry
@@ -188,8 +357,7 @@ impl Remote {
         }
       }))
       .await;
-    process_registry.set_address(format!("{}:{}", host_str, port)).await;
-    tracing::info!("Starting server: {}", socket_addr);
+    process_registry.set_address(address.clone()).await;
 
     let self_weak = Arc::downgrade(&my_self);
 
@@ -205,14 +373,17 @@ impl Remote {
 
     let router = server.add_service(RemotingServer::new(endpoint_reader));
     let shutdown_future = async {
-      tracing::info!("Server started: {}", socket_addr);
+      tracing::info!("Server started: {}", address);
       on_start().await;
       rx.await.ok();
     };
-    if router.serve_with_shutdown(socket_addr, shutdown_future).await.is_err() {
-      return Err(RemoteError::ServerError);
-    }
-    Ok(())
+    transport
+      .serve(&self.config, router, Box::pin(shutdown_future))
+      .await
+      .map_err(|e| {
+        tracing::error!("Server failed: {:?}", e);
+        RemoteError::ServerError
+      })
   }
 
   fn configure_server(mut server: Server, sc: &ServerConfig) -> Server {
@@ -259,6 +430,132 @@ impl Remote {
     Ok(())
   }
 
+  pub async fn list_processes(
+    &self,
+    address: &str,
+    pattern: &str,
+    match_type: ListProcessesMatchType,
+  ) -> Result<Vec<Pid>, RemoteError> {
+    let mut client = self.create_remoting_client(address).await?;
+    let request = self
+      .create_request(ListProcessesRequest {
+        pattern: pattern.to_string(),
+        r#type: match_type as i32,
+      })
+      .await;
+    let response = client
+      .list_processes(request)
+      .await
+      .map_err(|status| RemoteError::ClientError(status.message().to_string()))?;
+    Ok(response.into_inner().pids)
+  }
+
+  // Kinds the node at `address` can spawn, e.g. to choose where to place a kind.
+  pub async fn list_kinds(&self, address: &str) -> Result<Vec<KindInfo>, RemoteError> {
+    let mut client = self.create_remoting_client(address).await?;
+    let request = self.create_request(ListKindsRequest {}).await;
+    let response = client
+      .list_kinds(request)
+      .await
+      .map_err(|status| RemoteError::ClientError(status.message().to_string()))?;
+    Ok(response.into_inner().kinds)
+  }
+
+  pub async fn get_process_diagnostics(&self, pid: &Pid) -> Result<String, RemoteError> {
+    let mut client = self.create_remoting_client(&pid.address).await?;
+    let request = self
+      .create_request(GetProcessDiagnosticsRequest { pid: Some(pid.clone()) })
+      .await;
+    let response = client
+      .get_process_diagnostics(request)
+      .await
+      .map_err(|status| RemoteError::ClientError(status.message().to_string()))?;
+    Ok(response.into_inner().diagnostics_string)
+  }
+
+  // Spawns `kind` on the node at `address` through its activator.
+  pub async fn spawn_remote(
+    &self,
+    address: &str,
+    kind: &str,
+    name: &str,
+    timeout: Duration,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    self.request_spawn(address, kind, name, None, timeout).await
+  }
+
+  // Like `spawn_remote`, and delivers `init` to the spawned actor before any other message.
+  // `init` must be registered with the serializer registries of both nodes.
+  pub async fn spawn_remote_with_init(
+    &self,
+    address: &str,
+    kind: &str,
+    name: &str,
+    init: MessageHandle,
+    timeout: Duration,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    let type_name = init.get_type_name();
+    let (serializer_id, data) = self
+      .serializer_registry
+      .serialize_any(init.as_any(), &SerializerId::None, &type_name)
+      .map_err(RemoteSpawnError::InvalidInit)?;
+    let init = SpawnInit {
+      type_name: self.serializer_registry.get_manifest(&type_name).to_string(),
+      serializer_id: serializer_id.into(),
+      data,
+    };
+    self.request_spawn(address, kind, name, Some(init), timeout).await
+  }
+
+  async fn request_spawn(
+    &self,
+    address: &str,
+    kind: &str,
+    name: &str,
+    init: Option<SpawnInit>,
+    timeout: Duration,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    let activator = ExtendedPid::new(Pid::new(address, "activator"));
+    let request = ActorPidRequest {
+      name: name.to_string(),
+      kind: kind.to_string(),
+      init,
+    };
+    let response = self
+      .actor_system
+      .get_root_context()
+      .await
+      .request_future(activator, MessageHandle::new(request), timeout)
+      .await
+      .result()
+      .await
+      .map_err(|e| RemoteSpawnError::from_future_error(address, e))?;
+    let response = response
+      .to_typed::<ActorPidResponse>()
+      .ok_or_else(|| RemoteSpawnError::UnexpectedResponse(response.get_type_name()))?;
+    RemoteSpawnError::check_response(address, kind, &response)
+  }
+
+  // The peer authenticates this node by the credentials of the request, as it does for message batches.
+  async fn create_request<T>(&self, message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    attach_credentials(
+      self.config.get_authenticator().await,
+      self.actor_system.get_id().await,
+      self.actor_system.get_address().await,
+      &mut request,
+    )
+    .await;
+    request
+  }
+
+  async fn create_remoting_client(&self, address: &str) -> Result<RemotingClient<Channel>, RemoteError> {
+    let channel = create_channel(&self.config, address)
+      .await
+      .map_err(|e| RemoteError::ClientError(e.to_string()))?;
+    Ok(RemotingClient::new(channel))
+  }
+
   pub async fn send_message(
     &self,
     target: Pid,
@@ -294,17 +591,49 @@ impl Extension for Remote {
 
 #[cfg(test)]
 mod tests {
-  use nexus_actor_core_rs::actor::actor::{Actor, ActorError, Props};
+  use nexus_actor_core_rs::actor::actor::{
+    Actor, ActorError, ExtendedPid, HeaderPropagation, Props, TraceContext, TRACE_PARENT_HEADER,
+  };
   use nexus_actor_core_rs::actor::actor_system::ActorSystem;
-  use nexus_actor_core_rs::actor::context::{BasePart, ContextHandle, MessagePart, SenderPart, SpawnerPart};
+  use nexus_actor_core_rs::actor::context::{
+    BasePart, ContextHandle, MessagePart, RootContext, SenderPart, SpawnerPart, StopperPart,
+  };
+  use nexus_actor_core_rs::actor::dispatch::future::ActorFutureError;
   use nexus_actor_core_rs::actor::message::Message;
-  use nexus_actor_core_rs::actor::message::{MessageHandle, ResponseHandle};
+  use nexus_actor_core_rs::actor::message::{MessageHandle, MessageHeaders, ReadonlyMessageHeaders, ResponseHandle};
+  use nexus_actor_core_rs::generated::actor::Pid;
+  use std::sync::Arc;
 
+  use crate::compression::Compression;
+  use crate::config::activation_rate_limit::ActivationRateLimit;
+  use crate::config::compression_config::CompressionConfig;
+  use crate::config::heartbeat_config::HeartbeatConfig;
+  use crate::config::kind_metadata::KindMetadata;
+  use crate::config::outbound_queue_config::{OutboundQueueConfig, OverflowPolicy};
+  use crate::config::reconnect_policy::ReconnectPolicy;
+  use crate::config::reliable_delivery_config::ReliableDeliveryConfig;
+  use crate::config::tls_config::TlsConfig;
   use crate::config::Config;
   use crate::config_option::ConfigOption;
+  use crate::fault_injector::FaultInjector;
 
-  use crate::remote::Remote;
-  use crate::serializer::initialize_proto_serializers;
+  use crate::generated::remote::connect_request::ConnectionType;
+  use crate::generated::remote::remote_message::MessageType;
+  use crate::generated::remote::remoting_client::RemotingClient;
+  use crate::generated::remote::{
+    ConnectRequest, KindInfo, ListProcessesMatchType, MessageAck, MessageBatch, MessageEnvelope, RemoteMessage,
+    ServerConnection,
+  };
+  use crate::messages::{
+    BlockListEvent, EndpointEvent, EndpointHighWatermarkEvent, EndpointReconnectingEvent, EndpointRejectedEvent,
+  };
+  use crate::remote::{Remote, RemoteError, PROTOCOL_VERSION};
+  use crate::remote_authenticator::SharedSecretAuthenticator;
+  use crate::remote_spawn::{RemoteSpawnError, SpawnPlacement};
+  use crate::serializer::SerializerId;
+  use crate::transport::in_memory_transport::{InMemoryNetwork, InMemoryTransport};
+  #[cfg(unix)]
+  use crate::transport::uds_transport::UdsTransport;
   use nexus_actor_message_derive_rs::Message;
   use std::env;
   use std::time::Duration;
@@ -429,13 +758,15 @@ mod tests {
       .with_env_filter(EnvFilter::from_default_env())
       .try_init();
 
-    initialize_proto_serializers::<EchoMessage>().expect("Failed to register serializer");
-
     // サーバー側のセットアップ
     let server_wait_group = WaitGroup::with_count(1);
     let server_system = ActorSystem::new().await.unwrap();
     let server_config = Config::from([ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8090)]).await;
     let mut server_remote = Remote::new(server_system.clone(), server_config).await;
+    server_remote
+      .get_serializer_registry()
+      .register_proto::<EchoMessage>()
+      .expect("Failed to register serializer");
     let cloned_server_wait_group = server_wait_group.clone();
     tokio::spawn(async move {
       server_remote
@@ -461,6 +792,10 @@ mod tests {
     let client_system = ActorSystem::new().await.unwrap();
     let client_config = Config::from([ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8091)]).await;
     let mut client_remote = Remote::new(client_system.clone(), client_config).await;
+    client_remote
+      .get_serializer_registry()
+      .register_proto::<EchoMessage>()
+      .expect("Failed to register serializer");
     let cloned_client_wait_group = client_wait_group.clone();
     tokio::spawn(async move {
       client_remote
@@ -493,4 +828,1873 @@ mod tests {
       panic!("Unexpected response type");
     }
   }
+
+  struct TestCertificates {
+    ca_certificate: String,
+    server_certificate: String,
+    server_private_key: String,
+    client_certificate: String,
+    client_private_key: String,
+  }
+
+  fn generate_test_certificates() -> TestCertificates {
+    let mut ca_params = rcgen::CertificateParams::new(Vec::<String>::new()).unwrap();
+    ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    ca_params
+      .distinguished_name
+      .push(rcgen::DnType::CommonName, "nexus-test-ca");
+    let ca_key = rcgen::KeyPair::generate().unwrap();
+    let ca_certificate = ca_params.self_signed(&ca_key).unwrap();
+
+    let issue = |common_name: &str| {
+      let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+      params.distinguished_name.push(rcgen::DnType::CommonName, common_name);
+      let key = rcgen::KeyPair::generate().unwrap();
+      let certificate = params.signed_by(&key, &ca_certificate, &ca_key).unwrap();
+      (certificate.pem(), key.serialize_pem())
+    };
+    let (server_certificate, server_private_key) = issue("server-node");
+    let (client_certificate, client_private_key) = issue("client-node");
+
+    TestCertificates {
+      ca_certificate: ca_certificate.pem(),
+      server_certificate,
+      server_private_key,
+      client_certificate,
+      client_private_key,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_remote_communication_with_mtls() {
+    let _ = env::set_var("RUST_LOG", "nexus_actor_core_rs=info");
+    let _ = tracing_subscriber::fmt()
+      .with_env_filter(EnvFilter::from_default_env())
+      .try_init();
+
+    let certificates = generate_test_certificates();
+
+    let server_wait_group = WaitGroup::with_count(1);
+    let server_system = ActorSystem::new().await.unwrap();
+    let server_tls_config = TlsConfig::new(
+      certificates.server_certificate.clone(),
+      certificates.server_private_key.clone(),
+    )
+    .with_ca_certificate(certificates.ca_certificate.clone())
+    .with_client_auth(true)
+    .with_domain_name("localhost")
+    .with_allowed_peer_identities(["client-node"]);
+    let server_config = Config::from([
+      ConfigOption::with_host("127.0.0.1"),
+      ConfigOption::with_port(8092),
+      ConfigOption::with_tls_config(server_tls_config),
+    ])
+    .await;
+    let mut server_remote = Remote::new(server_system.clone(), server_config).await;
+    server_remote
+      .get_serializer_registry()
+      .register_proto::<EchoMessage>()
+      .expect("Failed to register serializer");
+    let cloned_server_wait_group = server_wait_group.clone();
+    tokio::spawn(async move {
+      server_remote
+        .start_with_callback(|| async {
+          cloned_server_wait_group.done().await;
+        })
+        .await
+        .expect("Failed to start server");
+    });
+    server_wait_group.wait().await;
+
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-tls")
+      .await
+      .unwrap();
+
+    let client_wait_group = WaitGroup::with_count(1);
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_tls_config = TlsConfig::new(
+      certificates.client_certificate.clone(),
+      certificates.client_private_key.clone(),
+    )
+    .with_ca_certificate(certificates.ca_certificate.clone())
+    .with_client_auth(true)
+    .with_domain_name("localhost")
+    .with_allowed_peer_identities(["server-node"]);
+    let client_config = Config::from([
+      ConfigOption::with_host("127.0.0.1"),
+      ConfigOption::with_port(8093),
+      ConfigOption::with_tls_config(client_tls_config),
+    ])
+    .await;
+    let mut client_remote = Remote::new(client_system.clone(), client_config).await;
+    client_remote
+      .get_serializer_registry()
+      .register_proto::<EchoMessage>()
+      .expect("Failed to register serializer");
+    let cloned_client_wait_group = client_wait_group.clone();
+    tokio::spawn(async move {
+      client_remote
+        .start_with_callback(|| async {
+          cloned_client_wait_group.done().await;
+        })
+        .await
+        .expect("Failed to start client");
+    });
+    client_wait_group.wait().await;
+
+    let response = client_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new("Hello, TLS!".to_string())),
+        Duration::from_secs(10),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+
+    let echo_response = response.to_typed::<EchoMessage>().expect("Unexpected response type");
+    assert_eq!(echo_response.message, "Echo: Hello, TLS!");
+  }
+
+  #[tokio::test]
+  async fn test_list_processes_and_get_process_diagnostics() {
+    let _ = env::set_var("RUST_LOG", "nexus_actor_core_rs=info");
+    let _ = tracing_subscriber::fmt()
+      .with_env_filter(EnvFilter::from_default_env())
+      .try_init();
+
+    let server_wait_group = WaitGroup::with_count(1);
+    let server_system = ActorSystem::new().await.unwrap();
+    let server_config = Config::from([ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8094)]).await;
+    let mut server_remote = Remote::new(server_system.clone(), server_config).await;
+    let cloned_server_wait_group = server_wait_group.clone();
+    tokio::spawn(async move {
+      server_remote
+        .start_with_callback(|| async {
+          cloned_server_wait_group.done().await;
+        })
+        .await
+        .expect("Failed to start server");
+    });
+    server_wait_group.wait().await;
+
+    let mut root_context = server_system.get_root_context().await;
+    for name in ["diag-1", "diag-2", "other"] {
+      let props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+      root_context.spawn_named(props, name).await.unwrap();
+    }
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = Remote::new(client_system, Config::default()).await;
+
+    let mut ids = client_remote
+      .list_processes("127.0.0.1:8094", "diag", ListProcessesMatchType::MatchPartOfString)
+      .await
+      .unwrap()
+      .into_iter()
+      .map(|pid| pid.id)
+      .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec!["diag-1".to_string(), "diag-2".to_string()]);
+
+    let pids = client_remote
+      .list_processes("127.0.0.1:8094", "other", ListProcessesMatchType::MatchExactString)
+      .await
+      .unwrap();
+    assert_eq!(pids.len(), 1);
+
+    let pids = client_remote
+      .list_processes("127.0.0.1:8094", "^diag-[0-9]$", ListProcessesMatchType::MatchRegex)
+      .await
+      .unwrap();
+    assert_eq!(pids.len(), 2);
+
+    let result = client_remote
+      .list_processes("127.0.0.1:8094", "(", ListProcessesMatchType::MatchRegex)
+      .await;
+    assert!(matches!(result, Err(RemoteError::ClientError(_))));
+
+    let diagnostics = client_remote.get_process_diagnostics(&pids[0]).await.unwrap();
+    assert!(diagnostics.contains("EchoActor"), "{}", diagnostics);
+    assert!(diagnostics.contains("mailbox: user_messages = 0"), "{}", diagnostics);
+
+    let mut missing = pids[0].clone();
+    missing.id = "missing".to_string();
+    assert!(client_remote.get_process_diagnostics(&missing).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_diagnostics_require_authentication() {
+    let network = InMemoryNetwork::new();
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "diag-auth-a")),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("secret")),
+      ],
+    )
+    .await;
+    let trusted_system = ActorSystem::new().await.unwrap();
+    let trusted_remote = start_remote(
+      &trusted_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "diag-auth-b")),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("secret")),
+      ],
+    )
+    .await;
+    let untrusted_system = ActorSystem::new().await.unwrap();
+    let untrusted_remote = start_remote(
+      &untrusted_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "diag-auth-c")),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("wrong")),
+      ],
+    )
+    .await;
+    let activator = Pid::new("diag-auth-a", "activator");
+
+    assert!(trusted_remote.list_kinds("diag-auth-a").await.is_ok());
+    assert!(trusted_remote
+      .list_processes("diag-auth-a", "activator", ListProcessesMatchType::MatchExactString)
+      .await
+      .is_ok());
+    assert!(trusted_remote.get_process_diagnostics(&activator).await.is_ok());
+
+    assert!(untrusted_remote.list_kinds("diag-auth-a").await.is_err());
+    assert!(untrusted_remote
+      .list_processes("diag-auth-a", "activator", ListProcessesMatchType::MatchExactString)
+      .await
+      .is_err());
+    assert!(untrusted_remote.get_process_diagnostics(&activator).await.is_err());
+  }
+
+  async fn start_remote(system: &ActorSystem, options: impl IntoIterator<Item = ConfigOption>) -> Remote {
+    let wait_group = WaitGroup::with_count(1);
+    let config = Config::from(options).await;
+    let remote = Remote::new(system.clone(), config).await;
+    remote
+      .get_serializer_registry()
+      .register_proto::<EchoMessage>()
+      .expect("Failed to register serializer");
+    let mut cloned_remote = remote.clone();
+    let cloned_wait_group = wait_group.clone();
+    tokio::spawn(async move {
+      cloned_remote
+        .start_with_callback(|| async {
+          cloned_wait_group.done().await;
+        })
+        .await
+        .expect("Failed to start remote");
+    });
+    wait_group.wait().await;
+    remote
+  }
+
+  #[tokio::test]
+  async fn test_handshake_rejects_invalid_credentials() {
+    let _ = env::set_var("RUST_LOG", "nexus_actor_core_rs=info");
+    let _ = tracing_subscriber::fmt()
+      .with_env_filter(EnvFilter::from_default_env())
+      .try_init();
+
+    let server_system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    server_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<EndpointRejectedEvent>().cloned();
+        async move {
+          if let Some(rejected) = evt {
+            let _ = tx.try_send(rejected);
+          }
+        }
+      })
+      .await;
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8095),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("secret")),
+      ],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-auth")
+      .await
+      .unwrap();
+
+    let trusted_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &trusted_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8096),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("secret")),
+      ],
+    )
+    .await;
+    let response = trusted_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid.clone(),
+        MessageHandle::new(EchoMessage::new("Hello, Auth!".to_string())),
+        Duration::from_secs(10),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(
+      response.to_typed::<EchoMessage>().unwrap().message,
+      "Echo: Hello, Auth!"
+    );
+
+    let untrusted_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &untrusted_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8097),
+        ConfigOption::with_authenticator(SharedSecretAuthenticator::new("wrong")),
+      ],
+    )
+    .await;
+    let result = untrusted_system
+      .get_root_context()
+      .await
+      .request_future(
+        // A fresh pid, since the cached process handle of echo_pid belongs to trusted_system.
+        ExtendedPid::new(echo_pid.inner_pid.clone()),
+        MessageHandle::new(EchoMessage::new("Hello, Auth!".to_string())),
+        Duration::from_secs(2),
+      )
+      .await
+      .result()
+      .await;
+    assert!(result.is_err());
+
+    let rejected = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(rejected.system_id, untrusted_system.get_id().await);
+    assert_eq!(rejected.reason, "authentication failed: Invalid credentials");
+  }
+
+  #[tokio::test]
+  async fn test_handshake_rejects_unsupported_protocol_version() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8098)],
+    )
+    .await;
+
+    for protocol_version in [0, PROTOCOL_VERSION + 1] {
+      let channel = tonic::transport::Channel::from_static("http://127.0.0.1:8098")
+        .connect()
+        .await
+        .unwrap();
+      let mut client = RemotingClient::new(channel);
+      let request = RemoteMessage {
+        message_type: Some(MessageType::ConnectRequest(ConnectRequest {
+          connection_type: Some(ConnectionType::ServerConnection(ServerConnection {
+            system_id: "legacy".to_string(),
+            address: "127.0.0.1:9999".to_string(),
+            protocol_version,
+            auth_token: String::new(),
+            compressions: vec![],
+          })),
+        })),
+      };
+      let mut response = client
+        .receive(tonic::Request::new(futures::stream::once(futures::future::ready(
+          request,
+        ))))
+        .await
+        .unwrap()
+        .into_inner();
+      let message = response.message().await.unwrap().unwrap();
+      match message.message_type {
+        Some(MessageType::ConnectResponse(connect_response)) => {
+          assert!(!connect_response.blocked);
+          assert_eq!(connect_response.protocol_version, PROTOCOL_VERSION);
+          assert!(connect_response
+            .rejection_reason
+            .starts_with(&format!("unsupported protocol version: {} ", protocol_version)));
+        }
+        other => panic!("Unexpected message: {:?}", other),
+      }
+    }
+  }
+
+  // Forwards TCP connections from `listen` to `target` until the returned handle is aborted.
+  fn start_proxy(listen: &'static str, target: &'static str) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+      let listener = tokio::net::TcpListener::bind(listen).await.unwrap();
+      let mut connections = tokio::task::JoinSet::new();
+      while let Ok((mut inbound, _)) = listener.accept().await {
+        connections.spawn(async move {
+          if let Ok(mut outbound) = tokio::net::TcpStream::connect(target).await {
+            let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+          }
+        });
+      }
+    })
+  }
+
+  #[tokio::test]
+  async fn test_endpoint_writer_reconnects_after_connection_loss() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8099)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-reconnect")
+      .await
+      .unwrap();
+    let proxy = start_proxy("127.0.0.1:8100", "127.0.0.1:8099");
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+    client_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<EndpointReconnectingEvent>().cloned();
+        async move {
+          if let Some(reconnecting) = evt {
+            let _ = tx.try_send(reconnecting);
+          }
+        }
+      })
+      .await;
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8101),
+        ConfigOption::with_reconnect_policy(
+          ReconnectPolicy::new(Duration::from_millis(50), Duration::from_millis(200), 50).with_jitter(0.0),
+        ),
+      ],
+    )
+    .await;
+
+    // Address the echo actor through the proxy so the connection can be cut.
+    let echo_pid = ExtendedPid::new(Pid {
+      address: "127.0.0.1:8100".to_string(),
+      id: "echo-reconnect".to_string(),
+      request_id: 0,
+    });
+    let request = |message: &str| {
+      let client_system = client_system.clone();
+      let echo_pid = echo_pid.clone();
+      let message = message.to_string();
+      async move {
+        client_system
+          .get_root_context()
+          .await
+          .request_future(
+            echo_pid,
+            MessageHandle::new(EchoMessage::new(message)),
+            Duration::from_secs(10),
+          )
+          .await
+          .result()
+          .await
+      }
+    };
+
+    let response = request("before").await.unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: before");
+
+    proxy.abort();
+    let _ = proxy.await;
+
+    let reconnecting = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(reconnecting.address, "127.0.0.1:8100");
+    assert_eq!(reconnecting.attempt, 1);
+    assert_eq!(reconnecting.max_attempts, 50);
+
+    let _proxy = start_proxy("127.0.0.1:8100", "127.0.0.1:8099");
+    let response = request("after").await.unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: after");
+  }
+
+  #[tokio::test]
+  async fn test_idle_endpoint_writer_does_not_reconnect() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8122)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-idle")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+    client_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<EndpointReconnectingEvent>().cloned();
+        async move {
+          if let Some(reconnecting) = evt {
+            let _ = tx.try_send(reconnecting);
+          }
+        }
+      })
+      .await;
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8123),
+        ConfigOption::with_reconnect_policy(
+          ReconnectPolicy::new(Duration::from_millis(50), Duration::from_millis(200), 50).with_jitter(0.0),
+        ),
+      ],
+    )
+    .await;
+
+    let echo_pid = ExtendedPid::new(Pid::new("127.0.0.1:8122", "echo-idle"));
+    let response = client_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new("hello".to_string())),
+        Duration::from_secs(10),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: hello");
+
+    // Several times the backoff, during which the connection has to stay up.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert!(rx.try_recv().is_err());
+  }
+
+  #[tokio::test]
+  async fn test_message_headers_survive_the_remote_round_trip() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8124)],
+    )
+    .await;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let props = Props::from_async_actor_receiver_with_opts(
+      move |ctx| {
+        let tx = tx.clone();
+        async move {
+          if ctx.get_message_handle().await.to_typed::<EchoMessage>().is_some() {
+            let headers = ctx.get_message_header_handle().await.map(|h| h.to_map());
+            tx.send(headers).await.unwrap();
+          }
+          Ok(())
+        }
+      },
+      [Props::with_receiver_middlewares([TraceContext::of_receiver()])],
+    )
+    .await;
+    server_system
+      .get_root_context()
+      .await
+      .spawn_named(props, "traced")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8125)],
+    )
+    .await;
+    let parent = TraceContext::new_root();
+    let mut headers = MessageHeaders::new();
+    headers.set(TRACE_PARENT_HEADER.to_string(), parent.to_string());
+    headers.set("tenant".to_string(), "acme".to_string());
+    let mut root_context = RootContext::new(
+      client_system.clone(),
+      Arc::new(headers),
+      &[HeaderPropagation::of_sender([TRACE_PARENT_HEADER, "tenant"])],
+    );
+    root_context
+      .send(
+        ExtendedPid::new(Pid::new("127.0.0.1:8124", "traced")),
+        MessageHandle::new(EchoMessage::new("hello".to_string())),
+      )
+      .await;
+
+    let headers = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+      .await
+      .unwrap()
+      .unwrap()
+      .unwrap();
+    assert_eq!(headers.get("tenant").map(String::as_str), Some("acme"));
+    // The receiver replaces the traceparent with its own span, in the same trace.
+    let received = TraceContext::parse(&headers[TRACE_PARENT_HEADER]).unwrap();
+    assert_eq!(received.trace_id, parent.trace_id);
+    assert_ne!(received.span_id, parent.span_id);
+  }
+
+  #[tokio::test]
+  async fn test_remote_communication_with_compression() {
+    let compression_config = CompressionConfig::new([Compression::Zstd, Compression::Gzip]).with_threshold(64);
+
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8102),
+        ConfigOption::with_compression_config(CompressionConfig::new([Compression::Gzip])),
+      ],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-compression")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = start_remote(
+      &client_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8103),
+        ConfigOption::with_compression_config(compression_config),
+      ],
+    )
+    .await;
+
+    let message = "compressible payload ".repeat(100);
+    let response = client_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new(message.clone())),
+        Duration::from_secs(10),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(
+      response.to_typed::<EchoMessage>().unwrap().message,
+      format!("Echo: {}", message)
+    );
+
+    let stats = client_remote.get_compression_stats("127.0.0.1:8102").unwrap();
+    assert_eq!(stats.messages, 1);
+    assert_eq!(stats.compressed_messages, 1);
+    assert!(stats.ratio() < 0.5);
+  }
+
+  #[tokio::test]
+  async fn test_heartbeat_terminates_unresponsive_endpoint() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8104)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-heartbeat")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+    client_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<EndpointEvent>().cloned();
+        async move {
+          if let Some(EndpointEvent::EndpointTerminated(terminated)) = evt {
+            let _ = tx.try_send(terminated);
+          }
+        }
+      })
+      .await;
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8105),
+        ConfigOption::with_heartbeat_config(
+          HeartbeatConfig::new(Duration::from_millis(100))
+            .with_timeout(Duration::from_secs(1))
+            .with_threshold(3.0)
+            .with_acceptable_heartbeat_pause(Duration::from_secs(1)),
+        ),
+      ],
+    )
+    .await;
+
+    let response = tokio::time::timeout(Duration::from_secs(10), async {
+      client_system
+        .get_root_context()
+        .await
+        .request_future(
+          echo_pid,
+          MessageHandle::new(EchoMessage::new("ping".to_string())),
+          Duration::from_secs(5),
+        )
+        .await
+        .result()
+        .await
+    })
+    .await
+    .expect("echo request did not complete")
+    .unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: ping");
+
+    // Pongs keep the endpoint alive while the peer answers.
+    sleep(Duration::from_secs(1)).await;
+    assert!(rx.try_recv().is_err());
+
+    // The connection stays open but the peer no longer answers, like a hung node.
+    tokio::time::timeout(Duration::from_secs(10), async {
+      server_system
+        .get_root_context()
+        .await
+        .stop_future(&ExtendedPid::new(Pid::new("127.0.0.1:8104", "activator")))
+        .await
+        .result()
+        .await
+    })
+    .await
+    .expect("activator did not stop")
+    .unwrap();
+    let terminated = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+      .await
+      .expect("endpoint was not terminated")
+      .unwrap();
+    assert_eq!(terminated.address, "127.0.0.1:8104");
+  }
+
+  // Replies to every EchoMessage with the greeting it was spawned with.
+  #[derive(Debug, Clone, Default)]
+  struct GreeterActor {
+    greeting: Option<String>,
+  }
+
+  #[async_trait::async_trait]
+  impl Actor for GreeterActor {
+    async fn receive(&mut self, ctx: ContextHandle) -> Result<(), ActorError> {
+      if let Some(msg) = ctx.get_message_handle().await.to_typed::<EchoMessage>() {
+        match &self.greeting {
+          None => self.greeting = Some(msg.message),
+          Some(greeting) => {
+            ctx
+              .respond(ResponseHandle::new(EchoMessage::new(format!(
+                "{}, {}",
+                greeting, msg.message
+              ))))
+              .await;
+          }
+        }
+      }
+      Ok(())
+    }
+  }
+
+  #[tokio::test]
+  async fn test_spawn_remote() {
+    let mut server_systems = vec![];
+    for port in [8106, 8107] {
+      let system = ActorSystem::new().await.unwrap();
+      start_remote(
+        &system,
+        [
+          ConfigOption::with_host("127.0.0.1"),
+          ConfigOption::with_port(port),
+          ConfigOption::with_kind(
+            "greeter",
+            Props::from_async_actor_producer(|_| async { GreeterActor::default() }).await,
+          ),
+        ],
+      )
+      .await;
+      server_systems.push(system);
+    }
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = start_remote(
+      &client_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8108)],
+    )
+    .await;
+    let timeout = Duration::from_secs(5);
+
+    let pid = client_remote
+      .spawn_remote_with_init(
+        "127.0.0.1:8106",
+        "greeter",
+        "alice",
+        MessageHandle::new(EchoMessage::new("Hello".to_string())),
+        timeout,
+      )
+      .await
+      .unwrap();
+    assert_eq!(pid.address(), "127.0.0.1:8106");
+    let response = client_system
+      .get_root_context()
+      .await
+      .request_future(
+        pid.clone(),
+        MessageHandle::new(EchoMessage::new("Bob".to_string())),
+        timeout,
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Hello, Bob");
+
+    assert_eq!(
+      client_remote
+        .spawn_remote("127.0.0.1:8106", "greeter", "alice", timeout)
+        .await,
+      Err(RemoteSpawnError::NameAlreadyExists(pid))
+    );
+    assert_eq!(
+      client_remote
+        .spawn_remote("127.0.0.1:8106", "unknown", "", timeout)
+        .await,
+      Err(RemoteSpawnError::SpawnFailed {
+        address: "127.0.0.1:8106".to_string(),
+        kind: "unknown".to_string(),
+      })
+    );
+
+    let placement = SpawnPlacement::round_robin(["127.0.0.1:8106", "127.0.0.1:8107"]);
+    let mut addresses = vec![];
+    for _ in 0..4 {
+      let pid = placement.spawn(&client_remote, "greeter", "", timeout).await.unwrap();
+      addresses.push(pid.address().to_string());
+    }
+    assert_eq!(
+      addresses,
+      ["127.0.0.1:8106", "127.0.0.1:8107", "127.0.0.1:8106", "127.0.0.1:8107"]
+    );
+
+    // 8106 now runs three spawned actors and 8107 two.
+    let placement = SpawnPlacement::least_loaded(["127.0.0.1:8106", "127.0.0.1:8107"]);
+    let pid = placement.spawn(&client_remote, "greeter", "", timeout).await.unwrap();
+    assert_eq!(pid.address(), "127.0.0.1:8107");
+  }
+
+  #[derive(Clone, PartialEq, Message, prost::Message)]
+  pub struct UnregisteredMessage {
+    #[prost(string, tag = "1")]
+    pub message: String,
+  }
+
+  #[tokio::test]
+  async fn test_unknown_message_type_is_reported_to_sender() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8109)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-unknown-type")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = start_remote(
+      &client_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8110)],
+    )
+    .await;
+    // Only the client knows how to serialize this type.
+    client_remote
+      .get_serializer_registry()
+      .register_proto::<UnregisteredMessage>()
+      .unwrap();
+
+    let root_context = client_system.get_root_context().await;
+    let result = root_context
+      .request_future(
+        echo_pid.clone(),
+        MessageHandle::new(UnregisteredMessage {
+          message: "lost".to_string(),
+        }),
+        Duration::from_secs(5),
+      )
+      .await
+      .result()
+      .await;
+    assert_eq!(result.err(), Some(ActorFutureError::DeadLetterError));
+
+    // Registered messages on the same connection are still delivered.
+    let response = root_context
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new("still here".to_string())),
+        Duration::from_secs(5),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: still here");
+  }
+
+  #[tokio::test]
+  async fn test_request_to_unknown_remote_target_fails_fast() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8118)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-unknown-target")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8119)],
+    )
+    .await;
+
+    let root_context = client_system.get_root_context().await;
+    let started = tokio::time::Instant::now();
+    let result = root_context
+      .request_future(
+        ExtendedPid::new(Pid::new("127.0.0.1:8118", "does-not-exist")),
+        MessageHandle::new(EchoMessage::new("lost".to_string())),
+        Duration::from_secs(10),
+      )
+      .await
+      .result()
+      .await;
+    assert_eq!(result.err(), Some(ActorFutureError::DeadLetterError));
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // The connection stays usable for known targets.
+    let response = root_context
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new("still here".to_string())),
+        Duration::from_secs(5),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+    assert_eq!(response.to_typed::<EchoMessage>().unwrap().message, "Echo: still here");
+  }
+
+  #[tokio::test]
+  async fn test_retransmitted_reliable_envelope_is_delivered_once() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8111)],
+    )
+    .await;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    let recorder_props = Props::from_async_actor_receiver(move |ctx| {
+      let tx = tx.clone();
+      async move {
+        if let Some(msg) = ctx.get_message_handle().await.to_typed::<EchoMessage>() {
+          let _ = tx.send(msg.message).await;
+        }
+        Ok(())
+      }
+    })
+    .await;
+    let recorder_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(recorder_props, "recorder")
+      .await
+      .unwrap();
+
+    let channel = tonic::transport::Channel::from_static("http://127.0.0.1:8111")
+      .connect()
+      .await
+      .unwrap();
+    let mut client = RemotingClient::new(channel);
+    let batch = RemoteMessage {
+      message_type: Some(MessageType::MessageBatch(MessageBatch {
+        type_names: vec![std::any::type_name::<EchoMessage>().to_string()],
+        targets: vec![recorder_pid.inner_pid.clone()],
+        envelopes: vec![MessageEnvelope {
+          message_data: prost::Message::encode_to_vec(&EchoMessage::new("once".to_string())),
+          serializer_id: SerializerId::Proto.into(),
+          sequence_number: 1,
+          ..MessageEnvelope::default()
+        }],
+        senders: vec![],
+        session_id: 42,
+      })),
+    };
+    // The second request is a retransmission after a lost acknowledgement.
+    for _ in 0..2 {
+      let mut response = client
+        .receive(tonic::Request::new(futures::stream::once(futures::future::ready(
+          batch.clone(),
+        ))))
+        .await
+        .unwrap()
+        .into_inner();
+      let message = response.message().await.unwrap().unwrap();
+      assert_eq!(
+        message.message_type,
+        Some(MessageType::MessageAck(MessageAck { sequence_number: 1 }))
+      );
+    }
+
+    assert_eq!(rx.recv().await.unwrap(), "once");
+    assert!(tokio::time::timeout(Duration::from_millis(500), rx.recv())
+      .await
+      .is_err());
+  }
+
+  #[tokio::test]
+  async fn test_reliable_delivery_round_trip() {
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8112),
+        ConfigOption::with_reliable_delivery_config(
+          ReliableDeliveryConfig::default().with_message_type::<EchoMessage>(),
+        ),
+      ],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-reliable")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8113),
+        ConfigOption::with_reliable_delivery_config(ReliableDeliveryConfig::default().with_address("127.0.0.1:8112")),
+      ],
+    )
+    .await;
+
+    let root_context = client_system.get_root_context().await;
+    for i in 0..3 {
+      let response = root_context
+        .request_future(
+          echo_pid.clone(),
+          MessageHandle::new(EchoMessage::new(format!("reliable {}", i))),
+          Duration::from_secs(5),
+        )
+        .await
+        .result()
+        .await
+        .unwrap();
+      assert_eq!(
+        response.to_typed::<EchoMessage>().unwrap().message,
+        format!("Echo: reliable {}", i)
+      );
+    }
+  }
+
+  #[tokio::test]
+  async fn test_full_outbound_queue_rejects_messages() {
+    let system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<EndpointHighWatermarkEvent>().cloned();
+        async move {
+          if let Some(high_watermark) = evt {
+            let _ = tx.try_send(high_watermark);
+          }
+        }
+      })
+      .await;
+    let remote = start_remote(
+      &system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8114),
+        // Nothing listens on 8115, so the EndpointWriter is busy connecting while messages pile up.
+        ConfigOption::with_reconnect_policy(ReconnectPolicy::new(Duration::from_secs(3), Duration::from_secs(3), 2)),
+        ConfigOption::with_outbound_queue_config(
+          OutboundQueueConfig::new(2)
+            .with_overflow_policy(OverflowPolicy::Reject)
+            .with_high_watermark(2),
+        ),
+      ],
+    )
+    .await;
+
+    let root_context = system.get_root_context().await;
+    let target = ExtendedPid::new(Pid::new("127.0.0.1:8115", "echo"));
+    let mut futures = vec![];
+    for i in 0..4 {
+      futures.push(
+        root_context
+          .request_future(
+            target.clone(),
+            MessageHandle::new(EchoMessage::new(format!("queued {}", i))),
+            Duration::from_secs(2),
+          )
+          .await,
+      );
+    }
+
+    for future in futures.split_off(2) {
+      assert_eq!(future.result().await.err(), Some(ActorFutureError::DeadLetterError));
+    }
+    let high_watermark = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(
+      high_watermark,
+      EndpointHighWatermarkEvent {
+        address: "127.0.0.1:8115".to_string(),
+        queued: 2,
+        high_watermark: 2,
+      }
+    );
+    assert_eq!(remote.get_outbound_queue_depth("127.0.0.1:8115"), Some(2));
+  }
+
+  #[tokio::test]
+  async fn test_full_outbound_queue_dead_letters_the_oldest_messages() {
+    let system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &system,
+      [
+        ConfigOption::with_host("127.0.0.1"),
+        ConfigOption::with_port(8120),
+        // Nothing listens on 8121, so the EndpointWriter is busy connecting while messages pile up.
+        ConfigOption::with_reconnect_policy(ReconnectPolicy::new(Duration::from_secs(3), Duration::from_secs(3), 2)),
+        ConfigOption::with_outbound_queue_config(
+          OutboundQueueConfig::new(2).with_overflow_policy(OverflowPolicy::DropOldest),
+        ),
+      ],
+    )
+    .await;
+
+    let root_context = system.get_root_context().await;
+    let target = ExtendedPid::new(Pid::new("127.0.0.1:8121", "echo"));
+    let mut futures = vec![];
+    for i in 0..4 {
+      futures.push(
+        root_context
+          .request_future(
+            target.clone(),
+            MessageHandle::new(EchoMessage::new(format!("queued {}", i))),
+            Duration::from_secs(2),
+          )
+          .await,
+      );
+    }
+
+    let newest = futures.split_off(2);
+    for future in futures {
+      assert_eq!(future.result().await.err(), Some(ActorFutureError::DeadLetterError));
+    }
+    for future in newest {
+      assert_eq!(future.result().await.err(), Some(ActorFutureError::TimeoutError));
+    }
+  }
+
+  // force_flush blocks until the periodic reader task exports, which needs a second worker.
+  #[tokio::test(flavor = "multi_thread")]
+  async fn test_remote_metrics_are_recorded_per_peer() {
+    use nexus_actor_core_rs::actor::{ConfigOption as CoreConfigOption, MetricsProvider};
+    use opentelemetry_sdk::metrics::data::{ResourceMetrics, Sum};
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::runtime;
+    use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+    use std::sync::Arc;
+
+    let exporter = InMemoryMetricsExporter::default();
+    let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+    let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+    let metrics_provider = Arc::new(MetricsProvider::Sdk(meter_provider.clone()));
+    let server_system =
+      ActorSystem::new_config_options([CoreConfigOption::SetMetricsProvider(metrics_provider.clone())])
+        .await
+        .unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8116)],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo-metrics")
+      .await
+      .unwrap();
+    let client_system = ActorSystem::new_config_options([CoreConfigOption::SetMetricsProvider(metrics_provider)])
+      .await
+      .unwrap();
+    start_remote(
+      &client_system,
+      [ConfigOption::with_host("127.0.0.1"), ConfigOption::with_port(8117)],
+    )
+    .await;
+    client_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid,
+        MessageHandle::new(EchoMessage::new("measured".to_string())),
+        Duration::from_secs(5),
+      )
+      .await
+      .result()
+      .await
+      .unwrap();
+
+    let bytes = |resource_metrics: &[ResourceMetrics], name: &str, address: &str| -> u64 {
+      resource_metrics
+        .iter()
+        .flat_map(|resource_metrics| resource_metrics.scope_metrics.iter())
+        .flat_map(|scope_metrics| scope_metrics.metrics.iter())
+        .filter(|metric| metric.name == name)
+        .filter_map(|metric| metric.data.as_any().downcast_ref::<Sum<u64>>())
+        .flat_map(|sum| sum.data_points.iter())
+        .filter(|data_point| {
+          data_point
+            .attributes
+            .iter()
+            .any(|attribute| attribute.key.as_str() == "address" && attribute.value.as_str() == address)
+        })
+        .map(|data_point| data_point.value)
+        .max()
+        .unwrap_or_default()
+    };
+    // The request went to the server and the response came back to the client. The server records its batch once
+    // the client has answered the Receive call, which may be after the response was delivered.
+    let mut recorded = false;
+    for _ in 0..50 {
+      meter_provider.force_flush().unwrap();
+      let resource_metrics = exporter.get_finished_metrics().unwrap();
+      if bytes(&resource_metrics, "nexus_actor_remote_bytes_sent", "127.0.0.1:8116") > 0
+        && bytes(&resource_metrics, "nexus_actor_remote_bytes_sent", "127.0.0.1:8117") > 0
+      {
+        recorded = true;
+        break;
+      }
+      sleep(Duration::from_millis(100)).await;
+    }
+    assert!(recorded);
+    // The server had no endpoint to the client when the request arrived, so it could not vouch for its address.
+    let resource_metrics = exporter.get_finished_metrics().unwrap();
+    assert!(bytes(&resource_metrics, "nexus_actor_remote_bytes_received", "unknown") > 0);
+    assert!(bytes(&resource_metrics, "nexus_actor_remote_bytes_received", "127.0.0.1:8116") > 0);
+  }
+
+  async fn request_echo(system: &ActorSystem, pid: &ExtendedPid, message: &str, timeout: Duration) -> Option<String> {
+    system
+      .get_root_context()
+      .await
+      .request_future(
+        pid.clone(),
+        MessageHandle::new(EchoMessage::new(message.to_string())),
+        timeout,
+      )
+      .await
+      .result()
+      .await
+      .ok()
+      .and_then(|response| response.to_typed::<EchoMessage>())
+      .map(|response| response.message)
+  }
+
+  #[tokio::test]
+  async fn test_in_memory_transport_with_latency_and_partition() {
+    let network = InMemoryNetwork::new();
+    let reconnect_policy = ReconnectPolicy::new(Duration::from_millis(50), Duration::from_millis(200), 100);
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "node-a")),
+        ConfigOption::with_reconnect_policy(reconnect_policy.clone()),
+      ],
+    )
+    .await;
+    assert_eq!(server_system.get_address().await, "node-a");
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "node-b")),
+        ConfigOption::with_reconnect_policy(reconnect_policy),
+      ],
+    )
+    .await;
+
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "hello", Duration::from_secs(5)).await,
+      Some("Echo: hello".to_string())
+    );
+
+    network.set_latency("node-b", "node-a", Duration::from_millis(300));
+    let started = tokio::time::Instant::now();
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "slow", Duration::from_secs(5)).await,
+      Some("Echo: slow".to_string())
+    );
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    network.clear_latency("node-b", "node-a");
+
+    network.partition("node-a", "node-b");
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "lost", Duration::from_millis(500)).await,
+      None
+    );
+
+    network.heal("node-a", "node-b");
+    let mut response = None;
+    for _ in 0..20 {
+      response = request_echo(&client_system, &echo_pid, "healed", Duration::from_millis(500)).await;
+      if response.is_some() {
+        break;
+      }
+    }
+    assert_eq!(response, Some("Echo: healed".to_string()));
+  }
+
+  #[cfg(unix)]
+  #[tokio::test]
+  async fn test_uds_transport() {
+    let dir = env::temp_dir();
+    let server_path = dir.join(format!("nexus-remote-{}-a.sock", std::process::id()));
+    let client_path = dir.join(format!("nexus-remote-{}-b.sock", std::process::id()));
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_transport(UdsTransport::new(&server_path))],
+    )
+    .await;
+    assert_eq!(
+      server_system.get_address().await,
+      server_path.to_string_lossy().to_string()
+    );
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [ConfigOption::with_transport(UdsTransport::new(&client_path))],
+    )
+    .await;
+
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "hello", Duration::from_secs(5)).await,
+      Some("Echo: hello".to_string())
+    );
+  }
+
+  #[tokio::test]
+  async fn test_fault_injector_drops_delays_and_blocks_batches() {
+    let network = InMemoryNetwork::new();
+    let fault_injector = FaultInjector::new();
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "faulty-a")),
+        ConfigOption::with_fault_injector(fault_injector.clone()),
+      ],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo")
+      .await
+      .unwrap();
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "faulty-b")),
+        ConfigOption::with_fault_injector(fault_injector.clone()),
+      ],
+    )
+    .await;
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "hello", Duration::from_secs(5)).await,
+      Some("Echo: hello".to_string())
+    );
+
+    // Only the responses are lost.
+    fault_injector.block("faulty-a", "faulty-b").await;
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "blocked", Duration::from_millis(500)).await,
+      None
+    );
+    fault_injector.unblock("faulty-a", "faulty-b").await;
+
+    fault_injector.set_drop_ratio("faulty-b", "faulty-a", 1.0);
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "dropped", Duration::from_millis(500)).await,
+      None
+    );
+    fault_injector.set_drop_ratio("faulty-b", "faulty-a", 0.0);
+
+    fault_injector.set_delay("faulty-b", "faulty-a", Duration::from_millis(300));
+    let started = tokio::time::Instant::now();
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "delayed", Duration::from_secs(5)).await,
+      Some("Echo: delayed".to_string())
+    );
+    assert!(started.elapsed() >= Duration::from_millis(300));
+
+    fault_injector.clear().await;
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "healed", Duration::from_secs(5)).await,
+      Some("Echo: healed".to_string())
+    );
+  }
+
+  #[tokio::test]
+  async fn test_unacked_reliable_batch_is_retransmitted_after_the_ack_timeout() {
+    let network = InMemoryNetwork::new();
+    let fault_injector = FaultInjector::new();
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network,
+        "retransmit-a",
+      ))],
+    )
+    .await;
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    let recorder_props = Props::from_async_actor_receiver(move |ctx| {
+      let tx = tx.clone();
+      async move {
+        if let Some(msg) = ctx.get_message_handle().await.to_typed::<EchoMessage>() {
+          let _ = tx.send(msg.message).await;
+        }
+        Ok(())
+      }
+    })
+    .await;
+    let recorder_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(recorder_props, "recorder")
+      .await
+      .unwrap();
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "retransmit-b")),
+        ConfigOption::with_fault_injector(fault_injector.clone()),
+        ConfigOption::with_reliable_delivery_config(
+          ReliableDeliveryConfig::default()
+            .with_address("retransmit-a")
+            .with_ack_timeout(Duration::from_millis(200)),
+        ),
+      ],
+    )
+    .await;
+
+    fault_injector.set_drop_ratio("retransmit-b", "retransmit-a", 1.0);
+    client_system
+      .get_root_context()
+      .await
+      .send(
+        recorder_pid,
+        MessageHandle::new(EchoMessage::new("retransmitted".to_string())),
+      )
+      .await;
+    assert!(tokio::time::timeout(Duration::from_millis(100), rx.recv())
+      .await
+      .is_err());
+    // The connection stays up, so only the ack timeout brings the batch back.
+    fault_injector.set_drop_ratio("retransmit-b", "retransmit-a", 0.0);
+    assert_eq!(
+      tokio::time::timeout(Duration::from_secs(2), rx.recv()).await.unwrap(),
+      Some("retransmitted".to_string())
+    );
+    assert!(tokio::time::timeout(Duration::from_millis(500), rx.recv())
+      .await
+      .is_err());
+  }
+
+  #[tokio::test]
+  async fn test_blocked_address_is_refused_until_the_block_expires() {
+    let network = InMemoryNetwork::new();
+    let server_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &server_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network,
+        "blocking-a",
+      ))],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo")
+      .await
+      .unwrap();
+
+    let client_system = ActorSystem::new().await.unwrap();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    client_system
+      .get_event_stream()
+      .await
+      .subscribe(move |evt| {
+        let tx = tx.clone();
+        let evt = evt.as_any().downcast_ref::<BlockListEvent>().cloned();
+        async move {
+          if let Some(block_list_event) = evt {
+            let _ = tx.try_send(block_list_event);
+          }
+        }
+      })
+      .await;
+    let client_remote = start_remote(
+      &client_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network,
+        "blocking-b",
+      ))],
+    )
+    .await;
+
+    client_remote
+      .get_block_list()
+      .block_for("blocking-a".to_string(), Duration::from_millis(500))
+      .await;
+    assert!(matches!(
+      rx.recv().await,
+      Some(BlockListEvent::Blocked { member, expires_at: Some(_) }) if member == "blocking-a"
+    ));
+    let result = client_system
+      .get_root_context()
+      .await
+      .request_future(
+        echo_pid.clone(),
+        MessageHandle::new(EchoMessage::new("blocked".to_string())),
+        Duration::from_secs(5),
+      )
+      .await
+      .result()
+      .await;
+    assert_eq!(result.err(), Some(ActorFutureError::DeadLetterError));
+
+    assert_eq!(
+      tokio::time::timeout(Duration::from_secs(2), rx.recv()).await.unwrap(),
+      Some(BlockListEvent::Unblocked {
+        member: "blocking-a".to_string()
+      })
+    );
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "unblocked", Duration::from_secs(5)).await,
+      Some("Echo: unblocked".to_string())
+    );
+  }
+
+  #[tokio::test]
+  async fn test_blocked_peer_is_rejected_by_the_endpoint_reader() {
+    let network = InMemoryNetwork::new();
+    let server_system = ActorSystem::new().await.unwrap();
+    let server_remote = start_remote(
+      &server_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network,
+        "rejecting-a",
+      ))],
+    )
+    .await;
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let echo_pid = server_system
+      .get_root_context()
+      .await
+      .spawn_named(echo_props, "echo")
+      .await
+      .unwrap();
+    let client_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &client_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network,
+        "rejecting-b",
+      ))],
+    )
+    .await;
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "hello", Duration::from_secs(5)).await,
+      Some("Echo: hello".to_string())
+    );
+
+    // Batches on the established connection are refused as well.
+    server_remote.get_block_list().block("rejecting-b".to_string()).await;
+    assert_eq!(
+      request_echo(&client_system, &echo_pid, "blocked", Duration::from_millis(500)).await,
+      None
+    );
+  }
+
+  #[tokio::test]
+  async fn test_list_kinds_and_unregister() {
+    let network = InMemoryNetwork::new();
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let server_system = ActorSystem::new().await.unwrap();
+    let mut server_remote = start_remote(
+      &server_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "kinds-a")),
+        ConfigOption::with_kind("echo", echo_props.clone()),
+        ConfigOption::with_kind_metadata(
+          "echo",
+          KindMetadata::default().with_version("1.2.0").with_max_instances(5),
+        ),
+      ],
+    )
+    .await;
+    server_remote.register("scratch", echo_props.clone());
+    let other_system = ActorSystem::new().await.unwrap();
+    start_remote(
+      &other_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "kinds-b")),
+        ConfigOption::with_kind("other", echo_props),
+      ],
+    )
+    .await;
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = start_remote(
+      &client_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network, "kinds-c",
+      ))],
+    )
+    .await;
+    let timeout = Duration::from_secs(5);
+
+    assert_eq!(
+      client_remote.list_kinds("kinds-a").await.unwrap(),
+      vec![
+        KindInfo {
+          name: "echo".to_string(),
+          version: "1.2.0".to_string(),
+          max_instances: 5,
+        },
+        KindInfo {
+          name: "scratch".to_string(),
+          version: String::new(),
+          max_instances: 0,
+        },
+      ]
+    );
+
+    assert!(server_remote.unregister("scratch").is_some());
+    assert!(server_remote.unregister("scratch").is_none());
+    let kinds = client_remote.list_kinds("kinds-a").await.unwrap();
+    assert_eq!(
+      kinds
+        .iter()
+        .map(|kind_info| kind_info.name.as_str())
+        .collect::<Vec<_>>(),
+      ["echo"]
+    );
+    assert_eq!(
+      client_remote.spawn_remote("kinds-a", "scratch", "", timeout).await,
+      Err(RemoteSpawnError::SpawnFailed {
+        address: "kinds-a".to_string(),
+        kind: "scratch".to_string(),
+      })
+    );
+
+    // Only kinds-b registers `other`.
+    let placement = SpawnPlacement::round_robin(["kinds-a", "kinds-b"]).with_kind_discovery();
+    for _ in 0..2 {
+      let pid = placement.spawn(&client_remote, "other", "", timeout).await.unwrap();
+      assert_eq!(pid.address(), "kinds-b");
+    }
+    assert_eq!(
+      placement.spawn(&client_remote, "missing", "", timeout).await,
+      Err(RemoteSpawnError::NoAvailableAddress)
+    );
+  }
+
+  #[tokio::test]
+  async fn test_activation_limits() {
+    let network = InMemoryNetwork::new();
+    let echo_props = Props::from_async_actor_producer(|_| async { EchoActor }).await;
+    let server_system = ActorSystem::new().await.unwrap();
+    let server_remote = start_remote(
+      &server_system,
+      [
+        ConfigOption::with_transport(InMemoryTransport::new(&network, "quota-a")),
+        ConfigOption::with_kind("limited", echo_props.clone()),
+        ConfigOption::with_kind_metadata("limited", KindMetadata::default().with_max_instances(2)),
+        ConfigOption::with_kind("unlimited", echo_props),
+        ConfigOption::with_activation_rate_limit(ActivationRateLimit::new(5, Duration::from_secs(60))),
+      ],
+    )
+    .await;
+    let client_system = ActorSystem::new().await.unwrap();
+    let client_remote = start_remote(
+      &client_system,
+      [ConfigOption::with_transport(InMemoryTransport::new(
+        &network, "quota-b",
+      ))],
+    )
+    .await;
+    let timeout = Duration::from_secs(5);
+    let quota_exceeded = |kind: &str| -> Result<ExtendedPid, RemoteSpawnError> {
+      Err(RemoteSpawnError::QuotaExceeded {
+        address: "quota-a".to_string(),
+        kind: kind.to_string(),
+      })
+    };
+
+    let first = client_remote
+      .spawn_remote("quota-a", "limited", "", timeout)
+      .await
+      .unwrap();
+    client_remote
+      .spawn_remote("quota-a", "limited", "", timeout)
+      .await
+      .unwrap();
+    assert_eq!(
+      client_remote.spawn_remote("quota-a", "limited", "", timeout).await,
+      quota_exceeded("limited")
+    );
+    assert_eq!(server_remote.get_activation_count("limited"), 2);
+
+    // Stopping an instance frees its slot.
+    client_system
+      .get_root_context()
+      .await
+      .stop_future(&first)
+      .await
+      .result()
+      .await
+      .unwrap();
+    for _ in 0..50 {
+      if server_remote.get_activation_count("limited") == 1 {
+        break;
+      }
+      sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(server_remote.get_activation_count("limited"), 1);
+    client_remote
+      .spawn_remote("quota-a", "unlimited", "", timeout)
+      .await
+      .unwrap();
+
+    let diagnostics = client_remote
+      .get_process_diagnostics(&Pid::new("quota-a", "activator"))
+      .await
+      .unwrap();
+    assert!(
+      diagnostics.contains("activations: [limited = 1, unlimited = 1]"),
+      "{}",
+      diagnostics
+    );
+
+    // The fifth request uses up the burst of the client, so that the sixth is refused whatever its kind.
+    client_remote
+      .spawn_remote("quota-a", "limited", "", timeout)
+      .await
+      .unwrap();
+    assert_eq!(
+      client_remote.spawn_remote("quota-a", "unlimited", "", timeout).await,
+      quota_exceeded("unlimited")
+    );
+    assert_eq!(server_remote.get_activation_count("unlimited"), 1);
+  }
 }
@@ -0,0 +1,346 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use thiserror::Error;
+
+pub(crate) const SYSTEM_ID_METADATA_KEY: &str = "nexus-system-id";
+pub(crate) const ADDRESS_METADATA_KEY: &str = "nexus-address";
+pub(crate) const AUTH_TOKEN_METADATA_KEY: &str = "nexus-auth-token";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationRequest {
+  pub system_id: String,
+  pub address: String,
+  pub auth_token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AuthenticationError {
+  #[error("Missing credentials")]
+  MissingCredentials,
+  #[error("Invalid credentials")]
+  InvalidCredentials,
+  #[error("Expired credentials")]
+  ExpiredCredentials,
+  #[error("Replayed credentials")]
+  ReplayedCredentials,
+  #[error("{0}")]
+  Other(String),
+}
+
+#[async_trait]
+pub trait RemoteAuthenticator: Debug + Send + Sync + 'static {
+  // Creates the token sent by this node when it connects to a peer.
+  async fn create_token(&self, system_id: &str, address: &str) -> String;
+
+  // Verifies the token presented by a connecting peer.
+  async fn authenticate(&self, request: &AuthenticationRequest) -> Result<(), AuthenticationError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteAuthenticatorHandle(Arc<dyn RemoteAuthenticator>);
+
+impl RemoteAuthenticatorHandle {
+  pub fn new_arc(authenticator: Arc<dyn RemoteAuthenticator>) -> Self {
+    RemoteAuthenticatorHandle(authenticator)
+  }
+
+  pub fn new(authenticator: impl RemoteAuthenticator) -> Self {
+    RemoteAuthenticatorHandle(Arc::new(authenticator))
+  }
+}
+
+#[async_trait]
+impl RemoteAuthenticator for RemoteAuthenticatorHandle {
+  async fn create_token(&self, system_id: &str, address: &str) -> String {
+    self.0.create_token(system_id, address).await
+  }
+
+  async fn authenticate(&self, request: &AuthenticationRequest) -> Result<(), AuthenticationError> {
+    self.0.authenticate(request).await
+  }
+}
+
+// Sets the metadata the EndpointReader authenticates a request with. The address is sent even without an
+// authenticator, since the receiver labels its metrics with it.
+pub(crate) async fn attach_credentials<T>(
+  authenticator: Option<RemoteAuthenticatorHandle>,
+  system_id: String,
+  address: String,
+  request: &mut tonic::Request<T>,
+) {
+  let auth_token = match authenticator {
+    Some(authenticator) => authenticator.create_token(&system_id, &address).await,
+    None => String::new(),
+  };
+  for (key, value) in [
+    (SYSTEM_ID_METADATA_KEY, system_id),
+    (ADDRESS_METADATA_KEY, address),
+    (AUTH_TOKEN_METADATA_KEY, auth_token),
+  ] {
+    match value.parse() {
+      Ok(value) => {
+        request.metadata_mut().insert(key, value);
+      }
+      Err(e) => tracing::error!("Failed to set metadata {}: {}", key, e),
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct SharedSecretAuthenticator {
+  secret: String,
+}
+
+impl SharedSecretAuthenticator {
+  pub fn new(secret: &str) -> Self {
+    Self {
+      secret: secret.to_string(),
+    }
+  }
+}
+
+impl Debug for SharedSecretAuthenticator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SharedSecretAuthenticator").finish_non_exhaustive()
+  }
+}
+
+#[async_trait]
+impl RemoteAuthenticator for SharedSecretAuthenticator {
+  async fn create_token(&self, _: &str, _: &str) -> String {
+    self.secret.clone()
+  }
+
+  async fn authenticate(&self, request: &AuthenticationRequest) -> Result<(), AuthenticationError> {
+    if request.auth_token.is_empty() {
+      return Err(AuthenticationError::MissingCredentials);
+    }
+    if constant_time_eq(request.auth_token.as_bytes(), self.secret.as_bytes()) {
+      Ok(())
+    } else {
+      Err(AuthenticationError::InvalidCredentials)
+    }
+  }
+}
+
+// Token format: `<unix seconds>.<hex nonce>.<hex HMAC-SHA256 of "system_id\naddress\nunix seconds\nnonce">`.
+// Every token is accepted once; a token presented again within the clock skew window is a replay.
+#[derive(Clone)]
+pub struct HmacAuthenticator {
+  secret: Vec<u8>,
+  max_clock_skew: Duration,
+  // Timestamps of the nonces accepted within the clock skew window, keyed by nonce.
+  seen_nonces: Arc<DashMap<String, u64>>,
+  swept_at: Arc<AtomicU64>,
+}
+
+impl HmacAuthenticator {
+  pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+    Self {
+      secret: secret.into(),
+      max_clock_skew: Duration::from_secs(60),
+      seen_nonces: Arc::new(DashMap::new()),
+      swept_at: Arc::new(AtomicU64::new(0)),
+    }
+  }
+
+  pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+    self.max_clock_skew = max_clock_skew;
+    self
+  }
+
+  fn mac(&self, system_id: &str, address: &str, timestamp: u64, nonce: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}\n{}\n{}", system_id, address, timestamp, nonce).as_bytes());
+    mac
+  }
+
+  fn accept_nonce(&self, nonce: &str, timestamp: u64, now: u64) -> Result<(), AuthenticationError> {
+    // Nonces outside the window can be forgotten, their tokens are rejected as expired anyway.
+    let swept_at = self.swept_at.load(Ordering::SeqCst);
+    if now > swept_at
+      && self
+        .swept_at
+        .compare_exchange(swept_at, now, Ordering::SeqCst, Ordering::SeqCst)
+        .is_ok()
+    {
+      let max_clock_skew = self.max_clock_skew.as_secs();
+      self
+        .seen_nonces
+        .retain(|_, seen_at| now.abs_diff(*seen_at) <= max_clock_skew);
+    }
+    match self.seen_nonces.entry(nonce.to_string()) {
+      Entry::Occupied(_) => Err(AuthenticationError::ReplayedCredentials),
+      Entry::Vacant(entry) => {
+        entry.insert(timestamp);
+        Ok(())
+      }
+    }
+  }
+}
+
+impl Debug for HmacAuthenticator {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HmacAuthenticator")
+      .field("max_clock_skew", &self.max_clock_skew)
+      .finish_non_exhaustive()
+  }
+}
+
+#[async_trait]
+impl RemoteAuthenticator for HmacAuthenticator {
+  async fn create_token(&self, system_id: &str, address: &str) -> String {
+    let timestamp = unix_timestamp();
+    let nonce = format!("{:032x}", rand::random::<u128>());
+    let signature = self.mac(system_id, address, timestamp, &nonce).finalize().into_bytes();
+    format!("{}.{}.{}", timestamp, nonce, encode_hex(&signature))
+  }
+
+  async fn authenticate(&self, request: &AuthenticationRequest) -> Result<(), AuthenticationError> {
+    if request.auth_token.is_empty() {
+      return Err(AuthenticationError::MissingCredentials);
+    }
+    let mut parts = request.auth_token.split('.');
+    let (Some(timestamp), Some(nonce), Some(signature), None) =
+      (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+      return Err(AuthenticationError::InvalidCredentials);
+    };
+    let timestamp = timestamp
+      .parse::<u64>()
+      .map_err(|_| AuthenticationError::InvalidCredentials)?;
+    let signature = decode_hex(signature).ok_or(AuthenticationError::InvalidCredentials)?;
+    self
+      .mac(&request.system_id, &request.address, timestamp, nonce)
+      .verify_slice(&signature)
+      .map_err(|_| AuthenticationError::InvalidCredentials)?;
+    let now = unix_timestamp();
+    if now.abs_diff(timestamp) > self.max_clock_skew.as_secs() {
+      return Err(AuthenticationError::ExpiredCredentials);
+    }
+    self.accept_nonce(nonce, timestamp, now)
+  }
+}
+
+fn unix_timestamp() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_secs()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  a.len() == b.len() && a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+  if s.len() % 2 != 0 {
+    return None;
+  }
+  (0..s.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn request(token: String) -> AuthenticationRequest {
+    AuthenticationRequest {
+      system_id: "system-a".to_string(),
+      address: "127.0.0.1:8080".to_string(),
+      auth_token: token,
+    }
+  }
+
+  #[tokio::test]
+  async fn test_shared_secret_authenticator() {
+    let authenticator = SharedSecretAuthenticator::new("secret");
+    let token = authenticator.create_token("system-a", "127.0.0.1:8080").await;
+    assert_eq!(authenticator.authenticate(&request(token)).await, Ok(()));
+    assert_eq!(
+      authenticator.authenticate(&request("wrong".to_string())).await,
+      Err(AuthenticationError::InvalidCredentials)
+    );
+    assert_eq!(
+      authenticator.authenticate(&request(String::new())).await,
+      Err(AuthenticationError::MissingCredentials)
+    );
+  }
+
+  #[tokio::test]
+  async fn test_hmac_authenticator() {
+    let authenticator = HmacAuthenticator::new("secret");
+    let token = authenticator.create_token("system-a", "127.0.0.1:8080").await;
+    assert_eq!(authenticator.authenticate(&request(token.clone())).await, Ok(()));
+
+    let other = HmacAuthenticator::new("other-secret");
+    assert_eq!(
+      other.authenticate(&request(token.clone())).await,
+      Err(AuthenticationError::InvalidCredentials)
+    );
+
+    let mut tampered = request(token);
+    tampered.system_id = "system-b".to_string();
+    assert_eq!(
+      authenticator.authenticate(&tampered).await,
+      Err(AuthenticationError::InvalidCredentials)
+    );
+
+    let stale = {
+      let timestamp = unix_timestamp() - 3600;
+      let signature = authenticator
+        .mac("system-a", "127.0.0.1:8080", timestamp, "00")
+        .finalize()
+        .into_bytes();
+      format!("{}.00.{}", timestamp, encode_hex(&signature))
+    };
+    assert_eq!(
+      authenticator.authenticate(&request(stale)).await,
+      Err(AuthenticationError::ExpiredCredentials)
+    );
+  }
+
+  #[tokio::test]
+  async fn test_hmac_authenticator_rejects_replayed_tokens() {
+    let authenticator = HmacAuthenticator::new("secret");
+    let token = authenticator.create_token("system-a", "127.0.0.1:8080").await;
+    assert_eq!(authenticator.authenticate(&request(token.clone())).await, Ok(()));
+    assert_eq!(
+      authenticator.authenticate(&request(token)).await,
+      Err(AuthenticationError::ReplayedCredentials)
+    );
+
+    // Every token carries its own nonce.
+    let token = authenticator.create_token("system-a", "127.0.0.1:8080").await;
+    assert_eq!(authenticator.authenticate(&request(token)).await, Ok(()));
+
+    // Tokens without a nonce are not accepted.
+    let timestamp = unix_timestamp();
+    let signature = Hmac::<Sha256>::new_from_slice(b"secret")
+      .unwrap()
+      .chain_update(format!("system-a\n127.0.0.1:8080\n{}", timestamp).as_bytes())
+      .finalize()
+      .into_bytes();
+    assert_eq!(
+      authenticator
+        .authenticate(&request(format!("{}.{}", timestamp, encode_hex(&signature))))
+        .await,
+      Err(AuthenticationError::InvalidCredentials)
+    );
+  }
+}
@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use nexus_actor_core_rs::actor::MetricsProvider;
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider, MetricsError, ObservableGauge, UpDownCounter};
+use opentelemetry::KeyValue;
+
+pub const LIB_NAME: &str = "nexus-actor-remote";
+
+const ADDRESS_KEY: &str = "address";
+const DIRECTION_KEY: &str = "direction";
+const KIND_KEY: &str = "kind";
+const MESSAGE_TYPE_KEY: &str = "message_type";
+const REASON_KEY: &str = "reason";
+// Label of values a peer chose that this node cannot vouch for.
+pub(crate) const UNKNOWN_LABEL: &str = "unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+  Sent,
+  Received,
+}
+
+impl Direction {
+  fn as_str(&self) -> &'static str {
+    match self {
+      Direction::Sent => "sent",
+      Direction::Received => "received",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ActivationRejection {
+  MaxInstances,
+  RateLimit,
+}
+
+impl ActivationRejection {
+  fn as_str(&self) -> &'static str {
+    match self {
+      ActivationRejection::MaxInstances => "max_instances",
+      ActivationRejection::RateLimit => "rate_limit",
+    }
+  }
+}
+
+// OpenTelemetry instruments of the remote transport, labelled by peer address.
+#[derive(Debug, Clone)]
+pub struct RemoteMetrics {
+  bytes_sent: Counter<u64>,
+  bytes_received: Counter<u64>,
+  batch_envelopes: Histogram<u64>,
+  serialize_duration: Histogram<f64>,
+  deserialize_duration: Histogram<f64>,
+  connections: UpDownCounter<i64>,
+  reconnect_count: Counter<u64>,
+  activation_rejections: Counter<u64>,
+  _outbound_queue_depth: ObservableGauge<u64>,
+  _activations: ObservableGauge<u64>,
+}
+
+impl RemoteMetrics {
+  pub fn new(
+    meter_provider: Arc<MetricsProvider>,
+    outbound_queue_depths: Arc<DashMap<String, Arc<AtomicUsize>>>,
+    activation_counts: Arc<DashMap<String, Arc<AtomicUsize>>>,
+  ) -> Result<Self, MetricsError> {
+    let meter = meter_provider.meter(LIB_NAME);
+    Ok(RemoteMetrics {
+      bytes_sent: meter
+        .u64_counter("nexus_actor_remote_bytes_sent")
+        .with_description("Bytes of message batches sent to a peer")
+        .with_unit("By")
+        .try_init()?,
+      bytes_received: meter
+        .u64_counter("nexus_actor_remote_bytes_received")
+        .with_description("Bytes of message batches received from a peer")
+        .with_unit("By")
+        .try_init()?,
+      batch_envelopes: meter
+        .u64_histogram("nexus_actor_remote_batch_envelopes")
+        .with_description("Envelopes per message batch")
+        .with_unit("1")
+        .try_init()?,
+      serialize_duration: meter
+        .f64_histogram("nexus_actor_remote_serialize_duration_seconds")
+        .with_description("Message serialization duration in seconds")
+        .with_unit("s")
+        .try_init()?,
+      deserialize_duration: meter
+        .f64_histogram("nexus_actor_remote_deserialize_duration_seconds")
+        .with_description("Message deserialization duration in seconds")
+        .with_unit("s")
+        .try_init()?,
+      connections: meter
+        .i64_up_down_counter("nexus_actor_remote_connections")
+        .with_description("Open EndpointWriter connections")
+        .with_unit("1")
+        .try_init()?,
+      reconnect_count: meter
+        .u64_counter("nexus_actor_remote_reconnect_count")
+        .with_description("Number of attempts to re-establish a lost connection")
+        .with_unit("1")
+        .try_init()?,
+      activation_rejections: meter
+        .u64_counter("nexus_actor_remote_activation_rejections")
+        .with_description("ActorPidRequests the activator rejected over a quota")
+        .with_unit("1")
+        .try_init()?,
+      _outbound_queue_depth: meter
+        .u64_observable_gauge("nexus_actor_remote_outbound_queue_depth")
+        .with_description("Messages waiting in the outbound queue of an endpoint")
+        .with_unit("1")
+        .with_callback(move |observer| {
+          for entry in outbound_queue_depths.iter() {
+            observer.observe(
+              entry.value().load(Ordering::Relaxed) as u64,
+              &[KeyValue::new(ADDRESS_KEY, entry.key().clone())],
+            );
+          }
+        })
+        .try_init()?,
+      _activations: meter
+        .u64_observable_gauge("nexus_actor_remote_activations")
+        .with_description("Live actors the activator spawned for a kind")
+        .with_unit("1")
+        .with_callback(move |observer| {
+          for entry in activation_counts.iter() {
+            observer.observe(
+              entry.value().load(Ordering::Relaxed) as u64,
+              &[KeyValue::new(KIND_KEY, entry.key().clone())],
+            );
+          }
+        })
+        .try_init()?,
+    })
+  }
+
+  pub(crate) fn record_batch(&self, address: &str, direction: Direction, bytes: usize, envelopes: usize) {
+    let attributes = [KeyValue::new(ADDRESS_KEY, address.to_string())];
+    match direction {
+      Direction::Sent => self.bytes_sent.add(bytes as u64, &attributes),
+      Direction::Received => self.bytes_received.add(bytes as u64, &attributes),
+    }
+    self.batch_envelopes.record(
+      envelopes as u64,
+      &[
+        KeyValue::new(ADDRESS_KEY, address.to_string()),
+        KeyValue::new(DIRECTION_KEY, direction.as_str()),
+      ],
+    );
+  }
+
+  pub(crate) fn record_serialize_duration(&self, address: &str, message_type: &str, duration: Duration) {
+    self
+      .serialize_duration
+      .record(duration.as_secs_f64(), &Self::message_attributes(address, message_type));
+  }
+
+  pub(crate) fn record_deserialize_duration(&self, address: &str, message_type: &str, duration: Duration) {
+    self
+      .deserialize_duration
+      .record(duration.as_secs_f64(), &Self::message_attributes(address, message_type));
+  }
+
+  pub(crate) fn increment_connections(&self, address: &str) {
+    self
+      .connections
+      .add(1, &[KeyValue::new(ADDRESS_KEY, address.to_string())]);
+  }
+
+  pub(crate) fn decrement_connections(&self, address: &str) {
+    self
+      .connections
+      .add(-1, &[KeyValue::new(ADDRESS_KEY, address.to_string())]);
+  }
+
+  pub(crate) fn increment_reconnect_count(&self, address: &str) {
+    self
+      .reconnect_count
+      .add(1, &[KeyValue::new(ADDRESS_KEY, address.to_string())]);
+  }
+
+  pub(crate) fn increment_activation_rejections(&self, kind: &str, rejection: ActivationRejection) {
+    self.activation_rejections.add(
+      1,
+      &[
+        KeyValue::new(KIND_KEY, kind.to_string()),
+        KeyValue::new(REASON_KEY, rejection.as_str()),
+      ],
+    );
+  }
+
+  fn message_attributes(address: &str, message_type: &str) -> [KeyValue; 2] {
+    [
+      KeyValue::new(ADDRESS_KEY, address.to_string()),
+      KeyValue::new(MESSAGE_TYPE_KEY, message_type.to_string()),
+    ]
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+  use opentelemetry_sdk::runtime;
+  use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+
+  // force_flush blocks until the periodic reader task exports, which needs a second worker.
+  #[tokio::test(flavor = "multi_thread")]
+  async fn test_remote_metrics() {
+    let exporter = InMemoryMetricsExporter::default();
+    let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+    let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+    let outbound_queue_depths = Arc::new(DashMap::new());
+    outbound_queue_depths.insert("127.0.0.1:8090".to_string(), Arc::new(AtomicUsize::new(3)));
+    let activation_counts = Arc::new(DashMap::new());
+    activation_counts.insert("echo".to_string(), Arc::new(AtomicUsize::new(2)));
+    let metrics = RemoteMetrics::new(
+      Arc::new(MetricsProvider::Sdk(meter_provider.clone())),
+      outbound_queue_depths,
+      activation_counts,
+    )
+    .unwrap();
+
+    metrics.record_batch("127.0.0.1:8090", Direction::Sent, 128, 2);
+    metrics.record_batch("127.0.0.1:8090", Direction::Received, 64, 1);
+    metrics.record_serialize_duration("127.0.0.1:8090", "Echo", Duration::from_micros(10));
+    metrics.record_deserialize_duration("127.0.0.1:8090", "Echo", Duration::from_micros(20));
+    metrics.increment_connections("127.0.0.1:8090");
+    metrics.increment_reconnect_count("127.0.0.1:8090");
+    metrics.increment_activation_rejections("echo", ActivationRejection::RateLimit);
+    meter_provider.force_flush().unwrap();
+
+    let mut names = exporter
+      .get_finished_metrics()
+      .unwrap()
+      .iter()
+      .flat_map(|resource_metrics| resource_metrics.scope_metrics.iter())
+      .flat_map(|scope_metrics| scope_metrics.metrics.iter())
+      .map(|metric| metric.name.to_string())
+      .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    assert_eq!(
+      names,
+      vec![
+        "nexus_actor_remote_activation_rejections",
+        "nexus_actor_remote_activations",
+        "nexus_actor_remote_batch_envelopes",
+        "nexus_actor_remote_bytes_received",
+        "nexus_actor_remote_bytes_sent",
+        "nexus_actor_remote_connections",
+        "nexus_actor_remote_deserialize_duration_seconds",
+        "nexus_actor_remote_outbound_queue_depth",
+        "nexus_actor_remote_reconnect_count",
+        "nexus_actor_remote_serialize_duration_seconds",
+      ]
+    );
+  }
+}
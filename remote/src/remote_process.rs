@@ -7,7 +7,7 @@ use nexus_actor_core_rs::actor::message::{
   unwrap_envelope, MessageHandle, ReadonlyMessageHeadersHandle, SystemMessage,
 };
 use nexus_actor_core_rs::actor::process::Process;
-use nexus_actor_core_rs::generated::actor::{Pid, Unwatch, Watch};
+use nexus_actor_core_rs::generated::actor::{Pid, Stop, Unwatch, Watch};
 use std::any::Any;
 
 #[derive(Debug, Clone)]
@@ -73,6 +73,11 @@ impl Process for RemoteProcess {
         self.remote.get_endpoint_manager().await.remote_unwatch(ruw).await;
       }
       (_, _) => {
+        // SystemMessage has no wire form; the reader turns Stop back into SystemMessage::Stop.
+        let message_handle = match message_handle.to_typed::<SystemMessage>() {
+          Some(SystemMessage::Stop) => MessageHandle::new(Stop {}),
+          _ => message_handle,
+        };
         self
           .remote
           .send_message(pid.inner_pid.clone(), None, message_handle, None, SerializerId::None)
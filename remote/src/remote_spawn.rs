@@ -0,0 +1,268 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use nexus_actor_core_rs::actor::actor::ExtendedPid;
+use nexus_actor_core_rs::actor::dispatch::future::ActorFutureError;
+use nexus_actor_core_rs::actor::message::MessageHandle;
+use thiserror::Error;
+
+use crate::generated::remote::{ActorPidResponse, ListProcessesMatchType};
+use crate::remote::Remote;
+use crate::response_status_code::ResponseStatusCode;
+use crate::serializer::SerializerError;
+
+// Activators spawn children named `remote-<name>`.
+const REMOTE_SPAWNED_PATTERN: &str = "^activator/remote-";
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RemoteSpawnError {
+  #[error("Remote is unavailable: {0}")]
+  Unavailable(String),
+  #[error("Timed out spawning on {0}")]
+  Timeout(String),
+  #[error("Process name already exists: {0}")]
+  NameAlreadyExists(ExtendedPid),
+  #[error("Failed to spawn kind {kind} on {address}")]
+  SpawnFailed { address: String, kind: String },
+  #[error("Spawn request was dead lettered: {0}")]
+  DeadLetter(String),
+  #[error("Unknown response status code: {0}")]
+  UnknownStatusCode(i32),
+  #[error("Unexpected spawn response: {0}")]
+  UnexpectedResponse(String),
+  #[error("Failed to serialize init message: {0}")]
+  InvalidInit(SerializerError),
+  #[error("Activation quota of kind {kind} exceeded on {address}")]
+  QuotaExceeded { address: String, kind: String },
+  #[error("No address available for placement")]
+  NoAvailableAddress,
+}
+
+impl RemoteSpawnError {
+  pub(crate) fn from_future_error(address: &str, error: ActorFutureError) -> Self {
+    match error {
+      ActorFutureError::TimeoutError => RemoteSpawnError::Timeout(address.to_string()),
+      ActorFutureError::DeadLetterError => RemoteSpawnError::DeadLetter(address.to_string()),
+    }
+  }
+
+  pub(crate) fn check_response(
+    address: &str,
+    kind: &str,
+    response: &ActorPidResponse,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    let status_code = ResponseStatusCode::try_from(response.status_code)
+      .map_err(|_| RemoteSpawnError::UnknownStatusCode(response.status_code))?;
+    let pid = response.pid.clone().map(ExtendedPid::new);
+    match (status_code, pid) {
+      (ResponseStatusCode::Ok, Some(pid)) => Ok(pid),
+      (ResponseStatusCode::Ok, None) => Err(RemoteSpawnError::UnexpectedResponse(
+        "ActorPidResponse without pid".to_string(),
+      )),
+      (ResponseStatusCode::Unavailable, _) => Err(RemoteSpawnError::Unavailable(address.to_string())),
+      (ResponseStatusCode::Timeout, _) => Err(RemoteSpawnError::Timeout(address.to_string())),
+      (ResponseStatusCode::ProcessNameAlreadyExists, Some(pid)) => Err(RemoteSpawnError::NameAlreadyExists(pid)),
+      (ResponseStatusCode::ProcessNameAlreadyExists, None) => Err(RemoteSpawnError::UnexpectedResponse(
+        "ProcessNameAlreadyExists without pid".to_string(),
+      )),
+      (ResponseStatusCode::Error, _) => Err(RemoteSpawnError::SpawnFailed {
+        address: address.to_string(),
+        kind: kind.to_string(),
+      }),
+      (ResponseStatusCode::DeadLetter, _) => Err(RemoteSpawnError::DeadLetter(address.to_string())),
+      (ResponseStatusCode::QuotaExceeded, _) => Err(RemoteSpawnError::QuotaExceeded {
+        address: address.to_string(),
+        kind: kind.to_string(),
+      }),
+      (ResponseStatusCode::Max, _) => Err(RemoteSpawnError::UnknownStatusCode(response.status_code)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementStrategy {
+  RoundRobin,
+  // Picks the address running the fewest actors spawned by remote activation.
+  LeastLoaded,
+}
+
+// Chooses which of several nodes a kind is spawned on.
+#[derive(Debug, Clone)]
+pub struct SpawnPlacement {
+  addresses: Vec<String>,
+  strategy: PlacementStrategy,
+  next: Arc<AtomicUsize>,
+  kind_discovery: bool,
+}
+
+impl SpawnPlacement {
+  pub fn new(addresses: impl IntoIterator<Item = impl Into<String>>, strategy: PlacementStrategy) -> Self {
+    Self {
+      addresses: addresses.into_iter().map(Into::into).collect(),
+      strategy,
+      next: Arc::new(AtomicUsize::new(0)),
+      kind_discovery: false,
+    }
+  }
+
+  pub fn round_robin(addresses: impl IntoIterator<Item = impl Into<String>>) -> Self {
+    Self::new(addresses, PlacementStrategy::RoundRobin)
+  }
+
+  pub fn least_loaded(addresses: impl IntoIterator<Item = impl Into<String>>) -> Self {
+    Self::new(addresses, PlacementStrategy::LeastLoaded)
+  }
+
+  // Places a kind only on the addresses that report it through `Remote::list_kinds`.
+  pub fn with_kind_discovery(mut self) -> Self {
+    self.kind_discovery = true;
+    self
+  }
+
+  pub fn get_addresses(&self) -> &[String] {
+    &self.addresses
+  }
+
+  pub async fn select(&self, remote: &Remote) -> Result<String, RemoteSpawnError> {
+    self.select_from(remote, &self.addresses).await
+  }
+
+  pub async fn select_for_kind(&self, remote: &Remote, kind: &str) -> Result<String, RemoteSpawnError> {
+    if !self.kind_discovery {
+      return self.select(remote).await;
+    }
+    let mut addresses = vec![];
+    for address in &self.addresses {
+      match remote.list_kinds(address).await {
+        Ok(kinds) if kinds.iter().any(|kind_info| kind_info.name == kind) => addresses.push(address.clone()),
+        Ok(_) => {}
+        Err(err) => tracing::warn!("Skipping address for placement: address = {}, error = {}", address, err),
+      }
+    }
+    self.select_from(remote, &addresses).await
+  }
+
+  async fn select_from(&self, remote: &Remote, addresses: &[String]) -> Result<String, RemoteSpawnError> {
+    if addresses.is_empty() {
+      return Err(RemoteSpawnError::NoAvailableAddress);
+    }
+    match self.strategy {
+      PlacementStrategy::RoundRobin => {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % addresses.len();
+        Ok(addresses[index].clone())
+      }
+      PlacementStrategy::LeastLoaded => {
+        let mut selected: Option<(usize, &String)> = None;
+        for address in addresses {
+          match remote
+            .list_processes(address, REMOTE_SPAWNED_PATTERN, ListProcessesMatchType::MatchRegex)
+            .await
+          {
+            Ok(pids) => {
+              if selected.map_or(true, |(load, _)| pids.len() < load) {
+                selected = Some((pids.len(), address));
+              }
+            }
+            Err(err) => tracing::warn!("Skipping address for placement: address = {}, error = {}", address, err),
+          }
+        }
+        selected
+          .map(|(_, address)| address.clone())
+          .ok_or(RemoteSpawnError::NoAvailableAddress)
+      }
+    }
+  }
+
+  pub async fn spawn(
+    &self,
+    remote: &Remote,
+    kind: &str,
+    name: &str,
+    timeout: Duration,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    let address = self.select_for_kind(remote, kind).await?;
+    remote.spawn_remote(&address, kind, name, timeout).await
+  }
+
+  pub async fn spawn_with_init(
+    &self,
+    remote: &Remote,
+    kind: &str,
+    name: &str,
+    init: MessageHandle,
+    timeout: Duration,
+  ) -> Result<ExtendedPid, RemoteSpawnError> {
+    let address = self.select_for_kind(remote, kind).await?;
+    remote.spawn_remote_with_init(&address, kind, name, init, timeout).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::RemoteSpawnError;
+  use crate::generated::remote::ActorPidResponse;
+  use crate::response_status_code::ResponseStatusCode;
+  use nexus_actor_core_rs::actor::actor::ExtendedPid;
+  use nexus_actor_core_rs::generated::actor::Pid;
+
+  fn response(status_code: ResponseStatusCode, pid: Option<Pid>) -> ActorPidResponse {
+    ActorPidResponse {
+      pid,
+      status_code: status_code as i32,
+    }
+  }
+
+  #[test]
+  fn test_check_response_maps_status_codes() {
+    let pid = Pid::new("127.0.0.1:8090", "remote-a");
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::Ok, Some(pid.clone()))),
+      Ok(ExtendedPid::new(pid.clone()))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response(
+        "n1",
+        "k",
+        &response(ResponseStatusCode::ProcessNameAlreadyExists, Some(pid.clone()))
+      ),
+      Err(RemoteSpawnError::NameAlreadyExists(ExtendedPid::new(pid)))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::Error, None)),
+      Err(RemoteSpawnError::SpawnFailed {
+        address: "n1".to_string(),
+        kind: "k".to_string(),
+      })
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::Unavailable, None)),
+      Err(RemoteSpawnError::Unavailable("n1".to_string()))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::Timeout, None)),
+      Err(RemoteSpawnError::Timeout("n1".to_string()))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::DeadLetter, None)),
+      Err(RemoteSpawnError::DeadLetter("n1".to_string()))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response("n1", "k", &response(ResponseStatusCode::Ok, None)),
+      Err(RemoteSpawnError::UnexpectedResponse(
+        "ActorPidResponse without pid".to_string()
+      ))
+    );
+    assert_eq!(
+      RemoteSpawnError::check_response(
+        "n1",
+        "k",
+        &ActorPidResponse {
+          pid: None,
+          status_code: 42
+        }
+      ),
+      Err(RemoteSpawnError::UnknownStatusCode(42))
+    );
+  }
+}
@@ -10,4 +10,6 @@ pub enum ResponseStatusCode {
   Error,
   DeadLetter,
   Max,
+  // Codes are sent to peers, so new ones are appended rather than inserted before Max.
+  QuotaExceeded,
 }
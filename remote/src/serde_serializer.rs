@@ -0,0 +1,211 @@
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use nexus_actor_core_rs::actor::message::Message;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::serializer::{Serializer, SerializerAny, SerializerError};
+
+// A binary encoding for serde types.
+pub trait SerdeFormat: Send + Sync + 'static {
+  fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String>;
+  fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String>;
+}
+
+#[cfg(feature = "msgpack")]
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl SerdeFormat for MessagePack {
+  fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    // Named fields keep the payload readable by peers with reordered struct fields.
+    rmp_serde::to_vec_named(value).map_err(|e| e.to_string())
+  }
+
+  fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    rmp_serde::from_slice(bytes).map_err(|e| e.to_string())
+  }
+}
+
+#[cfg(feature = "cbor")]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl SerdeFormat for Cbor {
+  fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+  }
+
+  fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    ciborium::from_reader(bytes).map_err(|e| e.to_string())
+  }
+}
+
+#[cfg(feature = "bincode")]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl SerdeFormat for Bincode {
+  fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    bincode::serialize(value).map_err(|e| e.to_string())
+  }
+
+  fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    use bincode::Options;
+    // The same encoding as bincode::deserialize, but a length prefix can't claim more than the payload holds, so a
+    // forged one fails instead of allocating.
+    bincode::DefaultOptions::new()
+      .with_fixint_encoding()
+      .allow_trailing_bytes()
+      .with_limit(bytes.len() as u64)
+      .deserialize(bytes)
+      .map_err(|e| e.to_string())
+  }
+}
+
+pub struct SerdeSerializer<T, F> {
+  _phantom: PhantomData<fn() -> (T, F)>,
+}
+
+impl<T, F> Default for SerdeSerializer<T, F> {
+  fn default() -> Self {
+    Self { _phantom: PhantomData }
+  }
+}
+
+#[cfg(feature = "msgpack")]
+pub type MessagePackSerializer<T> = SerdeSerializer<T, MessagePack>;
+#[cfg(feature = "cbor")]
+pub type CborSerializer<T> = SerdeSerializer<T, Cbor>;
+#[cfg(feature = "bincode")]
+pub type BincodeSerializer<T> = SerdeSerializer<T, Bincode>;
+
+impl<T: Serialize + DeserializeOwned, F: SerdeFormat> Serializer<T> for SerdeSerializer<T, F> {
+  fn serialize(&self, msg: &T) -> Result<Vec<u8>, SerializerError> {
+    F::encode(msg).map_err(SerializerError::SerializationError)
+  }
+
+  fn deserialize(&self, bytes: &[u8]) -> Result<T, SerializerError> {
+    F::decode(bytes).map_err(SerializerError::DeserializationError)
+  }
+
+  fn get_type_name(&self) -> String {
+    std::any::type_name::<T>().to_string()
+  }
+}
+
+impl<T: Message + Serialize + DeserializeOwned + Send + Sync + 'static, F: SerdeFormat> SerializerAny
+  for SerdeSerializer<T, F>
+{
+  fn serialize_any(&self, msg: &dyn Any) -> Result<Vec<u8>, SerializerError> {
+    msg
+      .downcast_ref::<T>()
+      .ok_or_else(|| SerializerError::SerializationError(format!("expected {}", std::any::type_name::<T>())))
+      .and_then(|m| self.serialize(m))
+  }
+
+  fn deserialize_any(&self, bytes: &[u8]) -> Result<Arc<dyn Any + Send + Sync>, SerializerError> {
+    self
+      .deserialize(bytes)
+      .map(|m| Arc::new(m) as Arc<dyn Any + Send + Sync>)
+  }
+
+  fn deserialize_message(&self, bytes: &[u8]) -> Result<Arc<dyn Message>, SerializerError> {
+    self.deserialize(bytes).map(|m| Arc::new(m) as Arc<dyn Message>)
+  }
+
+  fn type_name(&self) -> String {
+    std::any::type_name::<T>().to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use nexus_actor_message_derive_rs::Message;
+  use serde::Deserialize;
+  use std::collections::BTreeMap;
+
+  #[derive(Debug, Clone, PartialEq, Message, Serialize, Deserialize)]
+  struct Order {
+    id: u64,
+    customer: String,
+    lines: Vec<(String, u32)>,
+    attributes: BTreeMap<String, String>,
+    note: Option<String>,
+  }
+
+  fn order() -> Order {
+    Order {
+      id: 42,
+      customer: "nexus".to_string(),
+      lines: vec![("apple".to_string(), 3), ("pear".to_string(), 1)],
+      attributes: BTreeMap::from([("priority".to_string(), "high".to_string())]),
+      note: None,
+    }
+  }
+
+  fn assert_round_trip<F: SerdeFormat>() {
+    let serializer = SerdeSerializer::<Order, F>::default();
+    let bytes = serializer.serialize(&order()).unwrap();
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), order());
+    let message = serializer.deserialize_message(&bytes).unwrap();
+    assert!(message.eq_message(&order()));
+    assert!(matches!(
+      serializer.deserialize(&bytes[..bytes.len() / 2]),
+      Err(SerializerError::DeserializationError(_))
+    ));
+  }
+
+  #[cfg(feature = "msgpack")]
+  #[test]
+  fn test_message_pack_round_trip() {
+    assert_round_trip::<MessagePack>();
+  }
+
+  #[cfg(feature = "cbor")]
+  #[test]
+  fn test_cbor_round_trip() {
+    assert_round_trip::<Cbor>();
+  }
+
+  #[cfg(feature = "bincode")]
+  #[test]
+  fn test_bincode_round_trip() {
+    assert_round_trip::<Bincode>();
+  }
+
+  #[cfg(feature = "bincode")]
+  #[test]
+  fn test_bincode_rejects_oversized_length_prefix() {
+    // A Vec<u8> claiming u64::MAX / 2 elements, followed by a few bytes.
+    let mut bytes = (u64::MAX / 2).to_le_bytes().to_vec();
+    bytes.extend_from_slice(&[1, 2, 3]);
+    assert!(Bincode::decode::<Vec<u8>>(&bytes).is_err());
+    assert!(Bincode::decode::<String>(&bytes).is_err());
+  }
+
+  #[cfg(all(feature = "msgpack", feature = "cbor"))]
+  #[test]
+  fn test_select_serializer_per_message_type() {
+    use crate::serializer::SerializerId;
+    use crate::serializer_registry::SerializerRegistry;
+
+    let registry = SerializerRegistry::new();
+    registry.register_cbor::<Order>().unwrap();
+    registry.register_message_pack::<Order>().unwrap();
+    let type_name = std::any::type_name::<Order>();
+    assert_eq!(registry.get_default_serializer_id(type_name), Some(SerializerId::Cbor));
+
+    registry.register_type::<Order>(SerializerId::MessagePack).unwrap();
+    let (serializer_id, bytes) = registry
+      .serialize_any(&order(), &SerializerId::None, type_name)
+      .unwrap();
+    assert_eq!(serializer_id, SerializerId::MessagePack);
+    assert_eq!(registry.deserialize::<Order>(&bytes, &serializer_id).unwrap(), order());
+  }
+}
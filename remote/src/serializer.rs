@@ -1,23 +1,27 @@
-use dashmap::DashMap;
 use nexus_actor_core_rs::actor::message::Message;
-use once_cell::sync::Lazy;
 use prost::Message as ProstMessage;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::fmt::{Display, Formatter};
-use std::hash::{DefaultHasher, Hash, Hasher};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum SerializerError {
-  #[error("Serialization error")]
+  #[error("Serialization error: {0}")]
   SerializationError(String),
-  #[error("Deserialization error")]
+  #[error("Deserialization error: {0}")]
   DeserializationError(String),
-  #[error("Unknown type")]
-  UnknownType,
+  #[error("No serializer registered for type {0}")]
+  UnknownType(String),
+  #[error("Serializer {serializer_id} is not registered for type {type_name}")]
+  SerializerNotFound {
+    serializer_id: SerializerId,
+    type_name: String,
+  },
+  #[error("Invalid serializer id: {0}")]
+  InvalidSerializerId(u32),
 }
 
 pub trait Serializer<T>: Send + Sync {
@@ -33,7 +37,7 @@ pub trait SerializerAny: Send + Sync {
   fn type_name(&self) -> String;
 }
 
-struct ProtoSerializer<T: ProstMessage> {
+pub struct ProtoSerializer<T: ProstMessage> {
   _phantom: PhantomData<T>,
 }
 
@@ -47,7 +51,7 @@ impl<T: Message + ProstMessage + Default + 'static> SerializerAny for ProtoSeria
   fn serialize_any(&self, msg: &dyn Any) -> Result<Vec<u8>, SerializerError> {
     msg
       .downcast_ref::<T>()
-      .ok_or(SerializerError::SerializationError("Invalid type".to_string()))
+      .ok_or_else(|| SerializerError::SerializationError(format!("expected {}", std::any::type_name::<T>())))
       .and_then(|m| self.serialize(m))
   }
 
@@ -55,14 +59,10 @@ impl<T: Message + ProstMessage + Default + 'static> SerializerAny for ProtoSeria
     self
       .deserialize(bytes)
       .map(|m| Arc::new(m) as Arc<dyn Any + Send + Sync>)
-      .map_err(|e| SerializerError::DeserializationError(e.to_string()))
   }
 
   fn deserialize_message(&self, bytes: &[u8]) -> Result<Arc<dyn Message>, SerializerError> {
-    self
-      .deserialize(bytes)
-      .map(|m| Arc::new(m) as Arc<dyn Message>)
-      .map_err(|e| SerializerError::DeserializationError(e.to_string()))
+    self.deserialize(bytes).map(|m| Arc::new(m) as Arc<dyn Message>)
   }
 
   fn type_name(&self) -> String {
@@ -84,7 +84,7 @@ impl<T: Message + ProstMessage + Default> Serializer<T> for ProtoSerializer<T> {
   }
 }
 
-struct JsonSerializer<T> {
+pub struct JsonSerializer<T> {
   _phantom: PhantomData<T>,
 }
 
@@ -98,7 +98,7 @@ impl<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>
   fn serialize_any(&self, msg: &dyn Any) -> Result<Vec<u8>, SerializerError> {
     msg
       .downcast_ref::<T>()
-      .ok_or(SerializerError::SerializationError("Invalid type".to_string()))
+      .ok_or_else(|| SerializerError::SerializationError(format!("expected {}", std::any::type_name::<T>())))
       .and_then(|m| self.serialize(m))
   }
 
@@ -106,14 +106,10 @@ impl<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>
     self
       .deserialize(bytes)
       .map(|m| Arc::new(m) as Arc<dyn Any + Send + Sync>)
-      .map_err(|e| SerializerError::DeserializationError(e.to_string()))
   }
 
   fn deserialize_message(&self, bytes: &[u8]) -> Result<Arc<dyn Message>, SerializerError> {
-    self
-      .deserialize(bytes)
-      .map(|m| Arc::new(m) as Arc<dyn Message>)
-      .map_err(|e| SerializerError::DeserializationError(e.to_string()))
+    self.deserialize(bytes).map(|m| Arc::new(m) as Arc<dyn Message>)
   }
 
   fn type_name(&self) -> String {
@@ -141,6 +137,10 @@ pub enum SerializerId {
   None = 0,
   Proto = 1,
   Json = 2,
+  // Ids up to 100 are reserved for built-in serializers; the serde based ones need their cargo feature.
+  MessagePack = 3,
+  Cbor = 4,
+  Bincode = 5,
   Custom(u32),
 }
 
@@ -153,6 +153,18 @@ impl SerializerId {
     SerializerId::Json
   }
 
+  pub fn of_message_pack() -> Self {
+    SerializerId::MessagePack
+  }
+
+  pub fn of_cbor() -> Self {
+    SerializerId::Cbor
+  }
+
+  pub fn of_bincode() -> Self {
+    SerializerId::Bincode
+  }
+
   pub fn of_custom(value: u32) -> Self {
     if value <= 100 {
       panic!("Custom serializer id must be greater than 100");
@@ -171,6 +183,9 @@ impl Display for SerializerId {
       SerializerId::None => write!(f, "None:0"),
       SerializerId::Proto => write!(f, "Proto:1"),
       SerializerId::Json => write!(f, "Json:2"),
+      SerializerId::MessagePack => write!(f, "MessagePack:3"),
+      SerializerId::Cbor => write!(f, "Cbor:4"),
+      SerializerId::Bincode => write!(f, "Bincode:5"),
       SerializerId::Custom(value) => write!(f, "Custom:{}", value),
     }
   }
@@ -182,6 +197,9 @@ impl From<SerializerId> for u32 {
       SerializerId::None => 0,
       SerializerId::Proto => 1,
       SerializerId::Json => 2,
+      SerializerId::MessagePack => 3,
+      SerializerId::Cbor => 4,
+      SerializerId::Bincode => 5,
       SerializerId::Custom(value) => value,
     }
   }
@@ -195,6 +213,9 @@ impl TryFrom<u32> for SerializerId {
       0 => Ok(SerializerId::None),
       1 => Ok(SerializerId::Proto),
       2 => Ok(SerializerId::Json),
+      3 => Ok(SerializerId::MessagePack),
+      4 => Ok(SerializerId::Cbor),
+      5 => Ok(SerializerId::Bincode),
       _ => {
         if value > 100 {
           Ok(SerializerId::Custom(value))
@@ -206,193 +227,6 @@ impl TryFrom<u32> for SerializerId {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct SerializerKey {
-  serializer_id: SerializerId,
-  type_name: String,
-  is_any: bool,
-}
-
-impl SerializerKey {
-  pub fn new(serializer_id: SerializerId, type_name: String, is_any: bool) -> Self {
-    Self {
-      serializer_id,
-      type_name,
-      is_any,
-    }
-  }
-}
-
-impl Hash for SerializerKey {
-  fn hash<H: Hasher>(&self, state: &mut H) {
-    self.serializer_id.hash(state);
-    self.type_name.hash(state);
-  }
-}
-
-static SERIALIZERS: Lazy<DashMap<SerializerKey, Arc<dyn Any + Send + Sync>>> = Lazy::new(DashMap::new);
-
-pub fn register_serializer<T: 'static>(
-  serializer_id: SerializerId,
-  serializer: Arc<dyn Serializer<T>>,
-) -> Result<(), SerializerError> {
-  tracing::debug!(
-    "Registering serializer: serializer_id = {}, type_name = {}",
-    serializer_id.to_string(),
-    serializer.get_type_name()
-  );
-  let key = SerializerKey::new(serializer_id, serializer.get_type_name(), false);
-  let mut h = DefaultHasher::new();
-  key.hash(&mut h);
-  tracing::debug!("register_serializer: key hash = {:?}", h.finish());
-  SERIALIZERS.insert(key, Arc::new(serializer) as Arc<dyn Any + Send + Sync>);
-  Ok(())
-}
-
-pub fn register_serializer_any(
-  serializer_id: SerializerId,
-  serializer: Arc<dyn SerializerAny>,
-) -> Result<(), SerializerError> {
-  tracing::debug!(
-    "Registering serializer: serializer_id = {}, type_name = {}",
-    serializer_id.to_string(),
-    serializer.type_name()
-  );
-  let key = SerializerKey::new(serializer_id, serializer.type_name(), true);
-  let mut h = DefaultHasher::new();
-  key.hash(&mut h);
-  tracing::debug!("register_serializer_any: key hash = {:?}", h.finish());
-  SERIALIZERS.insert(key, Arc::new(serializer) as Arc<dyn Any + Send + Sync>);
-  Ok(())
-}
-
-pub trait AnyDowncastExt {
-  fn downcast_arc<T: Any + Send + Sync>(self) -> Result<Arc<T>, Arc<dyn Any + Send + Sync>>;
-}
-
-impl AnyDowncastExt for Arc<dyn Any + Send + Sync> {
-  fn downcast_arc<T: Any + Send + Sync>(self) -> Result<Arc<T>, Arc<dyn Any + Send + Sync>> {
-    Arc::downcast(self)
-  }
-}
-
-pub fn find_serializer<T: 'static>(serializer_id: &SerializerId, type_name: &str) -> Option<Arc<dyn Serializer<T>>> {
-  let key = SerializerKey::new(serializer_id.clone(), type_name.to_string(), false);
-  SERIALIZERS
-    .get(&key)
-    .and_then(|s| s.clone().downcast_arc::<Arc<dyn Serializer<T>>>().ok())
-    .map(|arc| arc.as_ref().clone())
-}
-
-pub fn find_serializer_any(serializer_id: &SerializerId, type_name: &str) -> Option<Arc<dyn SerializerAny>> {
-  let key = SerializerKey::new(serializer_id.clone(), type_name.to_string(), true);
-  let mut h = DefaultHasher::new();
-  key.hash(&mut h);
-  tracing::debug!("find_serializer_any: key hash = {:?}", h.finish());
-  let value_opt = SERIALIZERS.get(&key);
-  tracing::debug!("find_serializer_any: value_opt = {:?}", value_opt);
-  value_opt
-    .and_then(|s| s.clone().downcast_arc::<Arc<dyn SerializerAny>>().ok())
-    .map(|arc| arc.as_ref().clone())
-}
-
-pub fn find_serializer_any_all(type_name: &str) -> Option<Arc<dyn SerializerAny>> {
-  tracing::debug!(
-    "find_serializer_any_all: type_name: {}, SERIALIZERS = {:?}",
-    type_name,
-    SERIALIZERS
-  );
-  for s_id in [SerializerId::Proto, SerializerId::Json].iter() {
-    let key = SerializerKey::new(s_id.clone(), type_name.to_string(), true);
-    tracing::debug!("find_serializer_any_all: key = {:?}", key);
-    let result = SERIALIZERS
-      .get(&key)
-      .and_then(|s| s.clone().downcast_arc::<Arc<dyn SerializerAny>>().ok())
-      .map(|arc| arc.as_ref().clone());
-    if result.is_some() {
-      tracing::debug!("find_serializer_any_all: found");
-      return result;
-    }
-  }
-  tracing::debug!("find_serializer_any_all: not found");
-  None
-}
-
-pub fn serialize<T: 'static>(msg: &T, serializer_id: &SerializerId) -> Result<Vec<u8>, SerializerError> {
-  let serializer =
-    find_serializer::<T>(serializer_id, std::any::type_name::<T>()).ok_or(SerializerError::UnknownType)?;
-  serializer.serialize(msg)
-}
-
-pub fn serialize_any(msg: &dyn Any, serializer_id: &SerializerId, type_name: &str) -> Result<Vec<u8>, SerializerError> {
-  tracing::debug!(
-    "serialize_any: serializer_id = {}, type_name = {}",
-    serializer_id,
-    type_name
-  );
-  if *serializer_id == SerializerId::None {
-    let serializer = find_serializer_any_all(type_name).ok_or(SerializerError::UnknownType)?;
-    return serializer.serialize_any(msg);
-  }
-  let serializer_opt = find_serializer_any(serializer_id, type_name);
-  let serializer = serializer_opt.ok_or(SerializerError::UnknownType)?;
-  serializer.serialize_any(msg)
-}
-
-pub fn deserialize<T: 'static>(bytes: &[u8], serializer_id: &SerializerId) -> Result<T, SerializerError> {
-  let serializer =
-    find_serializer::<T>(serializer_id, std::any::type_name::<T>()).ok_or(SerializerError::UnknownType)?;
-  serializer.deserialize(bytes)
-}
-
-pub fn deserialize_any(
-  bytes: &[u8],
-  serializer_id: &SerializerId,
-  type_name: &str,
-) -> Result<Arc<dyn Any + Send + Sync>, SerializerError> {
-  if *serializer_id == SerializerId::None {
-    let serializer = find_serializer_any_all(type_name).ok_or(SerializerError::UnknownType)?;
-    return serializer.deserialize_any(bytes);
-  }
-  let serializer = find_serializer_any(serializer_id, type_name).ok_or(SerializerError::UnknownType)?;
-  serializer.deserialize_any(bytes)
-}
-
-pub fn deserialize_message(
-  bytes: &[u8],
-  serializer_id: &SerializerId,
-  type_name: &str,
-) -> Result<Arc<dyn Message>, SerializerError> {
-  if *serializer_id == SerializerId::None {
-    let serializer = find_serializer_any_all(type_name).ok_or(SerializerError::UnknownType)?;
-    return serializer.deserialize_message(bytes);
-  }
-  let serializer = find_serializer_any(serializer_id, type_name).ok_or(SerializerError::UnknownType)?;
-  serializer.deserialize_message(bytes)
-}
-
-pub fn initialize_serializers<T>() -> Result<(), SerializerError>
-where
-  T: Message + Default + ProstMessage + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static, {
-  initialize_proto_serializers::<T>()?;
-  initialize_json_serializers::<T>()?;
-  Ok(())
-}
-
-pub fn initialize_json_serializers<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>(
-) -> Result<(), SerializerError> {
-  register_serializer(SerializerId::Json, Arc::new(JsonSerializer::<T>::default()))?;
-  register_serializer_any(SerializerId::Json, Arc::new(JsonSerializer::<T>::default()))?;
-  Ok(())
-}
-
-pub fn initialize_proto_serializers<T: Message + Default + ProstMessage + Send + Sync + 'static>(
-) -> Result<(), SerializerError> {
-  register_serializer(SerializerId::Proto, Arc::new(ProtoSerializer::<T>::default()))?;
-  register_serializer_any(SerializerId::Proto, Arc::new(ProtoSerializer::<T>::default()))?;
-  Ok(())
-}
-
 pub trait RootSerializable: Message {
   fn serialize(&self) -> Result<Arc<dyn RootSerialized>, SerializerError>;
 }
@@ -406,8 +240,6 @@ mod tests {
   use super::*;
   use nexus_actor_core_rs::actor::message::Message;
   use nexus_actor_message_derive_rs::Message;
-  use std::env;
-  use tracing_subscriber::EnvFilter;
 
   #[derive(Clone, PartialEq, Message, ::prost::Message, Serialize, Deserialize)]
   pub struct TestMessage {
@@ -419,43 +251,43 @@ mod tests {
 
   #[test]
   fn test_proto_serialization() {
-    initialize_serializers::<TestMessage>().expect("Failed to register serializer");
+    let serializer = ProtoSerializer::<TestMessage>::default();
     let msg = TestMessage {
       a: 42,
       b: "world".to_string(),
     };
-    let bytes = serialize(&msg, &SerializerId::Proto).unwrap();
-    let deserialized = deserialize::<TestMessage>(&bytes, &SerializerId::Proto).unwrap();
-    assert_eq!(msg, deserialized);
+    let bytes = serializer.serialize(&msg).unwrap();
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), msg);
+    let deserialized = serializer.deserialize_message(&bytes).unwrap();
+    assert!(deserialized.eq_message(&msg));
   }
 
   #[test]
-  fn test_proto_serialization_any() {
-    let _ = env::set_var("RUST_LOG", "nexus_actor_core_rs=info");
-    let _ = tracing_subscriber::fmt()
-      .with_env_filter(EnvFilter::from_default_env())
-      .try_init();
-
-    initialize_proto_serializers::<TestMessage>().expect("Failed to register serializer");
+  fn test_json_serialization() {
+    let serializer = JsonSerializer::<TestMessage>::default();
     let msg = TestMessage {
       a: 42,
-      b: "world".to_string(),
+      b: "hello".to_string(),
     };
-    let bytes = serialize_any(&msg, &SerializerId::Proto, std::any::type_name::<TestMessage>()).unwrap();
-    // let deserialized = deserialize_any(&bytes, &SerializerId::Proto, std::any::type_name::<TestMessage>()).unwrap();
-    let _deserialized = deserialize::<TestMessage>(&bytes, &SerializerId::Proto).unwrap();
-    // assert_eq!(msg, deserialized);
+    let bytes = serializer.serialize(&msg).unwrap();
+    assert_eq!(serializer.deserialize(&bytes).unwrap(), msg);
+    assert!(matches!(
+      serializer.serialize_any(&"not a test message"),
+      Err(SerializerError::SerializationError(_))
+    ));
   }
 
   #[test]
-  fn test_json_serialization() {
-    initialize_serializers::<TestMessage>().expect("Failed to register serializer");
-    let msg = TestMessage {
-      a: 42,
-      b: "hello".to_string(),
-    };
-    let bytes = serialize(&msg, &SerializerId::Json).unwrap();
-    let deserialized = deserialize::<TestMessage>(&bytes, &SerializerId::Json).unwrap();
-    assert_eq!(msg, deserialized);
+  fn test_serializer_id_conversion() {
+    assert_eq!(SerializerId::try_from(1), Ok(SerializerId::Proto));
+    for serializer_id in [SerializerId::MessagePack, SerializerId::Cbor, SerializerId::Bincode] {
+      assert_eq!(
+        SerializerId::try_from(u32::from(serializer_id.clone())),
+        Ok(serializer_id)
+      );
+    }
+    assert_eq!(SerializerId::try_from(101), Ok(SerializerId::Custom(101)));
+    assert!(SerializerId::try_from(50).is_err());
+    assert_eq!(u32::from(SerializerId::of_custom(200)), 200);
   }
 }
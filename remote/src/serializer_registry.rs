@@ -0,0 +1,509 @@
+use std::any::Any;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use nexus_actor_core_rs::actor::message::Message;
+use prost::Message as ProstMessage;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bincode")]
+use crate::serde_serializer::BincodeSerializer;
+#[cfg(feature = "cbor")]
+use crate::serde_serializer::CborSerializer;
+#[cfg(feature = "msgpack")]
+use crate::serde_serializer::MessagePackSerializer;
+use crate::serializer::{JsonSerializer, ProtoSerializer, SerializerAny, SerializerError, SerializerId};
+use crate::type_manifest::{TypeManifest, Upcaster};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SerializerKey {
+  serializer_id: SerializerId,
+  type_name: String,
+}
+
+impl SerializerKey {
+  fn new(serializer_id: SerializerId, type_name: &str) -> Self {
+    Self {
+      serializer_id,
+      type_name: type_name.to_string(),
+    }
+  }
+}
+
+// Registration of a type's serializers, manifest and aliases, usually derived with
+// `#[derive(SerializerRegistration)]`.
+pub trait SerializerRegistration {
+  fn register_serializers(registry: &SerializerRegistry) -> Result<(), SerializerError>;
+}
+
+// Serializers of a single Remote, keyed by serializer id and type name.
+#[derive(Clone, Default)]
+pub struct SerializerRegistry {
+  serializers: Arc<DashMap<SerializerKey, Arc<dyn SerializerAny>>>,
+  default_serializer_ids: Arc<DashMap<String, SerializerId>>,
+  // Manifest each local type is sent under; types without one are sent under their type name.
+  manifests: Arc<DashMap<String, TypeManifest>>,
+  // Manifest names and former type names resolving to a local type name.
+  aliases: Arc<DashMap<String, String>>,
+  upcasters: Arc<DashMap<TypeManifest, Upcaster>>,
+}
+
+impl SerializerRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // The first serializer registered for a type becomes its default; use `register_type` to change it.
+  pub fn register_serializer(
+    &self,
+    serializer_id: SerializerId,
+    serializer: impl SerializerAny + 'static,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer_arc(serializer_id, Arc::new(serializer))
+  }
+
+  pub fn register_serializer_arc(
+    &self,
+    serializer_id: SerializerId,
+    serializer: Arc<dyn SerializerAny>,
+  ) -> Result<(), SerializerError> {
+    if serializer_id == SerializerId::None {
+      return Err(SerializerError::InvalidSerializerId(serializer_id.into()));
+    }
+    let type_name = serializer.type_name();
+    tracing::debug!(
+      "Registering serializer: serializer_id = {}, type_name = {}",
+      serializer_id,
+      type_name
+    );
+    self
+      .default_serializer_ids
+      .entry(type_name.clone())
+      .or_insert_with(|| serializer_id.clone());
+    self
+      .serializers
+      .insert(SerializerKey::new(serializer_id, &type_name), serializer);
+    Ok(())
+  }
+
+  // Makes `serializer_id` the serializer used when `T` is sent without an explicit one.
+  pub fn register_type<T: 'static>(&self, serializer_id: SerializerId) -> Result<(), SerializerError> {
+    let type_name = std::any::type_name::<T>();
+    self.find_serializer(&serializer_id, type_name)?;
+    self.default_serializer_ids.insert(type_name.to_string(), serializer_id);
+    Ok(())
+  }
+
+  pub fn register_proto<T: Message + Default + ProstMessage + Send + Sync + 'static>(
+    &self,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer(SerializerId::Proto, ProtoSerializer::<T>::default())
+  }
+
+  pub fn register_json<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>(
+    &self,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer(SerializerId::Json, JsonSerializer::<T>::default())
+  }
+
+  #[cfg(feature = "msgpack")]
+  pub fn register_message_pack<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>(
+    &self,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer(SerializerId::MessagePack, MessagePackSerializer::<T>::default())
+  }
+
+  #[cfg(feature = "cbor")]
+  pub fn register_cbor<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>(
+    &self,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer(SerializerId::Cbor, CborSerializer::<T>::default())
+  }
+
+  #[cfg(feature = "bincode")]
+  pub fn register_bincode<T: Message + Serialize + for<'de> Deserialize<'de> + Send + Sync + 'static>(
+    &self,
+  ) -> Result<(), SerializerError> {
+    self.register_serializer(SerializerId::Bincode, BincodeSerializer::<T>::default())
+  }
+
+  pub fn register<T: SerializerRegistration>(&self) -> Result<(), SerializerError> {
+    T::register_serializers(self)
+  }
+
+  // Sends `T` as `name` at `version`. Incoming payloads named `name` resolve to `T`.
+  pub fn register_manifest<T: 'static>(&self, name: &str, version: u32) {
+    let type_name = std::any::type_name::<T>();
+    self
+      .manifests
+      .insert(type_name.to_string(), TypeManifest::new(name, version));
+    self.aliases.insert(name.to_string(), type_name.to_string());
+  }
+
+  // Resolves payloads sent under `alias`, e.g. the type name before a rename, to `T`.
+  pub fn register_alias<T: 'static>(&self, alias: &str) {
+    self
+      .aliases
+      .insert(alias.to_string(), std::any::type_name::<T>().to_string());
+  }
+
+  pub fn register_upcaster(&self, manifest: TypeManifest, upcaster: Upcaster) {
+    self.upcasters.insert(manifest, upcaster);
+  }
+
+  // Payloads sent under `manifest` are deserialized as `Old` and converted with `f`.
+  // `Old` needs a serializer registered under the serializer id the peer used.
+  pub fn register_upcaster_fn<Old, New>(&self, manifest: TypeManifest, f: impl Fn(Old) -> New + Send + Sync + 'static)
+  where
+    Old: Clone + Send + Sync + 'static,
+    New: Message, {
+    self.register_upcaster(
+      manifest,
+      Upcaster::new(move |registry, bytes, serializer_id| {
+        let old = registry.deserialize::<Old>(bytes, serializer_id)?;
+        Ok(Arc::new(f(old)) as Arc<dyn Message>)
+      }),
+    );
+  }
+
+  pub fn get_manifest(&self, type_name: &str) -> TypeManifest {
+    self
+      .manifests
+      .get(type_name)
+      .map(|manifest| manifest.clone())
+      .unwrap_or_else(|| TypeManifest::unversioned(type_name))
+  }
+
+  pub fn resolve_type_name(&self, name: &str) -> String {
+    self
+      .aliases
+      .get(name)
+      .map(|type_name| type_name.clone())
+      .unwrap_or_else(|| name.to_string())
+  }
+
+  // Deserializes a payload received under `manifest`, running the upcaster registered for it if any.
+  pub fn deserialize_manifest(
+    &self,
+    bytes: &[u8],
+    serializer_id: &SerializerId,
+    manifest: &str,
+  ) -> Result<Arc<dyn Message>, SerializerError> {
+    let manifest = TypeManifest::parse(manifest);
+    let upcaster = self.upcasters.get(&manifest).map(|upcaster| upcaster.clone());
+    match upcaster {
+      Some(upcaster) => upcaster.run(self, bytes, serializer_id),
+      None => self.deserialize_message(bytes, serializer_id, &self.resolve_type_name(&manifest.name)),
+    }
+  }
+
+  pub fn get_default_serializer_id(&self, type_name: &str) -> Option<SerializerId> {
+    self
+      .default_serializer_ids
+      .get(type_name)
+      .map(|serializer_id| serializer_id.clone())
+  }
+
+  // `SerializerId::None` resolves to the default serializer of the type.
+  pub fn resolve_serializer_id(
+    &self,
+    serializer_id: &SerializerId,
+    type_name: &str,
+  ) -> Result<SerializerId, SerializerError> {
+    match serializer_id {
+      SerializerId::None => self
+        .get_default_serializer_id(type_name)
+        .ok_or_else(|| SerializerError::UnknownType(type_name.to_string())),
+      serializer_id => Ok(serializer_id.clone()),
+    }
+  }
+
+  pub fn find_serializer(
+    &self,
+    serializer_id: &SerializerId,
+    type_name: &str,
+  ) -> Result<Arc<dyn SerializerAny>, SerializerError> {
+    let serializer_id = self.resolve_serializer_id(serializer_id, type_name)?;
+    let key = SerializerKey::new(serializer_id, type_name);
+    match self.serializers.get(&key) {
+      Some(serializer) => Ok(serializer.value().clone()),
+      None => Err(SerializerError::SerializerNotFound {
+        serializer_id: key.serializer_id,
+        type_name: key.type_name,
+      }),
+    }
+  }
+
+  // Returns the id of the serializer actually used along with the bytes.
+  pub fn serialize_any(
+    &self,
+    msg: &dyn Any,
+    serializer_id: &SerializerId,
+    type_name: &str,
+  ) -> Result<(SerializerId, Vec<u8>), SerializerError> {
+    let serializer_id = self.resolve_serializer_id(serializer_id, type_name)?;
+    let bytes = self.find_serializer(&serializer_id, type_name)?.serialize_any(msg)?;
+    Ok((serializer_id, bytes))
+  }
+
+  pub fn deserialize_any(
+    &self,
+    bytes: &[u8],
+    serializer_id: &SerializerId,
+    type_name: &str,
+  ) -> Result<Arc<dyn Any + Send + Sync>, SerializerError> {
+    self.find_serializer(serializer_id, type_name)?.deserialize_any(bytes)
+  }
+
+  pub fn deserialize_message(
+    &self,
+    bytes: &[u8],
+    serializer_id: &SerializerId,
+    type_name: &str,
+  ) -> Result<Arc<dyn Message>, SerializerError> {
+    self
+      .find_serializer(serializer_id, type_name)?
+      .deserialize_message(bytes)
+  }
+
+  pub fn serialize<T: 'static>(&self, msg: &T, serializer_id: &SerializerId) -> Result<Vec<u8>, SerializerError> {
+    self
+      .serialize_any(msg, serializer_id, std::any::type_name::<T>())
+      .map(|(_, bytes)| bytes)
+  }
+
+  pub fn deserialize<T: Clone + Send + Sync + 'static>(
+    &self,
+    bytes: &[u8],
+    serializer_id: &SerializerId,
+  ) -> Result<T, SerializerError> {
+    let type_name = std::any::type_name::<T>();
+    let value = self.deserialize_any(bytes, serializer_id, type_name)?;
+    value
+      .downcast_ref::<T>()
+      .cloned()
+      .ok_or_else(|| SerializerError::DeserializationError(format!("expected {}", type_name)))
+  }
+}
+
+impl Debug for SerializerRegistry {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SerializerRegistry")
+      .field(
+        "serializers",
+        &self.serializers.iter().map(|kv| kv.key().clone()).collect::<Vec<_>>(),
+      )
+      .field(
+        "manifests",
+        &self
+          .manifests
+          .iter()
+          .map(|kv| kv.value().to_string())
+          .collect::<Vec<_>>(),
+      )
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{SerializerRegistration, SerializerRegistry};
+  use crate::serializer::{SerializerError, SerializerId};
+  use crate::type_manifest::TypeManifest;
+  use nexus_actor_core_rs::actor::message::Message;
+  use nexus_actor_message_derive_rs::{Message, SerializerRegistration};
+  use serde::{Deserialize, Serialize};
+
+  #[derive(Clone, PartialEq, Message, ::prost::Message, Serialize, Deserialize)]
+  pub struct TestMessage {
+    #[prost(int32, tag = "1")]
+    pub a: i32,
+    #[prost(string, tag = "2")]
+    pub b: String,
+  }
+
+  fn test_message() -> TestMessage {
+    TestMessage {
+      a: 42,
+      b: "world".to_string(),
+    }
+  }
+
+  #[derive(Clone, PartialEq, Message, ::prost::Message, Serialize, Deserialize, SerializerRegistration)]
+  #[serializer(json, proto, manifest = "registered", version = 2, alias = "old::Registered")]
+  pub struct RegisteredMessage {
+    #[prost(string, tag = "1")]
+    pub name: String,
+  }
+
+  #[test]
+  fn test_serializer_registration_derive() {
+    let registry = SerializerRegistry::new();
+    registry.register::<RegisteredMessage>().unwrap();
+    let type_name = std::any::type_name::<RegisteredMessage>();
+    assert_eq!(registry.get_default_serializer_id(type_name), Some(SerializerId::Json));
+    assert!(registry.find_serializer(&SerializerId::Proto, type_name).is_ok());
+    assert_eq!(registry.get_manifest(type_name), TypeManifest::new("registered", 2));
+    assert_eq!(registry.resolve_type_name("registered"), type_name);
+    assert_eq!(registry.resolve_type_name("old::Registered"), type_name);
+
+    let message = RegisteredMessage {
+      name: "world".to_string(),
+    };
+    let (serializer_id, bytes) = registry
+      .serialize_any(&message, &SerializerId::None, type_name)
+      .unwrap();
+    assert_eq!(
+      registry
+        .deserialize::<RegisteredMessage>(&bytes, &serializer_id)
+        .unwrap(),
+      message
+    );
+  }
+
+  #[test]
+  fn test_default_serializer_id_is_first_registered() {
+    let registry = SerializerRegistry::new();
+    registry.register_json::<TestMessage>().unwrap();
+    registry.register_proto::<TestMessage>().unwrap();
+    let type_name = std::any::type_name::<TestMessage>();
+    assert_eq!(registry.get_default_serializer_id(type_name), Some(SerializerId::Json));
+
+    let (serializer_id, bytes) = registry
+      .serialize_any(&test_message(), &SerializerId::None, type_name)
+      .unwrap();
+    assert_eq!(serializer_id, SerializerId::Json);
+    assert_eq!(
+      registry.deserialize::<TestMessage>(&bytes, &serializer_id).unwrap(),
+      test_message()
+    );
+
+    registry.register_type::<TestMessage>(SerializerId::Proto).unwrap();
+    let (serializer_id, bytes) = registry
+      .serialize_any(&test_message(), &SerializerId::None, type_name)
+      .unwrap();
+    assert_eq!(serializer_id, SerializerId::Proto);
+    let message = registry
+      .deserialize_message(&bytes, &SerializerId::Proto, type_name)
+      .unwrap();
+    assert!(message.eq_message(&test_message()));
+  }
+
+  #[test]
+  fn test_registries_are_independent() {
+    let registry = SerializerRegistry::new();
+    registry.register_proto::<TestMessage>().unwrap();
+    let other = SerializerRegistry::new();
+    let type_name = std::any::type_name::<TestMessage>();
+    assert_eq!(
+      other.serialize(&test_message(), &SerializerId::None),
+      Err(SerializerError::UnknownType(type_name.to_string()))
+    );
+    assert!(registry.serialize(&test_message(), &SerializerId::None).is_ok());
+  }
+
+  #[derive(Debug, Clone, PartialEq, Message, Serialize, Deserialize)]
+  struct OrderV1 {
+    id: u64,
+    amount: u64,
+  }
+
+  #[derive(Debug, Clone, PartialEq, Message, Serialize, Deserialize)]
+  struct OrderV2 {
+    id: u64,
+    amount: u64,
+    currency: String,
+  }
+
+  #[test]
+  fn test_alias_resolves_renamed_type() {
+    let registry = SerializerRegistry::new();
+    registry.register_json::<TestMessage>().unwrap();
+    registry.register_alias::<TestMessage>("legacy::Ping");
+    let bytes = registry.serialize(&test_message(), &SerializerId::Json).unwrap();
+    let message = registry
+      .deserialize_manifest(&bytes, &SerializerId::Json, "legacy::Ping")
+      .unwrap();
+    assert!(message.eq_message(&test_message()));
+    let message = registry
+      .deserialize_manifest(&bytes, &SerializerId::Json, std::any::type_name::<TestMessage>())
+      .unwrap();
+    assert!(message.eq_message(&test_message()));
+  }
+
+  #[test]
+  fn test_upcaster_converts_older_versions() {
+    let old_node = SerializerRegistry::new();
+    old_node.register_json::<OrderV1>().unwrap();
+    old_node.register_manifest::<OrderV1>("shop.Order", 1);
+    let manifest = old_node.get_manifest(std::any::type_name::<OrderV1>());
+    assert_eq!(manifest, TypeManifest::new("shop.Order", 1));
+    let bytes = old_node
+      .serialize(&OrderV1 { id: 7, amount: 100 }, &SerializerId::None)
+      .unwrap();
+
+    let new_node = SerializerRegistry::new();
+    new_node.register_json::<OrderV2>().unwrap();
+    new_node.register_json::<OrderV1>().unwrap();
+    new_node.register_manifest::<OrderV2>("shop.Order", 2);
+    new_node.register_upcaster_fn(TypeManifest::new("shop.Order", 1), |old: OrderV1| OrderV2 {
+      id: old.id,
+      amount: old.amount,
+      currency: "JPY".to_string(),
+    });
+    let expected = OrderV2 {
+      id: 7,
+      amount: 100,
+      currency: "JPY".to_string(),
+    };
+    let message = new_node
+      .deserialize_manifest(&bytes, &SerializerId::Json, &manifest.to_string())
+      .unwrap();
+    assert!(message.eq_message(&expected));
+
+    let bytes = new_node.serialize(&expected, &SerializerId::None).unwrap();
+    let manifest = new_node.get_manifest(std::any::type_name::<OrderV2>()).to_string();
+    assert_eq!(manifest, "shop.Order@2");
+    let message = new_node
+      .deserialize_manifest(&bytes, &SerializerId::Json, &manifest)
+      .unwrap();
+    assert!(message.eq_message(&expected));
+  }
+
+  #[test]
+  fn test_missing_serializer_errors() {
+    let registry = SerializerRegistry::new();
+    registry.register_proto::<TestMessage>().unwrap();
+    let type_name = std::any::type_name::<TestMessage>();
+    assert_eq!(
+      registry.register_type::<TestMessage>(SerializerId::Json),
+      Err(SerializerError::SerializerNotFound {
+        serializer_id: SerializerId::Json,
+        type_name: type_name.to_string(),
+      })
+    );
+    assert_eq!(
+      registry
+        .deserialize_any(&[], &SerializerId::None, "unknown::Type")
+        .err(),
+      Some(SerializerError::UnknownType("unknown::Type".to_string()))
+    );
+    assert_eq!(
+      registry
+        .register_serializer(
+          SerializerId::None,
+          crate::serializer::ProtoSerializer::<TestMessage>::default()
+        )
+        .err(),
+      Some(SerializerError::InvalidSerializerId(0))
+    );
+    assert_eq!(
+      registry.serialize(&"not registered".to_string(), &SerializerId::Proto),
+      Err(SerializerError::SerializerNotFound {
+        serializer_id: SerializerId::Proto,
+        type_name: std::any::type_name::<String>().to_string(),
+      })
+    );
+  }
+}
@@ -0,0 +1,87 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use thiserror::Error;
+use tonic::transport::server::Router;
+use tonic::transport::{Channel, Endpoint};
+
+use crate::config::Config;
+
+pub mod in_memory_transport;
+pub mod tcp_transport;
+#[cfg(unix)]
+pub mod uds_transport;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TransportError {
+  #[error("Invalid address: {0}")]
+  InvalidAddress(String),
+  #[error("Invalid TLS configuration: {0}")]
+  Tls(String),
+  #[error("Failed to bind: {0}")]
+  Bind(String),
+  #[error("Failed to connect: {0}")]
+  Connection(String),
+  #[error("Server error: {0}")]
+  Server(String),
+}
+
+pub type ShutdownSignal<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+// Carries the remoting gRPC service between nodes. The address returned by `local_address` is written into the
+// pids of this node, and is what peers pass to `connect` to reach it.
+#[async_trait]
+pub trait Transport: Debug + Send + Sync + 'static {
+  async fn local_address(&self, config: &Config) -> Result<String, TransportError>;
+
+  // Serves `router` until `signal` resolves.
+  async fn serve(&self, config: &Config, router: Router, signal: ShutdownSignal<'_>) -> Result<(), TransportError>;
+
+  async fn connect(&self, config: &Config, address: &str) -> Result<Channel, TransportError>;
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportHandle(Arc<dyn Transport>);
+
+impl TransportHandle {
+  pub fn new_arc(transport: Arc<dyn Transport>) -> Self {
+    TransportHandle(transport)
+  }
+
+  pub fn new(transport: impl Transport) -> Self {
+    TransportHandle(Arc::new(transport))
+  }
+}
+
+#[async_trait]
+impl Transport for TransportHandle {
+  async fn local_address(&self, config: &Config) -> Result<String, TransportError> {
+    self.0.local_address(config).await
+  }
+
+  async fn serve(&self, config: &Config, router: Router, signal: ShutdownSignal<'_>) -> Result<(), TransportError> {
+    self.0.serve(config, router, signal).await
+  }
+
+  async fn connect(&self, config: &Config, address: &str) -> Result<Channel, TransportError> {
+    self.0.connect(config, address).await
+  }
+}
+
+// Endpoint for transports that bring their own connector. The authority is never resolved; TLS, when configured,
+// is layered on top of the connection the connector returns.
+pub(crate) async fn connector_endpoint(config: &Config, address: &str) -> Result<Endpoint, TransportError> {
+  let tls_config = config.get_tls_config().await;
+  let scheme = if tls_config.is_some() { "https" } else { "http" };
+  let mut endpoint = Endpoint::from_shared(format!("{}://localhost", scheme))
+    .map_err(|e| TransportError::InvalidAddress(e.to_string()))?;
+  if let Some(tls_config) = tls_config {
+    endpoint = endpoint
+      .tls_config(tls_config.client_tls_config(address)?)
+      .map_err(|e| TransportError::Tls(e.to_string()))?;
+  }
+  Ok(endpoint)
+}
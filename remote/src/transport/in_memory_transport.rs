@@ -0,0 +1,280 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use dashmap::{DashMap, DashSet};
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf};
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
+use tonic::transport::server::Router;
+use tonic::transport::{Channel, Uri};
+use tower::service_fn;
+
+use crate::config::Config;
+use crate::transport::{connector_endpoint, ShutdownSignal, Transport, TransportError};
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+// Connects the nodes of a single process without sockets. Links between nodes can be slowed down or partitioned.
+#[derive(Debug, Clone)]
+pub struct InMemoryNetwork {
+  inner: Arc<InMemoryNetworkInner>,
+}
+
+#[derive(Debug)]
+struct InMemoryNetworkInner {
+  listeners: DashMap<String, mpsc::Sender<DuplexStream>>,
+  latencies: DashMap<(String, String), Duration>,
+  partitions: DashSet<(String, String)>,
+  // Bumped on every partition so that the links crossing it close their connections.
+  partition_changes: watch::Sender<u64>,
+}
+
+impl Default for InMemoryNetwork {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl InMemoryNetwork {
+  pub fn new() -> Self {
+    Self {
+      inner: Arc::new(InMemoryNetworkInner {
+        listeners: DashMap::new(),
+        latencies: DashMap::new(),
+        partitions: DashSet::new(),
+        partition_changes: watch::Sender::new(0),
+      }),
+    }
+  }
+
+  // Delays the bytes sent from `from` to `to`.
+  pub fn set_latency(&self, from: &str, to: &str, latency: Duration) {
+    self.inner.latencies.insert((from.to_string(), to.to_string()), latency);
+  }
+
+  pub fn clear_latency(&self, from: &str, to: &str) {
+    self.inner.latencies.remove(&(from.to_string(), to.to_string()));
+  }
+
+  // Closes the connections between `a` and `b` and refuses new ones until the partition is healed.
+  pub fn partition(&self, a: &str, b: &str) {
+    self.inner.partitions.insert(Self::link(a, b));
+    self.inner.partition_changes.send_modify(|changes| *changes += 1);
+  }
+
+  pub fn heal(&self, a: &str, b: &str) {
+    self.inner.partitions.remove(&Self::link(a, b));
+  }
+
+  pub fn is_partitioned(&self, a: &str, b: &str) -> bool {
+    self.inner.partitions.contains(&Self::link(a, b))
+  }
+
+  fn link(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+      (a.to_string(), b.to_string())
+    } else {
+      (b.to_string(), a.to_string())
+    }
+  }
+
+  fn latency(&self, from: &str, to: &str) -> Duration {
+    self
+      .inner
+      .latencies
+      .get(&(from.to_string(), to.to_string()))
+      .map_or(Duration::ZERO, |latency| *latency)
+  }
+
+  fn bind(&self, address: &str) -> Result<mpsc::Receiver<DuplexStream>, TransportError> {
+    let (tx, rx) = mpsc::channel(16);
+    match self.inner.listeners.entry(address.to_string()) {
+      dashmap::mapref::entry::Entry::Occupied(_) => {
+        Err(TransportError::Bind(format!("Address already in use: {}", address)))
+      }
+      dashmap::mapref::entry::Entry::Vacant(entry) => {
+        entry.insert(tx);
+        Ok(rx)
+      }
+    }
+  }
+
+  fn unbind(&self, address: &str) {
+    self.inner.listeners.remove(address);
+  }
+
+  async fn connect(&self, from: &str, to: &str) -> Result<DuplexStream, TransportError> {
+    if self.is_partitioned(from, to) {
+      return Err(TransportError::Connection(format!(
+        "{} is partitioned from {}",
+        from, to
+      )));
+    }
+    let listener = self
+      .inner
+      .listeners
+      .get(to)
+      .map(|listener| listener.clone())
+      .ok_or_else(|| TransportError::Connection(format!("No node is listening at {}", to)))?;
+    let (client, client_link) = tokio::io::duplex(BUFFER_SIZE);
+    let (server_link, server) = tokio::io::duplex(BUFFER_SIZE);
+    listener
+      .send(server)
+      .await
+      .map_err(|_| TransportError::Connection(format!("No node is listening at {}", to)))?;
+    let (client_reader, client_writer) = tokio::io::split(client_link);
+    let (server_reader, server_writer) = tokio::io::split(server_link);
+    tokio::spawn(
+      self
+        .clone()
+        .forward(from.to_string(), to.to_string(), client_reader, server_writer),
+    );
+    tokio::spawn(
+      self
+        .clone()
+        .forward(to.to_string(), from.to_string(), server_reader, client_writer),
+    );
+    Ok(client)
+  }
+
+  // Copies the bytes of one direction of a link, holding each chunk back for the latency of the link.
+  async fn forward(
+    self,
+    from: String,
+    to: String,
+    mut reader: ReadHalf<DuplexStream>,
+    mut writer: WriteHalf<DuplexStream>,
+  ) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+    let read = async {
+      let mut buf = vec![0u8; BUFFER_SIZE];
+      loop {
+        match reader.read(&mut buf).await {
+          Ok(0) | Err(_) => break,
+          Ok(n) => {
+            let deliver_at = Instant::now() + self.latency(&from, &to);
+            if tx.send((deliver_at, buf[..n].to_vec())).is_err() {
+              break;
+            }
+          }
+        }
+      }
+      drop(tx);
+    };
+    let write = async {
+      while let Some((deliver_at, chunk)) = rx.recv().await {
+        tokio::time::sleep_until(deliver_at).await;
+        if writer.write_all(&chunk).await.is_err() {
+          break;
+        }
+      }
+      let _ = writer.shutdown().await;
+    };
+    let mut partition_changes = self.inner.partition_changes.subscribe();
+    let partitioned = async {
+      while partition_changes.changed().await.is_ok() {
+        if self.is_partitioned(&from, &to) {
+          return;
+        }
+      }
+      std::future::pending::<()>().await
+    };
+    tokio::select! {
+      _ = async { tokio::join!(read, write) } => {}
+      _ = partitioned => {
+        tracing::debug!("InMemoryNetwork closed a partitioned link: from = {}, to = {}", from, to);
+      }
+    }
+  }
+}
+
+// Serves a node at `address` of an `InMemoryNetwork`. All nodes of the network must use it.
+#[derive(Debug, Clone)]
+pub struct InMemoryTransport {
+  network: InMemoryNetwork,
+  address: String,
+}
+
+impl InMemoryTransport {
+  pub fn new(network: &InMemoryNetwork, address: &str) -> Self {
+    Self {
+      network: network.clone(),
+      address: address.to_string(),
+    }
+  }
+
+  pub fn get_network(&self) -> &InMemoryNetwork {
+    &self.network
+  }
+}
+
+#[async_trait]
+impl Transport for InMemoryTransport {
+  async fn local_address(&self, _: &Config) -> Result<String, TransportError> {
+    Ok(self.address.clone())
+  }
+
+  async fn serve(&self, _: &Config, router: Router, signal: ShutdownSignal<'_>) -> Result<(), TransportError> {
+    let connections = self.network.bind(&self.address)?;
+    tracing::info!("Starting server: {}", self.address);
+    let incoming = futures::stream::unfold(connections, |mut connections| async move {
+      let connection = connections.recv().await?;
+      Some((Ok::<_, std::io::Error>(connection), connections))
+    });
+    let result = router
+      .serve_with_incoming_shutdown(Box::pin(incoming), signal)
+      .await
+      .map_err(|e| TransportError::Server(e.to_string()));
+    self.network.unbind(&self.address);
+    result
+  }
+
+  async fn connect(&self, config: &Config, address: &str) -> Result<Channel, TransportError> {
+    let network = self.network.clone();
+    let from = self.address.clone();
+    let to = address.to_string();
+    connector_endpoint(config, address)
+      .await?
+      .connect_with_connector(service_fn(move |_: Uri| {
+        let network = network.clone();
+        let from = from.clone();
+        let to = to.clone();
+        async move { network.connect(&from, &to).await.map(TokioIo::new) }
+      }))
+      .await
+      .map_err(|e| TransportError::Connection(e.to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::InMemoryNetwork;
+  use std::time::Duration;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+  #[tokio::test]
+  async fn test_link_latency_and_partition() {
+    let network = InMemoryNetwork::new();
+    let mut connections = network.bind("b").unwrap();
+    assert!(network.bind("b").is_err());
+
+    network.set_latency("a", "b", Duration::from_millis(200));
+    let mut client = network.connect("a", "b").await.unwrap();
+    let mut server = connections.recv().await.unwrap();
+    let started = tokio::time::Instant::now();
+    client.write_all(b"ping").await.unwrap();
+    let mut buf = [0u8; 4];
+    server.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    network.partition("b", "a");
+    assert_eq!(server.read(&mut buf).await.unwrap(), 0);
+    assert!(network.connect("a", "b").await.is_err());
+
+    network.heal("a", "b");
+    assert!(network.connect("a", "b").await.is_ok());
+  }
+}
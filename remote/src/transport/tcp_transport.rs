@@ -0,0 +1,68 @@
+use std::net::ToSocketAddrs;
+
+use async_trait::async_trait;
+use tonic::transport::server::Router;
+use tonic::transport::Channel;
+
+use crate::config::Config;
+use crate::transport::{ShutdownSignal, Transport, TransportError};
+
+// gRPC over TCP, bound to the configured host and port.
+#[derive(Debug, Clone, Default)]
+pub struct TcpTransport;
+
+impl TcpTransport {
+  async fn host_and_port(config: &Config) -> Result<(String, u16), TransportError> {
+    let host = match config.get_advertised_host().await {
+      Some(advertised_host) => advertised_host,
+      None => config
+        .get_host()
+        .await
+        .ok_or_else(|| TransportError::InvalidAddress("Host is not set".to_string()))?,
+    };
+    let port = config
+      .get_port()
+      .await
+      .ok_or_else(|| TransportError::InvalidAddress("Port is not set".to_string()))?;
+    Ok((host, port))
+  }
+}
+
+#[async_trait]
+impl Transport for TcpTransport {
+  async fn local_address(&self, config: &Config) -> Result<String, TransportError> {
+    let (host, port) = Self::host_and_port(config).await?;
+    Ok(format!("{}:{}", host, port))
+  }
+
+  async fn serve(&self, config: &Config, router: Router, signal: ShutdownSignal<'_>) -> Result<(), TransportError> {
+    let (host, port) = Self::host_and_port(config).await?;
+    tracing::debug!("Host: {}", host);
+    let socket_addr = (host.as_str(), port)
+      .to_socket_addrs()
+      .map_err(|e| TransportError::InvalidAddress(e.to_string()))?
+      .find(|addr| addr.is_ipv4())
+      .ok_or_else(|| TransportError::InvalidAddress(format!("Failed to resolve hostname: {}", host)))?;
+    tracing::info!("Starting server: {}", socket_addr);
+    router
+      .serve_with_shutdown(socket_addr, signal)
+      .await
+      .map_err(|e| TransportError::Server(e.to_string()))
+  }
+
+  async fn connect(&self, config: &Config, address: &str) -> Result<Channel, TransportError> {
+    let tls_config = config.get_tls_config().await;
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    let url = format!("{}://{}", scheme, address);
+    let mut endpoint = Channel::from_shared(url).map_err(|e| TransportError::InvalidAddress(e.to_string()))?;
+    if let Some(tls_config) = tls_config {
+      endpoint = endpoint
+        .tls_config(tls_config.client_tls_config(address)?)
+        .map_err(|e| TransportError::Tls(e.to_string()))?;
+    }
+    endpoint
+      .connect()
+      .await
+      .map_err(|e| TransportError::Connection(e.to_string()))
+  }
+}
@@ -0,0 +1,104 @@
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use hyper_util::rt::TokioIo;
+use tokio::net::{UnixListener, UnixStream};
+use tonic::transport::server::Router;
+use tonic::transport::{Channel, Uri};
+use tower::service_fn;
+
+use crate::config::Config;
+use crate::transport::{connector_endpoint, ShutdownSignal, Transport, TransportError};
+
+// gRPC over Unix domain sockets, e.g. between an application and its sidecar. The socket path is the address of
+// the node, so peers must use this transport as well.
+#[derive(Debug, Clone)]
+pub struct UdsTransport {
+  path: PathBuf,
+}
+
+impl UdsTransport {
+  pub fn new(path: impl Into<PathBuf>) -> Self {
+    Self { path: path.into() }
+  }
+
+  pub fn get_path(&self) -> &PathBuf {
+    &self.path
+  }
+}
+
+#[async_trait]
+impl Transport for UdsTransport {
+  async fn local_address(&self, _: &Config) -> Result<String, TransportError> {
+    Ok(self.path.to_string_lossy().into_owned())
+  }
+
+  async fn serve(&self, _: &Config, router: Router, signal: ShutdownSignal<'_>) -> Result<(), TransportError> {
+    remove_stale_socket(&self.path)?;
+    let listener = UnixListener::bind(&self.path).map_err(|e| TransportError::Bind(e.to_string()))?;
+    tracing::info!("Starting server: {}", self.path.display());
+    let incoming = futures::stream::unfold(listener, |listener| async move {
+      let stream = listener.accept().await.map(|(stream, _)| stream);
+      Some((stream, listener))
+    });
+    let result = router
+      .serve_with_incoming_shutdown(Box::pin(incoming), signal)
+      .await
+      .map_err(|e| TransportError::Server(e.to_string()));
+    let _ = std::fs::remove_file(&self.path);
+    result
+  }
+
+  async fn connect(&self, config: &Config, address: &str) -> Result<Channel, TransportError> {
+    let path = PathBuf::from(address);
+    connector_endpoint(config, address)
+      .await?
+      .connect_with_connector(service_fn(move |_: Uri| {
+        let path = path.clone();
+        async move { UnixStream::connect(path).await.map(TokioIo::new) }
+      }))
+      .await
+      .map_err(|e| TransportError::Connection(e.to_string()))
+  }
+}
+
+// A socket file left behind by a previous run would make bind fail. Anything else at the path is left alone.
+fn remove_stale_socket(path: &Path) -> Result<(), TransportError> {
+  match std::fs::symlink_metadata(path) {
+    Ok(metadata) if metadata.file_type().is_socket() => {
+      std::fs::remove_file(path).map_err(|e| TransportError::Bind(e.to_string()))
+    }
+    Ok(_) => Err(TransportError::Bind(format!(
+      "{} exists and is not a socket",
+      path.display()
+    ))),
+    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(TransportError::Bind(e.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::remove_stale_socket;
+  use crate::transport::TransportError;
+  use std::env;
+
+  #[test]
+  fn test_only_stale_sockets_are_removed() {
+    let dir = env::temp_dir();
+    let socket_path = dir.join(format!("nexus-uds-{}-stale.sock", std::process::id()));
+    let file_path = dir.join(format!("nexus-uds-{}-file", std::process::id()));
+    let _ = std::fs::remove_file(&socket_path);
+    drop(std::os::unix::net::UnixListener::bind(&socket_path).unwrap());
+    std::fs::write(&file_path, b"data").unwrap();
+
+    assert!(remove_stale_socket(&socket_path).is_ok());
+    assert!(!socket_path.exists());
+    assert!(remove_stale_socket(&socket_path).is_ok());
+
+    assert!(matches!(remove_stale_socket(&file_path), Err(TransportError::Bind(_))));
+    assert_eq!(std::fs::read(&file_path).unwrap(), b"data");
+    std::fs::remove_file(&file_path).unwrap();
+  }
+}
@@ -0,0 +1,108 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use nexus_actor_core_rs::actor::message::Message;
+
+use crate::serializer::{SerializerError, SerializerId};
+use crate::serializer_registry::SerializerRegistry;
+
+// Name and schema version a message type is sent under, written to `MessageBatch::type_names`.
+// Version 0 is written as the bare name so that unversioned peers keep interoperating.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeManifest {
+  pub name: String,
+  pub version: u32,
+}
+
+impl TypeManifest {
+  const VERSION_SEPARATOR: char = '@';
+
+  pub fn new(name: impl Into<String>, version: u32) -> Self {
+    Self {
+      name: name.into(),
+      version,
+    }
+  }
+
+  pub fn unversioned(name: impl Into<String>) -> Self {
+    Self::new(name, 0)
+  }
+
+  pub fn parse(manifest: &str) -> Self {
+    match manifest.rsplit_once(Self::VERSION_SEPARATOR) {
+      Some((name, version)) => match version.parse::<u32>() {
+        Ok(version) => Self::new(name, version),
+        Err(_) => Self::unversioned(manifest),
+      },
+      None => Self::unversioned(manifest),
+    }
+  }
+}
+
+impl Display for TypeManifest {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if self.version == 0 {
+      write!(f, "{}", self.name)
+    } else {
+      write!(f, "{}{}{}", self.name, Self::VERSION_SEPARATOR, self.version)
+    }
+  }
+}
+
+// Turns a payload written under an older manifest into the current message type.
+#[derive(Clone)]
+pub struct Upcaster(
+  Arc<
+    dyn Fn(&SerializerRegistry, &[u8], &SerializerId) -> Result<Arc<dyn Message>, SerializerError>
+      + Send
+      + Sync
+      + 'static,
+  >,
+);
+
+impl Upcaster {
+  pub fn new(
+    f: impl Fn(&SerializerRegistry, &[u8], &SerializerId) -> Result<Arc<dyn Message>, SerializerError>
+      + Send
+      + Sync
+      + 'static,
+  ) -> Self {
+    Self(Arc::new(f))
+  }
+
+  pub fn run(
+    &self,
+    registry: &SerializerRegistry,
+    bytes: &[u8],
+    serializer_id: &SerializerId,
+  ) -> Result<Arc<dyn Message>, SerializerError> {
+    (self.0)(registry, bytes, serializer_id)
+  }
+}
+
+impl Debug for Upcaster {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Upcaster").finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::TypeManifest;
+
+  #[test]
+  fn test_manifest_round_trip() {
+    for manifest in [
+      TypeManifest::unversioned("shop::Order"),
+      TypeManifest::new("shop::Order", 3),
+      TypeManifest::new("shop::Wrapper<shop::Order>", 1),
+    ] {
+      assert_eq!(TypeManifest::parse(&manifest.to_string()), manifest);
+    }
+    assert_eq!(TypeManifest::new("order", 0).to_string(), "order");
+    assert_eq!(
+      TypeManifest::parse("order@latest"),
+      TypeManifest::unversioned("order@latest")
+    );
+  }
+}
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use nexus_actor_core_rs::actor::actor::{ExtendedPid, Props};
+use nexus_actor_core_rs::actor::actor_system::ActorSystem;
+use nexus_actor_core_rs::actor::context::{BasePart, MessagePart, SenderPart, SpawnerPart};
+use nexus_actor_core_rs::actor::message::{Message, MessageHandle, ResponseHandle};
+use nexus_actor_message_derive_rs::Message;
+use nexus_actor_remote_rs::config::Config;
+use nexus_actor_remote_rs::config_option::ConfigOption;
+use nexus_actor_remote_rs::remote::Remote;
+use nexus_actor_remote_rs::FaultInjector;
+use nexus_actor_utils_rs::concurrent::WaitGroup;
+
+const SERVER_ADDRESS: &str = "127.0.0.1:8126";
+const CLIENT_ADDRESS: &str = "127.0.0.1:8127";
+
+#[derive(Clone, PartialEq, Message, ::prost::Message)]
+struct Ping {
+  #[prost(string, tag = "1")]
+  text: String,
+}
+
+async fn start_remote(system: &ActorSystem, port: u16, fault_injector: &FaultInjector) {
+  let wait_group = WaitGroup::with_count(1);
+  let config = Config::from([
+    ConfigOption::with_host("127.0.0.1"),
+    ConfigOption::with_port(port),
+    ConfigOption::with_fault_injector(fault_injector.clone()),
+  ])
+  .await;
+  let mut remote = Remote::new(system.clone(), config).await;
+  remote
+    .get_serializer_registry()
+    .register_proto::<Ping>()
+    .expect("Failed to register serializer");
+  let cloned_wait_group = wait_group.clone();
+  tokio::spawn(async move {
+    remote
+      .start_with_callback(|| async {
+        cloned_wait_group.done().await;
+      })
+      .await
+      .expect("Failed to start remote");
+  });
+  wait_group.wait().await;
+}
+
+async fn ping(system: &ActorSystem, pid: &ExtendedPid, text: &str, timeout: Duration) -> Option<String> {
+  let result = system
+    .get_root_context()
+    .await
+    .request_future(
+      pid.clone(),
+      MessageHandle::new(Ping { text: text.to_string() }),
+      timeout,
+    )
+    .await
+    .result()
+    .await;
+  result
+    .ok()
+    .and_then(|response| response.to_typed::<Ping>())
+    .map(|pong| pong.text)
+}
+
+#[tokio::test]
+async fn test_fault_injector_through_the_public_api() {
+  let fault_injector = FaultInjector::with_seed(7);
+  let server_system = ActorSystem::new().await.unwrap();
+  start_remote(&server_system, 8126, &fault_injector).await;
+  let pong_props = Props::from_async_actor_receiver(|ctx| async move {
+    if let Some(ping) = ctx.get_message_handle().await.to_typed::<Ping>() {
+      ctx
+        .respond(ResponseHandle::new(Ping {
+          text: format!("pong: {}", ping.text),
+        }))
+        .await;
+    }
+    Ok(())
+  })
+  .await;
+  let pid = server_system
+    .get_root_context()
+    .await
+    .spawn_named(pong_props, "pong")
+    .await
+    .unwrap();
+  let client_system = ActorSystem::new().await.unwrap();
+  start_remote(&client_system, 8127, &fault_injector).await;
+
+  assert_eq!(
+    ping(&client_system, &pid, "hello", Duration::from_secs(5)).await,
+    Some("pong: hello".to_string())
+  );
+
+  fault_injector.block(CLIENT_ADDRESS, SERVER_ADDRESS).await;
+  assert!(fault_injector.is_blocked(CLIENT_ADDRESS, SERVER_ADDRESS).await);
+  assert_eq!(
+    ping(&client_system, &pid, "blocked", Duration::from_millis(500)).await,
+    None
+  );
+  fault_injector.unblock(CLIENT_ADDRESS, SERVER_ADDRESS).await;
+
+  fault_injector.set_drop_ratio(SERVER_ADDRESS, CLIENT_ADDRESS, 1.0);
+  assert_eq!(
+    ping(&client_system, &pid, "dropped", Duration::from_millis(500)).await,
+    None
+  );
+
+  fault_injector.clear().await;
+  assert_eq!(
+    ping(&client_system, &pid, "healed", Duration::from_secs(5)).await,
+    Some("pong: healed".to_string())
+  );
+}
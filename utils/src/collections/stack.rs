@@ -33,6 +33,10 @@ impl<T> Stack<T> {
   pub fn clear(&mut self) {
     self.items.clear();
   }
+
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    self.items.iter().rev()
+  }
 }
 
 impl<T> Default for Stack<T> {